@@ -183,11 +183,11 @@ fi
 
 # Validate method
 case $METHOD in
-    "mid-price"|"volume-weighted"|"micro-price")
+    "mid-price"|"volume-weighted"|"micro-price"|"execution-vwap"|"book-pressure"|"geometric-mid"|"volume-weighted-decay"|"ensemble")
         ;;
     *)
         print_error "Invalid method: $METHOD"
-        print_error "Valid methods: mid-price, volume-weighted, micro-price"
+        print_error "Valid methods: mid-price, volume-weighted, micro-price, execution-vwap, book-pressure, geometric-mid, volume-weighted-decay, ensemble"
         exit 1
         ;;
 esac
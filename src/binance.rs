@@ -1,16 +1,330 @@
+use crate::config::{Config, DepthStreamMode};
+use crate::exchange::ExchangeConnector;
+use crate::order_book::{OrderBookSnapshot, OrderBookUpdate, PartialDepth};
+use crate::rate_limit::RateLimiter;
+use crate::trades::f64_from_str;
 use anyhow::{Result, anyhow};
-use reqwest::Client;
+use reqwest::{Client, Proxy, Response, StatusCode};
 use serde::{Deserialize, Serialize};
-use tracing::debug;
+use serde_json::value::RawValue;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::{debug, warn};
 
 /// Binance REST API client
 pub struct BinanceClient {
     client: Client,
+    market: Market,
     base_url: String,
+    ws_base_url: String,
+    rate_limiter: Arc<RateLimiter>,
+    retry_policy: RetryPolicy,
+    exchange_info: RwLock<Option<CachedExchangeInfo>>,
+    exchange_info_ttl: Duration,
+    depth_speed_ms: u32, // 100 or 1000
+}
+
+/// Parsed exchangeInfo symbols keyed by uppercase symbol
+struct CachedExchangeInfo {
+    fetched_at: Instant,
+    symbols: HashMap<String, SymbolInfo>,
+}
+
+/// Binance Spot Testnet REST API base URL
+pub const TESTNET_REST_URL: &str = "https://testnet.binance.vision";
+
+/// Binance Spot Testnet WebSocket base URL
+pub const TESTNET_WS_URL: &str = "wss://testnet.binance.vision/ws";
+
+/// Response header carrying the request weight used in the current minute
+const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-1m";
+
+/// Default lifetime of the cached exchangeInfo
+const DEFAULT_EXCHANGE_INFO_TTL: Duration = Duration::from_secs(3600);
+
+/// Binance API domain (regional deployment)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ApiDomain {
+    /// binance.com
+    #[default]
+    Global,
+    /// binance.us (spot only)
+    Us,
+}
+
+impl ApiDomain {
+    /// Spot REST API base URL on this domain
+    pub fn rest_base_url(&self) -> &'static str {
+        match self {
+            ApiDomain::Global => Market::Spot.rest_base_url(),
+            ApiDomain::Us => "https://api.binance.us",
+        }
+    }
+    
+    /// Spot WebSocket base URL on this domain
+    pub fn ws_base_url(&self) -> &'static str {
+        match self {
+            ApiDomain::Global => Market::Spot.ws_base_url(),
+            ApiDomain::Us => "wss://stream.binance.us:9443/ws",
+        }
+    }
+}
+
+impl std::fmt::Display for ApiDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiDomain::Global => write!(f, "binance.com"),
+            ApiDomain::Us => write!(f, "binance.us"),
+        }
+    }
+}
+
+/// Retry behaviour for REST requests
+///
+/// Connection errors, 5xx and 429 responses are retried with exponential
+/// backoff; other 4xx responses fail immediately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total attempts including the first request
+    pub max_attempts: u32,
+    /// Delay before the first retry (milliseconds)
+    pub initial_backoff_ms: u64,
+    /// Upper bound on the delay between retries (milliseconds)
+    pub max_backoff_ms: u64,
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff_ms: 0,
+            max_backoff_ms: 0,
+        }
+    }
+    
+    /// Delay before retry number `retry` (1-based)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u64 << retry.saturating_sub(1).min(16);
+        let delay_ms = self.initial_backoff_ms.saturating_mul(factor).min(self.max_backoff_ms);
+        Duration::from_millis(delay_ms)
+    }
+    
+    /// Whether a response status is worth retrying
+    pub fn is_retryable_status(status: StatusCode) -> bool {
+        status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+    }
+}
+
+/// HTTP client settings for REST requests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientOptions {
+    /// Proxy URL for all requests (e.g. `http://proxy.local:3128`)
+    pub proxy: Option<String>,
+    /// Time allowed to establish a TCP/TLS connection
+    pub connect_timeout: Duration,
+    /// Time allowed for a whole request, including the response body
+    pub request_timeout: Duration,
+}
+
+impl ClientOptions {
+    /// Build a `reqwest::Client` with these settings
+    fn build_client(&self) -> Result<Client> {
+        let mut builder = Client::builder()
+            .connect_timeout(self.connect_timeout)
+            .timeout(self.request_timeout);
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            connect_timeout: Duration::from_secs(5),
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 5_000,
+        }
+    }
+}
+
+/// Binance market the client talks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Market {
+    /// Spot market (api.binance.com)
+    #[default]
+    Spot,
+    /// USD-M futures (fapi.binance.com)
+    UsdFutures,
+}
+
+impl Market {
+    /// REST API base URL for this market
+    pub fn rest_base_url(&self) -> &'static str {
+        match self {
+            Market::Spot => "https://api.binance.com",
+            Market::UsdFutures => "https://fapi.binance.com",
+        }
+    }
+    
+    /// WebSocket base URL for this market
+    pub fn ws_base_url(&self) -> &'static str {
+        match self {
+            Market::Spot => "wss://stream.binance.com:9443/ws",
+            Market::UsdFutures => "wss://fstream.binance.com/ws",
+        }
+    }
+    
+    /// Market served by a known Binance REST or WebSocket host; `None` for custom hosts
+    ///
+    /// The testnet and regional domains are spot only.
+    pub fn for_host(url: &str) -> Option<Market> {
+        let url = url.trim_end_matches('/');
+        let spot_hosts = [
+            Market::Spot.rest_base_url(),
+            Market::Spot.ws_base_url(),
+            TESTNET_REST_URL,
+            TESTNET_WS_URL,
+            ApiDomain::Us.rest_base_url(),
+            ApiDomain::Us.ws_base_url(),
+        ];
+        let futures_hosts = [Market::UsdFutures.rest_base_url(), Market::UsdFutures.ws_base_url()];
+        
+        if spot_hosts.contains(&url) {
+            Some(Market::Spot)
+        } else if futures_hosts.contains(&url) {
+            Some(Market::UsdFutures)
+        } else {
+            None
+        }
+    }
+    
+    /// Path of the exchangeInfo endpoint
+    fn exchange_info_path(&self) -> &'static str {
+        match self {
+            Market::Spot => "/api/v3/exchangeInfo",
+            Market::UsdFutures => "/fapi/v1/exchangeInfo",
+        }
+    }
+    
+    /// Path of the order book depth endpoint
+    fn depth_path(&self) -> &'static str {
+        match self {
+            Market::Spot => "/api/v3/depth",
+            Market::UsdFutures => "/fapi/v1/depth",
+        }
+    }
+    
+    /// Path of the server time endpoint
+    fn time_path(&self) -> &'static str {
+        match self {
+            Market::Spot => "/api/v3/time",
+            Market::UsdFutures => "/fapi/v1/time",
+        }
+    }
+    
+    /// Path of the 24hr ticker statistics endpoint
+    fn ticker_24h_path(&self) -> &'static str {
+        match self {
+            Market::Spot => "/api/v3/ticker/24hr",
+            Market::UsdFutures => "/fapi/v1/ticker/24hr",
+        }
+    }
+    
+    /// Path of the kline/candlestick endpoint
+    fn klines_path(&self) -> &'static str {
+        match self {
+            Market::Spot => "/api/v3/klines",
+            Market::UsdFutures => "/fapi/v1/klines",
+        }
+    }
+    
+    /// Request weight of a klines request with the given limit
+    fn klines_weight(&self, limit: u32) -> u32 {
+        match self {
+            Market::Spot => 2,
+            Market::UsdFutures => match limit {
+                0..=99 => 1,
+                100..=499 => 2,
+                500..=1000 => 5,
+                _ => 10,
+            },
+        }
+    }
+    
+    /// Request weight of a single-symbol 24hr ticker
+    fn ticker_24h_weight(&self) -> u32 {
+        match self {
+            Market::Spot => 2,
+            Market::UsdFutures => 1,
+        }
+    }
+    
+    /// Request weight of the exchangeInfo endpoint
+    fn exchange_info_weight(&self) -> u32 {
+        match self {
+            Market::Spot => 20,
+            Market::UsdFutures => 1,
+        }
+    }
+    
+    /// Request weight of a depth snapshot with the given limit
+    fn depth_weight(&self, limit: u32) -> u32 {
+        match self {
+            Market::Spot => match limit {
+                0..=100 => 5,
+                101..=500 => 25,
+                501..=1000 => 50,
+                _ => 250,
+            },
+            Market::UsdFutures => match limit {
+                0..=50 => 2,
+                51..=100 => 5,
+                101..=500 => 10,
+                _ => 20,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Market {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Market::Spot => write!(f, "Spot"),
+            Market::UsdFutures => write!(f, "USD-M Futures"),
+        }
+    }
+}
+
+impl std::str::FromStr for Market {
+    type Err = anyhow::Error;
+    
+    /// Parse a CLI market name (`spot` or `futures`)
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "spot" => Ok(Market::Spot),
+            "futures" | "usd-futures" | "usdm" => Ok(Market::UsdFutures),
+            other => Err(anyhow!("Unknown market: {}", other)),
+        }
+    }
 }
 
 /// Symbol information from Binance API (simplified)
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SymbolInfo {
     pub symbol: String,
     #[serde(rename = "baseAsset")]
@@ -26,19 +340,134 @@ pub struct SymbolInfo {
     pub quote_precision: u32,
     #[serde(skip)]
     pub quantity_precision: u32,
-    #[serde(skip)]
+    #[serde(default)]
     pub filters: Vec<SymbolFilter>,
 }
 
+/// A listed symbol whose status is not `TRADING` (e.g. `BREAK` or `HALT`)
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Symbol {symbol} is not trading (status: {status})")]
+pub struct SymbolNotTrading {
+    pub symbol: String,
+    pub status: String,
+}
+
+impl SymbolInfo {
+    /// Whether the symbol is open for trading, so its streams carry live data
+    pub fn is_trading(&self) -> bool {
+        self.status == "TRADING"
+    }
+    
+    /// Minimum price increment from the `PRICE_FILTER`
+    pub fn tick_size(&self) -> Option<f64> {
+        self.filters
+            .iter()
+            .find(|filter| filter.filter_type == "PRICE_FILTER")
+            .and_then(|filter| filter.tick_size.as_deref())
+            .and_then(|tick_size| tick_size.parse::<f64>().ok())
+            .filter(|tick_size| *tick_size > 0.0)
+    }
+    
+    /// Decimal places needed to show a price without losing ticks
+    ///
+    /// Counts the significant decimals of `tickSize`, falling back to `price_precision`.
+    pub fn display_precision(&self) -> usize {
+        self.filters
+            .iter()
+            .find(|filter| filter.filter_type == "PRICE_FILTER")
+            .and_then(|filter| filter.tick_size.as_deref())
+            .filter(|tick_size| tick_size.parse::<f64>().is_ok_and(|tick| tick > 0.0))
+            .map(|tick_size| {
+                let trimmed = tick_size.trim_end_matches('0');
+                trimmed.split_once('.').map_or(0, |(_, decimals)| decimals.len())
+            })
+            .unwrap_or(self.price_precision as usize)
+    }
+}
+
 fn default_precision() -> u32 {
     8
 }
 
-/// Symbol filters (simplified - we'll skip these for now)
-#[derive(Debug, Deserialize, Serialize)]
+/// Symbol filters (simplified - only the price tick is parsed)
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SymbolFilter {
     #[serde(rename = "filterType")]
     pub filter_type: String,
+    #[serde(rename = "tickSize", default)]
+    pub tick_size: Option<String>,
+}
+
+/// Rolling 24hr price statistics for a symbol
+#[derive(Debug, Clone, Deserialize)]
+pub struct Ticker24h {
+    pub symbol: String,
+    #[serde(rename = "lastPrice", deserialize_with = "f64_from_str")]
+    pub last_price: f64,
+    #[serde(rename = "highPrice", deserialize_with = "f64_from_str")]
+    pub high_price: f64,
+    #[serde(rename = "lowPrice", deserialize_with = "f64_from_str")]
+    pub low_price: f64,
+    /// Base asset volume
+    #[serde(deserialize_with = "f64_from_str")]
+    pub volume: f64,
+    /// Quote asset volume
+    #[serde(rename = "quoteVolume", deserialize_with = "f64_from_str")]
+    pub quote_volume: f64,
+    #[serde(rename = "priceChangePercent", deserialize_with = "f64_from_str")]
+    pub price_change_percent: f64,
+}
+
+/// Candle intervals accepted by the klines endpoint
+pub const KLINE_INTERVALS: [&str; 15] = [
+    "1m", "3m", "5m", "15m", "30m", "1h", "2h", "4h", "6h", "8h", "12h", "1d", "3d", "1w", "1M",
+];
+
+/// One OHLCV candle from the klines endpoint
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Kline {
+    /// Open time (milliseconds)
+    pub open_time: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Base asset volume
+    pub volume: f64,
+    /// Close time (milliseconds)
+    pub close_time: u64,
+    /// Quote asset volume
+    pub quote_volume: f64,
+    pub trades: u64,
+}
+
+impl Kline {
+    /// Parse one row of the klines array-of-arrays response
+    fn from_row(row: &[serde_json::Value]) -> Result<Self> {
+        let integer = |index: usize| {
+            row.get(index)
+                .and_then(|value| value.as_u64())
+                .ok_or_else(|| anyhow!("Invalid kline field {}: {:?}", index, row.get(index)))
+        };
+        let decimal = |index: usize| {
+            row.get(index)
+                .and_then(|value| value.as_str())
+                .and_then(|value| value.parse::<f64>().ok())
+                .ok_or_else(|| anyhow!("Invalid kline field {}: {:?}", index, row.get(index)))
+        };
+        
+        Ok(Self {
+            open_time: integer(0)?,
+            open: decimal(1)?,
+            high: decimal(2)?,
+            low: decimal(3)?,
+            close: decimal(4)?,
+            volume: decimal(5)?,
+            close_time: integer(6)?,
+            quote_volume: decimal(7)?,
+            trades: integer(8)?,
+        })
+    }
 }
 
 /// Exchange information response
@@ -51,53 +480,331 @@ struct ExchangeInfo {
 #[derive(Debug, Serialize)]
 pub struct StreamConfig {
     pub method: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub params: Vec<String>,
     pub id: u64,
 }
 
+/// Frame from a combined-stream connection: a payload wrapped with its stream name
+#[derive(Debug, Deserialize)]
+pub struct CombinedStreamFrame<'a> {
+    pub stream: String,
+    #[serde(borrow)]
+    pub data: &'a RawValue,
+}
+
+impl CombinedStreamFrame<'_> {
+    /// Parse a frame, or `None` for a bare payload or subscription ack
+    pub fn parse(message: &str) -> Option<CombinedStreamFrame<'_>> {
+        serde_json::from_str(message).ok()
+    }
+    
+    /// Uppercase symbol the stream belongs to, e.g. `BTCUSDT` for `btcusdt@depth@100ms`
+    pub fn symbol(&self) -> String {
+        let name = self.stream.split('@').next().unwrap_or_default();
+        name.to_uppercase()
+    }
+}
+
 impl BinanceClient {
     /// Create a new Binance client
     pub fn new() -> Self {
+        Self::for_market(Market::Spot)
+    }
+    
+    /// Create a client for a specific market
+    pub fn for_market(market: Market) -> Self {
+        Self::with_base_urls(market, market.rest_base_url(), market.ws_base_url())
+    }
+    
+    /// Create a spot client for a regional API domain
+    pub fn for_domain(domain: ApiDomain) -> Self {
+        Self::with_base_urls(Market::Spot, domain.rest_base_url(), domain.ws_base_url())
+    }
+    
+    /// Create a client with custom REST and WebSocket hosts
+    pub fn with_base_urls(market: Market, base_url: &str, ws_base_url: &str) -> Self {
+        let client = ClientOptions::default().build_client().unwrap_or_else(|e| {
+            warn!("Failed to apply default HTTP client options: {}", e);
+            Client::new()
+        });
+        
         Self {
-            client: Client::new(),
-            base_url: "https://api.binance.com".to_string(),
+            client,
+            market,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            ws_base_url: ws_base_url.trim_end_matches('/').to_string(),
+            rate_limiter: Arc::new(RateLimiter::default()),
+            retry_policy: RetryPolicy::default(),
+            exchange_info: RwLock::new(None),
+            exchange_info_ttl: DEFAULT_EXCHANGE_INFO_TTL,
+            depth_speed_ms: 100,
+        }
+    }
+    
+    /// Set how long fetched exchangeInfo is served from cache
+    pub fn with_exchange_info_ttl(mut self, ttl: Duration) -> Self {
+        self.exchange_info_ttl = ttl;
+        self
+    }
+    
+    /// Use a proxy and/or custom timeouts for REST requests
+    pub fn with_options(mut self, options: ClientOptions) -> Result<Self> {
+        self.client = options.build_client()?;
+        Ok(self)
+    }
+    
+    /// Use a custom retry policy for REST requests
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+    
+    /// Use a custom rate limiter (e.g. one shared between clients on the same IP)
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+    
+    /// Stream depth at 100ms or 1000ms; other values use the 1000ms streams
+    pub fn with_depth_speed_ms(mut self, speed_ms: u32) -> Self {
+        self.depth_speed_ms = speed_ms;
+        self
+    }
+    
+    /// Create a client using the market, hosts and depth speed from the application config
+    pub fn from_config(config: &Config) -> Self {
+        Self::with_base_urls(
+            config.websocket.market,
+            &config.websocket.rest_base_url,
+            &config.websocket.base_url,
+        )
+        .with_depth_speed_ms(config.websocket.depth_stream_speed_ms)
+    }
+    
+    /// Create a client for the Binance Spot Testnet
+    pub fn testnet() -> Self {
+        Self::with_base_urls(Market::Spot, TESTNET_REST_URL, TESTNET_WS_URL)
+    }
+    
+    /// Get the market this client is configured for
+    pub fn market(&self) -> Market {
+        self.market
+    }
+    
+    /// Get the REST API base URL
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+    
+    /// Get the WebSocket base URL
+    pub fn ws_base_url(&self) -> &str {
+        &self.ws_base_url
+    }
+    
+    /// Get the request weight rate limiter
+    pub fn rate_limiter(&self) -> &Arc<RateLimiter> {
+        &self.rate_limiter
+    }
+    
+    /// Get the REST retry policy
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+    
+    /// Send a GET request, retrying transient failures per the retry policy
+    ///
+    /// Each attempt waits for the rate limiter to grant its weight.
+    async fn get(&self, url: &str, weight: u32) -> Result<Response> {
+        let max_attempts = self.retry_policy.max_attempts.max(1);
+        let mut attempt = 1;
+        
+        loop {
+            self.rate_limiter.acquire(weight).await;
+            
+            match self.client.get(url).send().await {
+                Ok(response) => {
+                    self.sync_used_weight(&response);
+                    
+                    let status = response.status();
+                    if !RetryPolicy::is_retryable_status(status) || attempt >= max_attempts {
+                        return Ok(response);
+                    }
+                    warn!("GET {} returned {} (attempt {}/{})", url, status, attempt, max_attempts);
+                }
+                Err(e) => {
+                    if attempt >= max_attempts {
+                        return Err(e.into());
+                    }
+                    warn!("GET {} failed: {} (attempt {}/{})", url, e, attempt, max_attempts);
+                }
+            }
+            
+            tokio::time::sleep(self.retry_policy.backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+    
+    /// Feed the server-reported used weight back into the rate limiter
+    fn sync_used_weight(&self, response: &Response) {
+        if let Some(used_weight) = response
+            .headers()
+            .get(USED_WEIGHT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u32>().ok())
+        {
+            debug!("Used request weight: {}", used_weight);
+            self.rate_limiter.sync_used_weight(used_weight);
         }
     }
     
     /// Get symbol information
+    ///
+    /// Served from the cached exchangeInfo, which is fetched on first use
+    /// and again once it is older than the configured TTL.
     pub async fn get_symbol_info(&self, symbol: &str) -> Result<SymbolInfo> {
-        let url = format!("{}/api/v3/exchangeInfo", self.base_url);
+        if !self.exchange_info_fresh() {
+            self.refresh_exchange_info().await?;
+        }
+        
+        let cache = self.exchange_info.read().unwrap();
+        cache
+            .as_ref()
+            .and_then(|cached| cached.symbols.get(&symbol.to_uppercase()))
+            .cloned()
+            .ok_or_else(|| anyhow!("Symbol {} not found", symbol))
+    }
+    
+    /// Get symbol information, failing with `SymbolNotTrading` unless the symbol is trading
+    pub async fn verify_tradeable(&self, symbol: &str) -> Result<SymbolInfo> {
+        let info = self.get_symbol_info(symbol).await?;
+        if !info.is_trading() {
+            return Err(SymbolNotTrading {
+                symbol: info.symbol,
+                status: info.status,
+            }
+            .into());
+        }
+        Ok(info)
+    }
+    
+    /// Get information for several symbols from one exchangeInfo download
+    ///
+    /// Keys are the uppercase symbols. Fails, naming every missing symbol,
+    /// if any is not listed.
+    pub async fn get_symbols_info(&self, symbols: &[&str]) -> Result<HashMap<String, SymbolInfo>> {
+        if !self.exchange_info_fresh() {
+            self.refresh_exchange_info().await?;
+        }
+        
+        let cache = self.exchange_info.read().unwrap();
+        let listed = cache.as_ref().map(|cached| &cached.symbols);
+        let mut found = HashMap::with_capacity(symbols.len());
+        let mut missing = Vec::new();
+        for symbol in symbols {
+            let symbol = symbol.to_uppercase();
+            match listed.and_then(|listed| listed.get(&symbol)) {
+                Some(info) => {
+                    found.insert(symbol, info.clone());
+                }
+                None => missing.push(symbol),
+            }
+        }
+        
+        if !missing.is_empty() {
+            return Err(anyhow!("Symbols not found: {}", missing.join(", ")));
+        }
+        Ok(found)
+    }
+    
+    /// Fetch exchangeInfo and replace the cached symbol list
+    pub async fn refresh_exchange_info(&self) -> Result<()> {
+        let url = format!("{}{}", self.base_url, self.market.exchange_info_path());
         
         debug!("Fetching exchange info from: {}", url);
         
-        let response = self.client
-            .get(&url)
-            .send()
-            .await?;
+        let response = self.get(&url, self.market.exchange_info_weight()).await?;
             
         if !response.status().is_success() {
             return Err(anyhow!("API request failed with status: {}", response.status()));
         }
         
         let exchange_info: ExchangeInfo = response.json().await?;
-        
-        let symbol_info = exchange_info
+        let symbols = exchange_info
             .symbols
             .into_iter()
-            .find(|s| s.symbol.to_uppercase() == symbol.to_uppercase())
-            .ok_or_else(|| anyhow!("Symbol {} not found", symbol))?;
-            
-        Ok(symbol_info)
+            .map(|info| (info.symbol.to_uppercase(), info))
+            .collect::<HashMap<_, _>>();
+        
+        debug!("Cached exchange info for {} symbols", symbols.len());
+        
+        *self.exchange_info.write().unwrap() = Some(CachedExchangeInfo {
+            fetched_at: Instant::now(),
+            symbols,
+        });
+        
+        Ok(())
+    }
+    
+    /// Check if cached exchangeInfo exists and is within its TTL
+    fn exchange_info_fresh(&self) -> bool {
+        self.exchange_info
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|cached| cached.fetched_at.elapsed() < self.exchange_info_ttl)
+            .unwrap_or(false)
+    }
+    
+    /// Get rolling 24hr statistics for a symbol
+    pub async fn get_24h_ticker(&self, symbol: &str) -> Result<Ticker24h> {
+        let url = format!(
+            "{}{}?symbol={}",
+            self.base_url,
+            self.market.ticker_24h_path(),
+            symbol.to_uppercase()
+        );
+        
+        let response = self.get(&url, self.market.ticker_24h_weight()).await?;
+        
+        if !response.status().is_success() {
+            return Err(anyhow!("24hr ticker request failed: {}", response.status()));
+        }
+        
+        let ticker: Ticker24h = response.json().await?;
+        Ok(ticker)
+    }
+    
+    /// Get the most recent `limit` candles of `interval` (one of `KLINE_INTERVALS`), oldest first
+    pub async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> Result<Vec<Kline>> {
+        if !KLINE_INTERVALS.contains(&interval) {
+            return Err(anyhow!("Unsupported kline interval: {}", interval));
+        }
+        
+        let url = format!(
+            "{}{}?symbol={}&interval={}&limit={}",
+            self.base_url,
+            self.market.klines_path(),
+            symbol.to_uppercase(),
+            interval,
+            limit
+        );
+        
+        let response = self.get(&url, self.market.klines_weight(limit)).await?;
+        
+        if !response.status().is_success() {
+            return Err(anyhow!("Klines request failed: {}", response.status()));
+        }
+        
+        let rows: Vec<Vec<serde_json::Value>> = response.json().await?;
+        rows.iter().map(|row| Kline::from_row(row)).collect()
     }
     
     /// Get current server time (for connection testing)
     pub async fn get_server_time(&self) -> Result<u64> {
-        let url = format!("{}/api/v3/time", self.base_url);
+        let url = format!("{}{}", self.base_url, self.market.time_path());
         
-        let response = self.client
-            .get(&url)
-            .send()
-            .await?;
+        let response = self.get(&url, 1).await?;
             
         if !response.status().is_success() {
             return Err(anyhow!("Server time request failed: {}", response.status()));
@@ -111,16 +818,121 @@ impl BinanceClient {
         Ok(server_time)
     }
     
+    /// Get an order book depth snapshot
+    pub async fn get_depth_snapshot(&self, symbol: &str, limit: u32) -> Result<OrderBookSnapshot> {
+        let url = self.depth_snapshot_url(symbol, limit);
+        
+        debug!("Fetching depth snapshot from: {}", url);
+        
+        let response = self.get(&url, self.market.depth_weight(limit)).await?;
+            
+        if !response.status().is_success() {
+            return Err(anyhow!("Failed to fetch order book snapshot: {}", response.status()));
+        }
+        
+        let snapshot: OrderBookSnapshot = response.json().await?;
+        
+        Ok(snapshot)
+    }
+    
+    /// Generate REST URL for an order book depth snapshot
+    pub fn depth_snapshot_url(&self, symbol: &str, limit: u32) -> String {
+        format!(
+            "{}{}?symbol={}&limit={}",
+            self.base_url,
+            self.market.depth_path(),
+            symbol.to_uppercase(),
+            limit
+        )
+    }
+    
     /// Generate WebSocket stream URL for order book
     pub fn get_orderbook_stream_url(&self, symbol: &str) -> String {
         let stream_name = format!("{}@depth", symbol.to_lowercase());
-        format!("wss://stream.binance.com:9443/ws/{}", stream_name)
+        format!("{}/{}", self.ws_base_url, stream_name)
     }
     
-    /// Generate WebSocket stream URL for order book updates (faster)
+    /// Generate WebSocket stream URL for order book updates at the client's depth speed
     pub fn get_orderbook_diff_stream_url(&self, symbol: &str) -> String {
-        let stream_name = format!("{}@depth@100ms", symbol.to_lowercase());
-        format!("wss://stream.binance.com:9443/ws/{}", stream_name)
+        format!(
+            "{}/{}",
+            self.ws_base_url,
+            Self::diff_depth_stream_name(symbol, self.depth_speed_ms)
+        )
+    }
+    
+    /// Generate WebSocket stream URL for partial-book depth
+    ///
+    /// `levels` must be 5, 10 or 20; `speed_ms` is 100 or 1000.
+    pub fn get_partial_depth_stream_url(&self, symbol: &str, levels: u32, speed_ms: u32) -> String {
+        format!(
+            "{}/{}",
+            self.ws_base_url,
+            Self::partial_depth_stream_name(symbol, levels, speed_ms)
+        )
+    }
+    
+    /// Stream name for diff depth updates; `speed_ms` is 100 or 1000
+    pub fn diff_depth_stream_name(symbol: &str, speed_ms: u32) -> String {
+        if speed_ms == 100 {
+            format!("{}@depth@100ms", symbol.to_lowercase())
+        } else {
+            format!("{}@depth", symbol.to_lowercase())
+        }
+    }
+    
+    /// Stream name for partial-book depth
+    pub fn partial_depth_stream_name(symbol: &str, levels: u32, speed_ms: u32) -> String {
+        if speed_ms == 100 {
+            format!("{}@depth{}@100ms", symbol.to_lowercase(), levels)
+        } else {
+            format!("{}@depth{}", symbol.to_lowercase(), levels)
+        }
+    }
+    
+    /// Generate WebSocket stream URL for aggregated trades
+    pub fn get_agg_trade_stream_url(&self, symbol: &str) -> String {
+        format!("{}/{}", self.ws_base_url, Self::agg_trade_stream_name(symbol))
+    }
+    
+    /// Stream name for aggregated trades
+    pub fn agg_trade_stream_name(symbol: &str) -> String {
+        format!("{}@aggTrade", symbol.to_lowercase())
+    }
+    
+    /// Generate WebSocket URL carrying several streams on one connection
+    ///
+    /// Each frame arrives wrapped as `{"stream":..,"data":..}`.
+    pub fn get_combined_stream_url(&self, streams: &[String]) -> String {
+        let base_url = self.ws_base_url.strip_suffix("/ws").unwrap_or(&self.ws_base_url);
+        format!("{}/stream?streams={}", base_url, streams.join("/"))
+    }
+    
+    /// Build a SUBSCRIBE request for additional streams on an open connection
+    pub fn subscribe_request(streams: Vec<String>, id: u64) -> StreamConfig {
+        StreamConfig {
+            method: "SUBSCRIBE".to_string(),
+            params: streams,
+            id,
+        }
+    }
+    
+    /// Build an UNSUBSCRIBE request for streams on an open connection
+    pub fn unsubscribe_request(streams: Vec<String>, id: u64) -> StreamConfig {
+        StreamConfig {
+            method: "UNSUBSCRIBE".to_string(),
+            params: streams,
+            id,
+        }
+    }
+    
+    /// Build a LIST_SUBSCRIPTIONS request, used as an application-level keepalive
+    pub fn list_subscriptions_request(id: u64) -> StreamConfig {
+        StreamConfig {
+            method: "LIST_SUBSCRIPTIONS".to_string(),
+            params: Vec::new(),
+            id,
+        }
     }
 }
 
@@ -130,9 +942,327 @@ impl Default for BinanceClient {
     }
 }
 
+impl ExchangeConnector for BinanceClient {
+    fn snapshot_url(&self, symbol: &str, limit: u32) -> String {
+        self.depth_snapshot_url(symbol, limit)
+    }
+    
+    fn diff_stream_url(&self, symbol: &str) -> String {
+        self.get_orderbook_diff_stream_url(symbol)
+    }
+    
+    fn parse_depth_message(&self, message: &str) -> Result<OrderBookUpdate> {
+        Ok(serde_json::from_str(message)?)
+    }
+    
+    async fn fetch_snapshot(&self, symbol: &str, limit: u32) -> Result<OrderBookSnapshot> {
+        self.get_depth_snapshot(symbol, limit).await
+    }
+    
+    async fn ping(&self) -> Result<()> {
+        self.get_server_time().await.map(|_| ())
+    }
+    
+    fn partial_stream_url(&self, symbol: &str, levels: u32) -> Option<String> {
+        Some(self.get_partial_depth_stream_url(symbol, levels, self.depth_speed_ms))
+    }
+    
+    fn parse_partial_depth_message(&self, message: &str) -> Result<PartialDepth> {
+        match self.market {
+            Market::Spot => Ok(serde_json::from_str(message)?),
+            // Futures frames carry `U`/`u`/`pu` like a diff instead of `lastUpdateId`
+            Market::UsdFutures => Ok(serde_json::from_str::<OrderBookUpdate>(message)?.into()),
+        }
+    }
+    
+    fn depth_stream_name(&self, symbol: &str, depth_stream: DepthStreamMode) -> Option<String> {
+        Some(match depth_stream {
+            DepthStreamMode::Diff => Self::diff_depth_stream_name(symbol, self.depth_speed_ms),
+            DepthStreamMode::Partial { levels } => Self::partial_depth_stream_name(symbol, levels, self.depth_speed_ms),
+        })
+    }
+    
+    fn trade_stream_name(&self, symbol: &str) -> Option<String> {
+        Some(Self::agg_trade_stream_name(symbol))
+    }
+    
+    fn combined_stream_url(&self, streams: &[String]) -> Option<String> {
+        Some(self.get_combined_stream_url(streams))
+    }
+    
+    fn unwrap_combined_frame<'a>(&self, message: &'a str) -> Option<(String, &'a str)> {
+        let frame = CombinedStreamFrame::parse(message)?;
+        Some((frame.symbol(), frame.data.get()))
+    }
+    
+    fn subscription_frame(&self, subscribe: bool, streams: Vec<String>, id: u64) -> Option<String> {
+        let request = if subscribe {
+            Self::subscribe_request(streams, id)
+        } else {
+            Self::unsubscribe_request(streams, id)
+        };
+        serde_json::to_string(&request).ok()
+    }
+    
+    fn keepalive_frame(&self, id: u64) -> Option<String> {
+        serde_json::to_string(&Self::list_subscriptions_request(id)).ok()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    
+    /// Serve one canned HTTP response per connection, in order
+    async fn mock_server(responses: Vec<(u16, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        tokio::spawn(async move {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buffer = [0u8; 1024];
+                let _ = stream.read(&mut buffer).await;
+                
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+        
+        format!("http://{}", addr)
+    }
+    
+    #[tokio::test]
+    async fn test_request_timeout_fails_fast() {
+        // Accepts connections but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+        
+        let options = ClientOptions {
+            request_timeout: Duration::from_millis(200),
+            ..ClientOptions::default()
+        };
+        let client = BinanceClient::with_base_urls(Market::Spot, &format!("http://{}", addr), "ws://unused")
+            .with_retry_policy(RetryPolicy::none())
+            .with_options(options)
+            .unwrap();
+        
+        let start = Instant::now();
+        assert!(client.get_server_time().await.is_err());
+        assert!(start.elapsed() < Duration::from_secs(2));
+        
+        // Unroutable address: the connect timeout bounds the wait
+        let options = ClientOptions {
+            connect_timeout: Duration::from_millis(200),
+            ..ClientOptions::default()
+        };
+        let client = BinanceClient::with_base_urls(Market::Spot, "http://10.255.255.1", "ws://unused")
+            .with_retry_policy(RetryPolicy::none())
+            .with_options(options)
+            .unwrap();
+        
+        let start = Instant::now();
+        assert!(client.get_server_time().await.is_err());
+        assert!(start.elapsed() < Duration::from_secs(2));
+        
+        let bad_proxy = ClientOptions {
+            proxy: Some("not a url".to_string()),
+            ..ClientOptions::default()
+        };
+        assert!(BinanceClient::new().with_options(bad_proxy).is_err());
+    }
+    
+    fn fast_retries() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff_ms: 10,
+            max_backoff_ms: 50,
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_retry_on_server_error() {
+        let base_url = mock_server(vec![
+            (503, "{}"),
+            (503, "{}"),
+            (200, r#"{"serverTime": 1700000000000}"#),
+        ])
+        .await;
+        
+        let client = BinanceClient::with_base_urls(Market::Spot, &base_url, "ws://unused")
+            .with_retry_policy(fast_retries());
+        
+        assert_eq!(client.get_server_time().await.unwrap(), 1700000000000);
+    }
+    
+    #[tokio::test]
+    async fn test_no_retry_on_client_error() {
+        let base_url = mock_server(vec![
+            (400, r#"{"code": -1121, "msg": "Invalid symbol."}"#),
+            (200, r#"{"serverTime": 1700000000000}"#),
+        ])
+        .await;
+        
+        let client = BinanceClient::with_base_urls(Market::Spot, &base_url, "ws://unused")
+            .with_retry_policy(fast_retries());
+        
+        assert!(client.get_server_time().await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_exchange_info_cached() {
+        let base_url = mock_server(vec![
+            (200, r#"{"symbols": [{"symbol": "BTCUSDT", "baseAsset": "BTC", "quoteAsset": "USDT", "status": "TRADING",
+                "filters": [{"filterType": "PRICE_FILTER", "minPrice": "0.01", "tickSize": "0.01000000"}, {"filterType": "LOT_SIZE"}]}]}"#),
+            (200, r#"{"symbols": [{"symbol": "ETHUSDT", "baseAsset": "ETH", "quoteAsset": "USDT", "status": "TRADING"}]}"#),
+        ])
+        .await;
+        
+        let client = BinanceClient::with_base_urls(Market::Spot, &base_url, "ws://unused")
+            .with_retry_policy(RetryPolicy::none());
+        
+        let info = client.get_symbol_info("BTCUSDT").await.unwrap();
+        assert_eq!(info.base_asset, "BTC");
+        assert_eq!(info.tick_size(), Some(0.01));
+        assert_eq!(info.display_precision(), 2);
+        
+        // Served from cache: the second response is not fetched
+        assert_eq!(client.get_symbol_info("btcusdt").await.unwrap().base_asset, "BTC");
+        assert!(client.get_symbol_info("ETHUSDT").await.is_err());
+        
+        client.refresh_exchange_info().await.unwrap();
+        assert_eq!(client.get_symbol_info("ETHUSDT").await.unwrap().base_asset, "ETH");
+        assert!(client.get_symbol_info("BTCUSDT").await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_get_symbols_info() {
+        let base_url = mock_server(vec![
+            (200, r#"{"symbols": [
+                {"symbol": "BTCUSDT", "baseAsset": "BTC", "quoteAsset": "USDT", "status": "TRADING"},
+                {"symbol": "ETHUSDT", "baseAsset": "ETH", "quoteAsset": "USDT", "status": "TRADING"},
+                {"symbol": "BNBUSDT", "baseAsset": "BNB", "quoteAsset": "USDT", "status": "TRADING"}]}"#),
+        ])
+        .await;
+        let client = BinanceClient::with_base_urls(Market::Spot, &base_url, "ws://unused")
+            .with_retry_policy(RetryPolicy::none());
+        
+        let infos = client.get_symbols_info(&["BTCUSDT", "ethusdt"]).await.unwrap();
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos["BTCUSDT"].base_asset, "BTC");
+        assert_eq!(infos["ETHUSDT"].base_asset, "ETH");
+        
+        // Served from the same download; every missing symbol is named
+        let err = client.get_symbols_info(&["BTCUSDT", "XRPUSDT", "SOLUSDT"]).await.unwrap_err();
+        assert_eq!(err.to_string(), "Symbols not found: XRPUSDT, SOLUSDT");
+    }
+    
+    #[tokio::test]
+    async fn test_verify_tradeable() {
+        let base_url = mock_server(vec![
+            (200, r#"{"symbols": [
+                {"symbol": "BTCUSDT", "baseAsset": "BTC", "quoteAsset": "USDT", "status": "TRADING"},
+                {"symbol": "LUNAUSDT", "baseAsset": "LUNA", "quoteAsset": "USDT", "status": "HALT"}]}"#),
+        ])
+        .await;
+        let client = BinanceClient::with_base_urls(Market::Spot, &base_url, "ws://unused")
+            .with_retry_policy(RetryPolicy::none());
+        
+        assert!(client.verify_tradeable("btcusdt").await.unwrap().is_trading());
+        
+        // Still listed, so plain lookups succeed
+        assert!(!client.get_symbol_info("LUNAUSDT").await.unwrap().is_trading());
+        let err = client.verify_tradeable("LUNAUSDT").await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SymbolNotTrading>(),
+            Some(&SymbolNotTrading { symbol: "LUNAUSDT".to_string(), status: "HALT".to_string() })
+        );
+        assert_eq!(err.to_string(), "Symbol LUNAUSDT is not trading (status: HALT)");
+        
+        // Unlisted symbols are a lookup failure, not a status problem
+        let err = client.verify_tradeable("XRPUSDT").await.unwrap_err();
+        assert!(err.downcast_ref::<SymbolNotTrading>().is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_get_24h_ticker() {
+        let base_url = mock_server(vec![
+            (200, r#"{"symbol": "BTCUSDT", "priceChange": "-94.99999800", "priceChangePercent": "-95.960",
+                "weightedAvgPrice": "0.29628482", "prevClosePrice": "0.10002000", "lastPrice": "4.00000200",
+                "lastQty": "200.00000000", "bidPrice": "4.00000000", "askPrice": "4.00000200", "openPrice": "99.00000000",
+                "highPrice": "100.00000000", "lowPrice": "0.10000000", "volume": "8913.30000000",
+                "quoteVolume": "15.30000000", "openTime": 1499783499040, "closeTime": 1499869899040,
+                "firstId": 28385, "lastId": 28460, "count": 76}"#),
+        ])
+        .await;
+        
+        let client = BinanceClient::with_base_urls(Market::Spot, &base_url, "ws://unused")
+            .with_retry_policy(RetryPolicy::none());
+        let ticker = client.get_24h_ticker("btcusdt").await.unwrap();
+        
+        assert_eq!(ticker.symbol, "BTCUSDT");
+        assert_eq!(ticker.last_price, 4.000002);
+        assert_eq!(ticker.high_price, 100.0);
+        assert_eq!(ticker.low_price, 0.1);
+        assert_eq!(ticker.volume, 8913.3);
+        assert_eq!(ticker.quote_volume, 15.3);
+        assert_eq!(ticker.price_change_percent, -95.96);
+    }
+    
+    #[tokio::test]
+    async fn test_get_klines() {
+        let base_url = mock_server(vec![
+            (200, r#"[
+                [1499040000000, "0.01634790", "0.80000000", "0.01575800", "0.01577100", "148976.11427815",
+                 1499644799999, "2434.19055334", 308, "1756.87402397", "28.46694368", "0"],
+                [1499644800000, "0.01577100", "0.01600000", "0.01550000", "0.01590000", "1000.00000000",
+                 1500249599999, "15.80000000", 12, "500.00000000", "7.90000000", "0"]]"#),
+            (200, r#"[[1499040000000, "not a price"]]"#),
+        ])
+        .await;
+        let client = BinanceClient::with_base_urls(Market::Spot, &base_url, "ws://unused")
+            .with_retry_policy(RetryPolicy::none());
+        
+        let klines = client.get_klines("btcusdt", "1w", 2).await.unwrap();
+        assert_eq!(klines.len(), 2);
+        assert_eq!(klines[0], Kline {
+            open_time: 1499040000000,
+            open: 0.0163479,
+            high: 0.8,
+            low: 0.015758,
+            close: 0.015771,
+            volume: 148976.11427815,
+            close_time: 1499644799999,
+            quote_volume: 2434.19055334,
+            trades: 308,
+        });
+        assert_eq!(klines[1].close, 0.0159);
+        
+        assert!(client.get_klines("BTCUSDT", "1w", 1).await.is_err());
+        assert!(client.get_klines("BTCUSDT", "7m", 1).await.unwrap_err().to_string().contains("7m"));
+    }
+    
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_millis(1_000));
+        assert_eq!(policy.backoff(10), Duration::from_millis(5_000));
+    }
     
     #[tokio::test]
     async fn test_get_symbol_info() {
@@ -165,5 +1295,196 @@ mod tests {
         
         let diff_url = client.get_orderbook_diff_stream_url("BTCUSDT");
         assert!(diff_url.contains("btcusdt@depth@100ms"));
+        
+        let partial_url = client.get_partial_depth_stream_url("BTCUSDT", 20, 100);
+        assert_eq!(partial_url, "wss://stream.binance.com:9443/ws/btcusdt@depth20@100ms");
+        
+        let partial_url = client.get_partial_depth_stream_url("BTCUSDT", 5, 1000);
+        assert_eq!(partial_url, "wss://stream.binance.com:9443/ws/btcusdt@depth5");
+        
+        let trade_url = client.get_agg_trade_stream_url("BTCUSDT");
+        assert_eq!(trade_url, "wss://stream.binance.com:9443/ws/btcusdt@aggTrade");
+        
+        let subscribe = BinanceClient::subscribe_request(vec!["btcusdt@aggTrade".to_string()], 1);
+        assert_eq!(
+            serde_json::to_string(&subscribe).unwrap(),
+            r#"{"method":"SUBSCRIBE","params":["btcusdt@aggTrade"],"id":1}"#
+        );
+    }
+    
+    #[test]
+    fn test_combined_streams() {
+        let client = BinanceClient::new();
+        let streams = vec!["btcusdt@depth@100ms".to_string(), "ethusdt@depth@100ms".to_string()];
+        assert_eq!(
+            client.get_combined_stream_url(&streams),
+            "wss://stream.binance.com:9443/stream?streams=btcusdt@depth@100ms/ethusdt@depth@100ms"
+        );
+        let futures = BinanceClient::for_market(Market::UsdFutures);
+        assert_eq!(
+            futures.combined_stream_url(&streams[1..]).unwrap(),
+            "wss://fstream.binance.com/stream?streams=ethusdt@depth@100ms"
+        );
+        assert_eq!(
+            client.depth_stream_name("ETHUSDT", DepthStreamMode::Partial { levels: 5 }).unwrap(),
+            "ethusdt@depth5@100ms"
+        );
+        
+        // The payload is handed on untouched, keyed by the stream's symbol
+        let data = r#"{"e":"depthUpdate","E":1,"s":"ETHUSDT","U":10,"u":11,"b":[["3000.10","1.5"]],"a":[]}"#;
+        let frame = format!(r#"{{"stream":"ethusdt@depth@100ms","data":{}}}"#, data);
+        let (symbol, payload) = client.unwrap_combined_frame(&frame).unwrap();
+        assert_eq!(symbol, "ETHUSDT");
+        assert_eq!(payload, data);
+        assert_eq!(client.parse_depth_message(payload).unwrap().first_update_id, 10);
+        
+        // Bare payloads and subscription acks are not wrapped
+        assert!(client.unwrap_combined_frame(data).is_none());
+        assert!(client.unwrap_combined_frame(r#"{"result":null,"id":1}"#).is_none());
+    }
+    
+    #[test]
+    fn test_depth_stream_speed() {
+        let fast = BinanceClient::from_config(&Config::builder().depth_stream_speed_ms(100).build().unwrap());
+        assert_eq!(
+            fast.get_orderbook_diff_stream_url("BTCUSDT"),
+            "wss://stream.binance.com:9443/ws/btcusdt@depth@100ms"
+        );
+        
+        let slow = BinanceClient::from_config(&Config::builder().depth_stream_speed_ms(1000).build().unwrap());
+        assert_eq!(
+            slow.get_orderbook_diff_stream_url("BTCUSDT"),
+            "wss://stream.binance.com:9443/ws/btcusdt@depth"
+        );
+        assert_eq!(
+            slow.partial_stream_url("BTCUSDT", 10).unwrap(),
+            "wss://stream.binance.com:9443/ws/btcusdt@depth10"
+        );
+        assert_eq!(BinanceClient::diff_depth_stream_name("BTCUSDT", 1000), "btcusdt@depth");
+    }
+    
+    #[test]
+    fn test_keepalive_frame() {
+        let client = BinanceClient::new();
+        assert_eq!(
+            client.keepalive_frame(7).unwrap(),
+            r#"{"method":"LIST_SUBSCRIPTIONS","id":7}"#
+        );
+    }
+    
+    #[test]
+    fn test_market_urls() {
+        let spot = BinanceClient::for_market(Market::Spot);
+        assert_eq!(spot.market(), Market::Spot);
+        assert_eq!(
+            spot.get_orderbook_diff_stream_url("BTCUSDT"),
+            "wss://stream.binance.com:9443/ws/btcusdt@depth@100ms"
+        );
+        
+        let futures = BinanceClient::for_market(Market::UsdFutures);
+        assert_eq!(futures.market(), Market::UsdFutures);
+        assert_eq!(futures.base_url, "https://fapi.binance.com");
+        assert_eq!(
+            futures.get_orderbook_stream_url("BTCUSDT"),
+            "wss://fstream.binance.com/ws/btcusdt@depth"
+        );
+        assert_eq!(
+            futures.get_orderbook_diff_stream_url("BTCUSDT"),
+            "wss://fstream.binance.com/ws/btcusdt@depth@100ms"
+        );
+        assert_eq!(Market::UsdFutures.exchange_info_path(), "/fapi/v1/exchangeInfo");
+        assert_eq!(Market::Spot.depth_weight(100), 5);
+        assert_eq!(Market::Spot.depth_weight(1000), 50);
+        assert_eq!(Market::UsdFutures.depth_weight(1000), 20);
+    }
+    
+    #[test]
+    fn test_depth_snapshot_url() {
+        let spot = BinanceClient::new();
+        assert_eq!(
+            spot.depth_snapshot_url("btcusdt", 100),
+            "https://api.binance.com/api/v3/depth?symbol=BTCUSDT&limit=100"
+        );
+        
+        let futures = BinanceClient::for_market(Market::UsdFutures);
+        assert_eq!(
+            futures.depth_snapshot_url("BTCUSDT", 500),
+            "https://fapi.binance.com/fapi/v1/depth?symbol=BTCUSDT&limit=500"
+        );
+        
+        let testnet = BinanceClient::testnet();
+        assert!(testnet
+            .depth_snapshot_url("BTCUSDT", 100)
+            .starts_with("https://testnet.binance.vision/api/v3/depth"));
+    }
+    
+    #[test]
+    fn test_us_domain_urls() {
+        let config = Config::builder().domain(ApiDomain::Us).build().unwrap();
+        
+        for client in [BinanceClient::for_domain(ApiDomain::Us), BinanceClient::from_config(&config)] {
+            assert_eq!(client.base_url(), "https://api.binance.us");
+            assert_eq!(
+                client.depth_snapshot_url("BTCUSDT", 100),
+                "https://api.binance.us/api/v3/depth?symbol=BTCUSDT&limit=100"
+            );
+            assert_eq!(
+                format!("{}{}", client.base_url(), client.market().exchange_info_path()),
+                "https://api.binance.us/api/v3/exchangeInfo"
+            );
+            assert_eq!(
+                client.get_orderbook_diff_stream_url("BTCUSDT"),
+                "wss://stream.binance.us:9443/ws/btcusdt@depth@100ms"
+            );
+            assert_eq!(
+                client.get_partial_depth_stream_url("BTCUSDT", 10, 100),
+                "wss://stream.binance.us:9443/ws/btcusdt@depth10@100ms"
+            );
+            assert_eq!(
+                client.get_agg_trade_stream_url("BTCUSDT"),
+                "wss://stream.binance.us:9443/ws/btcusdt@aggTrade"
+            );
+        }
+        
+        assert_eq!(BinanceClient::for_domain(ApiDomain::Global).base_url(), "https://api.binance.com");
+    }
+    
+    #[test]
+    fn test_futures_market_from_config() {
+        let config = Config::builder().market(Market::UsdFutures).build().unwrap();
+        let client = BinanceClient::from_config(&config);
+        
+        assert_eq!(client.market(), Market::UsdFutures);
+        assert_eq!(
+            client.depth_snapshot_url("BTCUSDT", 100),
+            "https://fapi.binance.com/fapi/v1/depth?symbol=BTCUSDT&limit=100"
+        );
+        assert!(client.get_orderbook_diff_stream_url("BTCUSDT").starts_with("wss://fstream.binance.com/ws/"));
+        
+        assert_eq!(BinanceClient::from_config(&Config::builder().build().unwrap()).market(), Market::Spot);
+        assert_eq!("futures".parse::<Market>().unwrap(), Market::UsdFutures);
+        assert!("options".parse::<Market>().is_err());
+    }
+    
+    #[test]
+    fn test_testnet_urls() {
+        let client = BinanceClient::testnet();
+        assert_eq!(client.base_url(), "https://testnet.binance.vision");
+        assert_eq!(client.ws_base_url(), "wss://testnet.binance.vision/ws");
+        assert_eq!(
+            client.get_orderbook_diff_stream_url("BTCUSDT"),
+            "wss://testnet.binance.vision/ws/btcusdt@depth@100ms"
+        );
+        
+        // Trailing slashes are normalized away
+        let custom = BinanceClient::with_base_urls(
+            Market::Spot,
+            "https://testnet.binance.vision/",
+            "wss://testnet.binance.vision/ws/",
+        );
+        assert_eq!(
+            custom.get_orderbook_stream_url("ETHUSDT"),
+            "wss://testnet.binance.vision/ws/ethusdt@depth"
+        );
     }
 }
\ No newline at end of file
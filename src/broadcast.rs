@@ -0,0 +1,159 @@
+use crate::fair_price::FairPriceResult;
+use anyhow::Result;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use std::net::SocketAddr;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tracing::{debug, info, warn};
+
+/// JSON frames buffered per client before it starts skipping results
+const FRAME_BUFFER: usize = 256;
+
+/// One pushed result: the fair price result tagged with its symbol
+#[derive(Serialize)]
+struct ResultFrame<'a> {
+    symbol: &'a str,
+    #[serde(flatten)]
+    result: &'a FairPriceResult,
+}
+
+/// WebSocket server pushing every fair price result to connected clients as JSON
+pub struct BroadcastServer {
+    frames: broadcast::Sender<String>,
+}
+
+impl BroadcastServer {
+    pub fn new() -> Self {
+        let (frames, _) = broadcast::channel(FRAME_BUFFER);
+        Self { frames }
+    }
+    
+    /// Number of connected clients
+    pub fn client_count(&self) -> usize {
+        self.frames.receiver_count()
+    }
+    
+    /// Push one result to every connected client
+    pub fn publish(&self, symbol: &str, result: &FairPriceResult) -> Result<()> {
+        let frame = serde_json::to_string(&ResultFrame { symbol, result })?;
+        
+        // No receivers just means no clients are connected
+        let _ = self.frames.send(frame);
+        Ok(())
+    }
+    
+    /// Publish every result from a result stream until the stream closes
+    pub async fn forward_results(
+        &self,
+        symbol: String,
+        mut results: broadcast::Receiver<FairPriceResult>,
+    ) {
+        loop {
+            match results.recv().await {
+                Ok(result) => {
+                    if let Err(e) = self.publish(&symbol, &result) {
+                        warn!("Failed to serialize result for {}: {}", symbol, e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Broadcast server for {} fell behind, skipped {} results", symbol, skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+    
+    /// Accept WebSocket clients on `addr` until the task is dropped
+    pub async fn serve(&self, addr: SocketAddr) -> Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        info!("📡 Broadcasting fair prices on ws://{}", listener.local_addr()?);
+        self.serve_listener(listener).await
+    }
+    
+    /// Accept WebSocket clients on an already bound listener
+    pub async fn serve_listener(&self, listener: TcpListener) -> Result<()> {
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let frames = self.frames.subscribe();
+            
+            tokio::spawn(async move {
+                if let Err(e) = handle_client(stream, frames).await {
+                    warn!("Broadcast client {} failed: {}", peer, e);
+                }
+                debug!("Broadcast client {} disconnected", peer);
+            });
+        }
+    }
+}
+
+impl Default for BroadcastServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Push frames to one client until it disconnects
+async fn handle_client(stream: TcpStream, mut frames: broadcast::Receiver<String>) -> Result<()> {
+    let ws_stream = accept_async(stream).await?;
+    let (mut ws_sender, mut ws_receiver) = ws_stream.split();
+    
+    loop {
+        tokio::select! {
+            frame = frames.recv() => match frame {
+                Ok(frame) => ws_sender.send(Message::Text(frame)).await?,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Broadcast client fell behind, skipped {} results", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            
+            // Clients only listen; watch for them going away
+            msg = ws_receiver.next() => match msg {
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Err(e)) => return Err(e.into()),
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_price::test_result;
+    use serde_json::Value;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio_tungstenite::connect_async;
+    
+    #[tokio::test]
+    async fn test_client_receives_pushed_result() {
+        let server = Arc::new(BroadcastServer::new());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let serving = server.clone();
+        tokio::spawn(async move { serving.serve_listener(listener).await });
+        
+        let (mut client, _) = connect_async(format!("ws://{}", addr)).await.unwrap();
+        while server.client_count() == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        
+        let result = test_result(50000.5);
+        server.publish("BTCUSDT", &result).unwrap();
+        
+        let frame = tokio::time::timeout(Duration::from_secs(5), client.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let value: Value = serde_json::from_str(frame.to_text().unwrap()).unwrap();
+        assert_eq!(value["symbol"], "BTCUSDT");
+        assert_eq!(value["fair_price"], 50000.5);
+        assert_eq!(value["metadata"]["total_volume"], 2.0);
+    }
+}
@@ -1,4 +1,11 @@
+use crate::binance::{ApiDomain, Market, TESTNET_REST_URL, TESTNET_WS_URL};
+use crate::order_book::CrossedBookPolicy;
+use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Prefix for environment variables read by `Config::from_env`
+pub const ENV_PREFIX: &str = "ORDERFLOW_";
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +21,12 @@ pub struct Config {
     
     /// Order book configuration
     pub order_book: OrderBookConfig,
+    
+    /// How each fair price result is logged
+    pub display_mode: DisplayMode,
+    
+    /// Decimal places for logged prices (`None` uses `DEFAULT_DISPLAY_PRECISION`)
+    pub display_precision: Option<usize>,
 }
 
 /// Fair price calculation methods
@@ -27,56 +40,691 @@ pub enum FairPriceMethod {
     
     /// Micro-price considering order flow
     MicroPrice,
+    
+    /// Average of the bid/ask VWAPs needed to fill a quote-currency notional
+    ExecutionVwap { notional: f64 },
+    
+    /// Top N levels weighted by quantity over distance from mid
+    BookPressure { levels: usize },
+    
+    /// Geometric mid-price: sqrt(best_bid * best_ask)
+    GeometricMid,
+    
+    /// Volume-weighted top N levels, decayed by `exp(-k * |price - mid| / mid)`
+    VolumeWeightedDecay { levels: usize, k: f64 },
+    
+    /// Confidence-weighted blend of every other method, discounted by their disagreement
+    Ensemble,
+}
+
+/// Depth stream used to maintain the order book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DepthStreamMode {
+    /// Diff stream applied on top of a REST snapshot
+    #[default]
+    Diff,
+    
+    /// Partial-book stream replacing the top N levels each frame (5, 10 or 20)
+    Partial { levels: u32 },
+}
+
+/// Frame sent on every heartbeat tick to keep the connection alive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum KeepaliveMode {
+    /// WebSocket ping control frame carrying `ping_payload`
+    #[default]
+    Ping,
+    
+    /// Application-level `LIST_SUBSCRIPTIONS` request, for gateways that drop control frames
+    ListSubscriptions,
+}
+
+/// Decimal places for logged prices when neither config nor symbol info sets one
+pub const DEFAULT_DISPLAY_PRECISION: usize = 4;
+
+/// How `WebSocketManager` logs each fair price result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DisplayMode {
+    /// Multi-line summary box (debug level)
+    #[default]
+    Box,
+    
+    /// Single summary line
+    OneLine,
+    
+    /// One JSON object per result
+    Json,
+    
+    /// Nothing; results are only published via `subscribe_results`
+    Silent,
+}
+
+impl FromStr for DisplayMode {
+    type Err = anyhow::Error;
+    
+    /// Parse a CLI display mode name (e.g. `one-line`)
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "box" => Ok(DisplayMode::Box),
+            "one-line" => Ok(DisplayMode::OneLine),
+            "json" => Ok(DisplayMode::Json),
+            "silent" => Ok(DisplayMode::Silent),
+            other => Err(anyhow!("Unknown display mode: {}", other)),
+        }
+    }
 }
 
 /// WebSocket configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebSocketConfig {
+    /// Binance market to stream (spot or USD-M futures)
+    pub market: Market,
+    
     /// Binance WebSocket base URL
     pub base_url: String,
     
+    /// Binance REST API base URL (snapshots and symbol lookups)
+    pub rest_base_url: String,
+    
     /// Reconnection settings
     pub reconnect_attempts: u32,
     pub reconnect_delay_ms: u64,
     
+    /// Reconnect when the server closes the connection cleanly instead of stopping;
+    /// waits `reconnect_delay_ms` and gives up after `reconnect_attempts` unstable closes
+    pub reconnect_on_clean_close: bool,
+    
+    /// A connection live this long resets the failed attempt and clean-close counts (milliseconds)
+    pub stable_connection_ms: u64,
+    
     /// Heartbeat settings
     pub ping_interval_ms: u64,
+    
+    /// Payload of heartbeat ping frames; some proxies drop empty pings
+    pub ping_payload: String,
+    
+    /// Frame sent on each heartbeat tick
+    pub keepalive: KeepaliveMode,
+    
+    /// Reconnect when no frame (data, ping or pong) arrives within this time (milliseconds)
+    pub read_timeout_ms: u64,
+    
+    /// Report the feed as stale when the order book has not advanced within this time (milliseconds)
+    pub max_feed_staleness_ms: u64,
+    
+    /// Recompute the fair price on this timer when no update arrives (milliseconds); disabled when `None`
+    pub recalc_interval_ms: Option<u64>,
+    
+    /// Depth stream mode
+    pub depth_stream: DepthStreamMode,
+    
+    /// Depth stream update speed (milliseconds, one of `DEPTH_STREAM_SPEEDS_MS`)
+    pub depth_stream_speed_ms: u32,
+    
+    /// Also subscribe to the aggTrade stream for trade-based order flow
+    pub agg_trades: bool,
+    
+    /// Rolling window for trade flow accumulation (milliseconds)
+    pub trade_flow_window_ms: u64,
+    
+    /// Received frames buffered ahead of processing; on overflow partial books are coalesced
+    /// and queued diffs flushed for a resync
+    pub max_buffered_messages: usize,
 }
 
+/// Update speeds offered by the Binance depth streams (milliseconds)
+pub const DEPTH_STREAM_SPEEDS_MS: [u32; 2] = [100, 1000];
+
+/// Depth limits accepted by the Binance depth snapshot endpoint
+pub const SNAPSHOT_DEPTHS: [u32; 8] = [5, 10, 20, 50, 100, 500, 1000, 5000];
+
 /// Order book configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBookConfig {
     /// Maximum depth to maintain
     pub max_depth: usize,
     
+    /// Levels requested in the REST depth snapshot (one of `SNAPSHOT_DEPTHS`)
+    pub snapshot_depth: u32,
+    
     /// Update frequency threshold (microseconds)
     pub update_threshold_us: u64,
+    
+    /// How to resolve a crossed book after an update (default: warn and leave it)
+    pub crossed_book_policy: CrossedBookPolicy,
+    
+    /// Drop levels not updated within this age (microseconds); disabled when `None`
+    pub max_level_age_us: Option<u64>,
+    
+    /// Compare the book against a REST snapshot this often (milliseconds); disabled when `None`
+    pub verify_interval_ms: Option<u64>,
+    
+    /// Replace the book with a fresh REST snapshot this often (milliseconds); disabled when `None`
+    pub snapshot_refresh_interval_ms: Option<u64>,
+    
+    /// Resync when a level's quantity differs from the snapshot by more than this fraction
+    pub max_quantity_divergence: f64,
+    
+    /// Resync when a level's price differs from the snapshot's at the same rank by more than this
+    pub max_price_divergence: f64,
+}
+
+impl FairPriceMethod {
+    /// Every method with its default parameters
+    pub fn all() -> Vec<FairPriceMethod> {
+        vec![
+            FairPriceMethod::MidPrice,
+            FairPriceMethod::VolumeWeighted { levels: 5 },
+            FairPriceMethod::MicroPrice,
+            FairPriceMethod::ExecutionVwap { notional: 10_000.0 },
+            FairPriceMethod::BookPressure { levels: 10 },
+            FairPriceMethod::GeometricMid,
+            FairPriceMethod::VolumeWeightedDecay { levels: 10, k: 1000.0 },
+        ]
+    }
+}
+
+impl FromStr for FairPriceMethod {
+    type Err = anyhow::Error;
+    
+    /// Parse a CLI method name (e.g. `micro-price`) with default parameters
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "mid-price" => Ok(FairPriceMethod::MidPrice),
+            "volume-weighted" => Ok(FairPriceMethod::VolumeWeighted { levels: 5 }),
+            "micro-price" => Ok(FairPriceMethod::MicroPrice),
+            "execution-vwap" => Ok(FairPriceMethod::ExecutionVwap { notional: 10_000.0 }),
+            "book-pressure" => Ok(FairPriceMethod::BookPressure { levels: 10 }),
+            "geometric-mid" => Ok(FairPriceMethod::GeometricMid),
+            "volume-weighted-decay" => Ok(FairPriceMethod::VolumeWeightedDecay { levels: 10, k: 1000.0 }),
+            "ensemble" => Ok(FairPriceMethod::Ensemble),
+            other => Err(anyhow!("Unknown calculation method: {}", other)),
+        }
+    }
 }
 
 impl Config {
     pub fn new(symbol: String, method_str: String) -> Self {
-        let calculation_method = match method_str.to_lowercase().as_str() {
-            "mid-price" => FairPriceMethod::MidPrice,
-            "volume-weighted" => FairPriceMethod::VolumeWeighted { levels: 5 },
-            "micro-price" => FairPriceMethod::MicroPrice,
-            _ => FairPriceMethod::MidPrice,
+        let calculation_method = method_str.parse().unwrap_or(FairPriceMethod::MidPrice);
+        
+        ConfigBuilder::new()
+            .symbol(symbol)
+            .method(calculation_method)
+            .config
+    }
+    
+    /// Build a configuration from `ORDERFLOW_*` environment variables
+    ///
+    /// Reads `ORDERFLOW_SYMBOL`, `ORDERFLOW_METHOD`, `ORDERFLOW_TESTNET`,
+    /// `ORDERFLOW_RECONNECT_ATTEMPTS`, `ORDERFLOW_RECONNECT_DELAY_MS`,
+    /// `ORDERFLOW_PING_INTERVAL_MS`, `ORDERFLOW_READ_TIMEOUT_MS`,
+    /// `ORDERFLOW_MAX_DEPTH` and `ORDERFLOW_SNAPSHOT_DEPTH`; unset variables
+    /// keep their defaults.
+    pub fn from_env() -> Result<Self> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+    
+    /// Build a configuration from `ORDERFLOW_*` values supplied by `lookup`
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name: &str| lookup(&format!("{}{}", ENV_PREFIX, name));
+        let parsed = |name: &str| -> Result<Option<u64>> {
+            var(name)
+                .map(|value| {
+                    value.trim().parse::<u64>().map_err(|_| {
+                        anyhow!("{}{} must be a non-negative integer, got '{}'", ENV_PREFIX, name, value)
+                    })
+                })
+                .transpose()
         };
         
+        let mut builder = ConfigBuilder::new();
+        if let Some(symbol) = var("SYMBOL") {
+            builder = builder.symbol(symbol.trim().to_uppercase());
+        }
+        if let Some(method) = var("METHOD") {
+            builder = builder.method(method.parse()?);
+        }
+        if let Some(testnet) = var("TESTNET") {
+            match testnet.trim().to_lowercase().as_str() {
+                "1" | "true" | "yes" => builder = builder.testnet(),
+                "0" | "false" | "no" | "" => {}
+                other => return Err(anyhow!("{}TESTNET must be true or false, got '{}'", ENV_PREFIX, other)),
+            }
+        }
+        if let Some(attempts) = parsed("RECONNECT_ATTEMPTS")? {
+            let attempts = u32::try_from(attempts)
+                .map_err(|_| anyhow!("{}RECONNECT_ATTEMPTS is too large", ENV_PREFIX))?;
+            builder = builder.reconnect_attempts(attempts);
+        }
+        if let Some(delay_ms) = parsed("RECONNECT_DELAY_MS")? {
+            builder = builder.reconnect_delay_ms(delay_ms);
+        }
+        if let Some(interval_ms) = parsed("PING_INTERVAL_MS")? {
+            builder = builder.ping_interval_ms(interval_ms);
+        }
+        if let Some(timeout_ms) = parsed("READ_TIMEOUT_MS")? {
+            builder = builder.read_timeout_ms(timeout_ms);
+        }
+        if let Some(max_depth) = parsed("MAX_DEPTH")? {
+            builder = builder.max_depth(max_depth as usize);
+        }
+        if let Some(depth) = parsed("SNAPSHOT_DEPTH")? {
+            let depth = u32::try_from(depth)
+                .map_err(|_| anyhow!("{}SNAPSHOT_DEPTH is too large", ENV_PREFIX))?;
+            builder = builder.snapshot_depth(depth);
+        }
+        
+        builder.build()
+    }
+    
+    /// Start building a configuration from defaults
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+    
+    /// Point REST and WebSocket hosts at a regional API domain (e.g. Binance.US)
+    pub fn with_domain(mut self, domain: ApiDomain) -> Self {
+        self.websocket.base_url = domain.ws_base_url().to_string();
+        self.websocket.rest_base_url = domain.rest_base_url().to_string();
+        self
+    }
+    
+    /// Stream another Binance market, pointing REST and WebSocket hosts at it
+    ///
+    /// Testnet and regional hosts are kept rather than silently swapped for
+    /// mainnet ones; `validate` rejects them for markets they don't serve.
+    pub fn with_market(mut self, market: Market) -> Self {
+        self.websocket.market = market;
+        if !self.uses_spot_only_hosts() {
+            self.websocket.base_url = market.ws_base_url().to_string();
+            self.websocket.rest_base_url = market.rest_base_url().to_string();
+        }
+        self
+    }
+    
+    /// Whether either host is the spot testnet or a regional domain
+    fn uses_spot_only_hosts(&self) -> bool {
+        [&self.websocket.base_url, &self.websocket.rest_base_url].into_iter().any(|url| {
+            let url = url.trim_end_matches('/');
+            Market::for_host(url) == Some(Market::Spot)
+                && url != Market::Spot.ws_base_url()
+                && url != Market::Spot.rest_base_url()
+        })
+    }
+    
+    /// Point REST and WebSocket hosts at the Binance Spot Testnet
+    pub fn with_testnet(mut self) -> Self {
+        self.websocket.base_url = TESTNET_WS_URL.to_string();
+        self.websocket.rest_base_url = TESTNET_REST_URL.to_string();
+        self
+    }
+    
+    /// Validate configuration values
+    pub fn validate(&self) -> Result<()> {
+        if self.symbol.trim().is_empty() {
+            return Err(anyhow!("Symbol must not be empty"));
+        }
+        
+        match self.calculation_method {
+            FairPriceMethod::VolumeWeighted { levels: 0 } => {
+                return Err(anyhow!("Volume-weighted levels must be greater than zero"));
+            }
+            FairPriceMethod::BookPressure { levels: 0 } => {
+                return Err(anyhow!("Book pressure levels must be greater than zero"));
+            }
+            FairPriceMethod::VolumeWeightedDecay { levels: 0, .. } => {
+                return Err(anyhow!("Volume-weighted decay levels must be greater than zero"));
+            }
+            FairPriceMethod::VolumeWeightedDecay { k, .. } if !k.is_finite() || k < 0.0 => {
+                return Err(anyhow!("Volume-weighted decay k must be finite and non-negative"));
+            }
+            FairPriceMethod::ExecutionVwap { notional } if notional.is_nan() || notional <= 0.0 => {
+                return Err(anyhow!("Execution VWAP notional must be positive"));
+            }
+            _ => {}
+        }
+        
+        if self.websocket.reconnect_attempts == 0 {
+            return Err(anyhow!("Reconnect attempts must be greater than zero"));
+        }
+        
+        if let DepthStreamMode::Partial { levels } = self.websocket.depth_stream {
+            if ![5, 10, 20].contains(&levels) {
+                return Err(anyhow!("Partial depth levels must be 5, 10 or 20"));
+            }
+        }
+        
+        for url in [&self.websocket.base_url, &self.websocket.rest_base_url] {
+            if let Some(host_market) = Market::for_host(url) {
+                if host_market != self.websocket.market {
+                    return Err(anyhow!(
+                        "{} serves the {} market, not {}",
+                        url,
+                        host_market,
+                        self.websocket.market
+                    ));
+                }
+            }
+        }
+        
+        if !DEPTH_STREAM_SPEEDS_MS.contains(&self.websocket.depth_stream_speed_ms) {
+            return Err(anyhow!("Depth stream speed must be 100 or 1000 ms"));
+        }
+        
+        if self.websocket.ping_interval_ms == 0 {
+            return Err(anyhow!("Ping interval must be greater than zero"));
+        }
+        
+        if self.websocket.max_buffered_messages == 0 {
+            return Err(anyhow!("Message buffer size must be greater than zero"));
+        }
+        
+        if self.websocket.read_timeout_ms == 0 {
+            return Err(anyhow!("Read timeout must be greater than zero"));
+        }
+        
+        if self.websocket.max_feed_staleness_ms == 0 {
+            return Err(anyhow!("Max feed staleness must be greater than zero"));
+        }
+        
+        if self.websocket.recalc_interval_ms == Some(0) {
+            return Err(anyhow!("Recalculation interval must be greater than zero"));
+        }
+        
+        if self.order_book.max_depth == 0 {
+            return Err(anyhow!("Max depth must be greater than zero"));
+        }
+        
+        if self.order_book.max_level_age_us == Some(0) {
+            return Err(anyhow!("Max level age must be greater than zero"));
+        }
+        
+        if self.order_book.verify_interval_ms == Some(0) {
+            return Err(anyhow!("Verify interval must be greater than zero"));
+        }
+        
+        if self.order_book.snapshot_refresh_interval_ms == Some(0) {
+            return Err(anyhow!("Snapshot refresh interval must be greater than zero"));
+        }
+        
+        if self.order_book.max_quantity_divergence.is_nan() || self.order_book.max_quantity_divergence < 0.0 {
+            return Err(anyhow!("Max quantity divergence must not be negative"));
+        }
+        
+        if self.order_book.max_price_divergence.is_nan() || self.order_book.max_price_divergence < 0.0 {
+            return Err(anyhow!("Max price divergence must not be negative"));
+        }
+        
+        if !SNAPSHOT_DEPTHS.contains(&self.order_book.snapshot_depth) {
+            return Err(anyhow!(
+                "Snapshot depth must be one of {:?}, got {}",
+                SNAPSHOT_DEPTHS,
+                self.order_book.snapshot_depth
+            ));
+        }
+        
+        Ok(())
+    }
+}
+
+/// Fluent builder for `Config`
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
         Self {
-            symbol,
-            calculation_method,
-            websocket: WebSocketConfig {
-                base_url: "wss://stream.binance.com:9443/ws/".to_string(),
-                reconnect_attempts: 5,
-                reconnect_delay_ms: 1000,
-                ping_interval_ms: 30000,
-            },
-            order_book: OrderBookConfig {
-                max_depth: 100,
-                update_threshold_us: 1000, // 1ms
+            config: Config {
+                symbol: "BTCUSDT".to_string(),
+                calculation_method: FairPriceMethod::MidPrice,
+                websocket: WebSocketConfig {
+                    market: Market::default(),
+                    base_url: "wss://stream.binance.com:9443/ws/".to_string(),
+                    rest_base_url: "https://api.binance.com".to_string(),
+                    reconnect_attempts: 5,
+                    reconnect_delay_ms: 1000,
+                    reconnect_on_clean_close: true,
+                    stable_connection_ms: 60_000,
+                    ping_interval_ms: 30000,
+                    ping_payload: String::new(),
+                    keepalive: KeepaliveMode::Ping,
+                    read_timeout_ms: 60000,
+                    max_feed_staleness_ms: 10000,
+                    recalc_interval_ms: None,
+                    depth_stream: DepthStreamMode::Diff,
+                    depth_stream_speed_ms: 100,
+                    agg_trades: false,
+                    trade_flow_window_ms: 5000,
+                    max_buffered_messages: 1024,
+                },
+                order_book: OrderBookConfig {
+                    max_depth: 100,
+                    snapshot_depth: 100,
+                    update_threshold_us: 1000, // 1ms
+                    crossed_book_policy: CrossedBookPolicy::default(),
+                    max_level_age_us: None,
+                    verify_interval_ms: None,
+                    snapshot_refresh_interval_ms: None,
+                    max_quantity_divergence: 0.5,
+                    max_price_divergence: 0.0,
+                },
+                display_mode: DisplayMode::Box,
+                display_precision: None,
             },
         }
     }
+    
+    /// Trading symbol (e.g., BTCUSDT)
+    pub fn symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.config.symbol = symbol.into();
+        self
+    }
+    
+    /// Fair price calculation method
+    pub fn method(mut self, method: FairPriceMethod) -> Self {
+        self.config.calculation_method = method;
+        self
+    }
+    
+    /// Binance WebSocket base URL
+    pub fn ws_base_url(mut self, url: impl Into<String>) -> Self {
+        self.config.websocket.base_url = url.into();
+        self
+    }
+    
+    /// Binance REST API base URL
+    pub fn rest_base_url(mut self, url: impl Into<String>) -> Self {
+        self.config.websocket.rest_base_url = url.into();
+        self
+    }
+    
+    /// Use the REST and WebSocket hosts of a regional API domain
+    pub fn domain(mut self, domain: ApiDomain) -> Self {
+        self.config = self.config.with_domain(domain);
+        self
+    }
+    
+    /// Stream another Binance market (e.g. USD-M futures) from its hosts
+    pub fn market(mut self, market: Market) -> Self {
+        self.config = self.config.with_market(market);
+        self
+    }
+    
+    /// Use the Binance Spot Testnet hosts
+    pub fn testnet(mut self) -> Self {
+        self.config = self.config.with_testnet();
+        self
+    }
+    
+    /// Maximum reconnection attempts
+    pub fn reconnect_attempts(mut self, attempts: u32) -> Self {
+        self.config.websocket.reconnect_attempts = attempts;
+        self
+    }
+    
+    /// Delay between reconnection attempts (milliseconds)
+    pub fn reconnect_delay_ms(mut self, delay_ms: u64) -> Self {
+        self.config.websocket.reconnect_delay_ms = delay_ms;
+        self
+    }
+    
+    /// Reconnect (without using up an attempt) or stop when the server closes cleanly
+    pub fn reconnect_on_clean_close(mut self, enabled: bool) -> Self {
+        self.config.websocket.reconnect_on_clean_close = enabled;
+        self
+    }
+    
+    /// Treat reconnect attempts as consecutive failures once a connection stays live this long (milliseconds)
+    pub fn stable_connection_ms(mut self, stable_ms: u64) -> Self {
+        self.config.websocket.stable_connection_ms = stable_ms;
+        self
+    }
+    
+    /// Heartbeat ping interval (milliseconds)
+    pub fn ping_interval_ms(mut self, interval_ms: u64) -> Self {
+        self.config.websocket.ping_interval_ms = interval_ms;
+        self
+    }
+    
+    /// Payload of heartbeat ping frames
+    pub fn ping_payload(mut self, payload: impl Into<String>) -> Self {
+        self.config.websocket.ping_payload = payload.into();
+        self
+    }
+    
+    /// Frame sent on each heartbeat tick
+    pub fn keepalive(mut self, mode: KeepaliveMode) -> Self {
+        self.config.websocket.keepalive = mode;
+        self
+    }
+    
+    /// Reconnect after this long without any received frame (milliseconds)
+    pub fn read_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.config.websocket.read_timeout_ms = timeout_ms;
+        self
+    }
+    
+    /// Frames buffered ahead of processing before the oldest data frames are dropped
+    pub fn max_buffered_messages(mut self, max_messages: usize) -> Self {
+        self.config.websocket.max_buffered_messages = max_messages;
+        self
+    }
+    
+    /// Treat the feed as stale after this long without an order book update (milliseconds)
+    pub fn max_feed_staleness_ms(mut self, staleness_ms: u64) -> Self {
+        self.config.websocket.max_feed_staleness_ms = staleness_ms;
+        self
+    }
+    
+    /// Recompute the fair price after this long without an update (milliseconds)
+    pub fn recalc_interval_ms(mut self, interval_ms: u64) -> Self {
+        self.config.websocket.recalc_interval_ms = Some(interval_ms);
+        self
+    }
+    
+    /// How each fair price result is logged
+    pub fn display_mode(mut self, mode: DisplayMode) -> Self {
+        self.config.display_mode = mode;
+        self
+    }
+    
+    /// Decimal places for logged prices, e.g. 8 for sub-cent symbols
+    pub fn display_precision(mut self, decimals: usize) -> Self {
+        self.config.display_precision = Some(decimals);
+        self
+    }
+    
+    /// Depth stream mode
+    pub fn depth_stream(mut self, mode: DepthStreamMode) -> Self {
+        self.config.websocket.depth_stream = mode;
+        self
+    }
+    
+    /// Depth stream update speed: 100ms for low latency, 1000ms for less CPU
+    pub fn depth_stream_speed_ms(mut self, speed_ms: u32) -> Self {
+        self.config.websocket.depth_stream_speed_ms = speed_ms;
+        self
+    }
+    
+    /// Subscribe to the aggTrade stream
+    pub fn agg_trades(mut self, enabled: bool) -> Self {
+        self.config.websocket.agg_trades = enabled;
+        self
+    }
+    
+    /// Rolling window for trade flow accumulation (milliseconds)
+    pub fn trade_flow_window_ms(mut self, window_ms: u64) -> Self {
+        self.config.websocket.trade_flow_window_ms = window_ms;
+        self
+    }
+    
+    /// Maximum order book depth to maintain
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.config.order_book.max_depth = max_depth;
+        self
+    }
+    
+    /// REST depth snapshot limit
+    pub fn snapshot_depth(mut self, depth: u32) -> Self {
+        self.config.order_book.snapshot_depth = depth;
+        self
+    }
+    
+    /// Minimum time between fair price calculations (microseconds)
+    pub fn update_threshold_us(mut self, threshold_us: u64) -> Self {
+        self.config.order_book.update_threshold_us = threshold_us;
+        self
+    }
+    
+    /// Crossed book resolution policy, e.g. `RemoveCrossed` to drop stale crossed levels
+    pub fn crossed_book_policy(mut self, policy: CrossedBookPolicy) -> Self {
+        self.config.order_book.crossed_book_policy = policy;
+        self
+    }
+    
+    /// Prune levels not updated within this age (microseconds)
+    pub fn max_level_age_us(mut self, max_age_us: u64) -> Self {
+        self.config.order_book.max_level_age_us = Some(max_age_us);
+        self
+    }
+    
+    /// Verify the book against a REST snapshot on this interval (milliseconds)
+    pub fn verify_interval_ms(mut self, interval_ms: u64) -> Self {
+        self.config.order_book.verify_interval_ms = Some(interval_ms);
+        self
+    }
+    
+    /// Unconditionally re-initialize the book from a REST snapshot on this interval (milliseconds)
+    pub fn snapshot_refresh_interval_ms(mut self, interval_ms: u64) -> Self {
+        self.config.order_book.snapshot_refresh_interval_ms = Some(interval_ms);
+        self
+    }
+    
+    /// Relative quantity divergence that triggers a resync
+    pub fn max_quantity_divergence(mut self, divergence: f64) -> Self {
+        self.config.order_book.max_quantity_divergence = divergence;
+        self
+    }
+    
+    /// Absolute price divergence at the same rank that triggers a resync
+    pub fn max_price_divergence(mut self, divergence: f64) -> Self {
+        self.config.order_book.max_price_divergence = divergence;
+        self
+    }
+    
+    /// Validate and return the configuration
+    pub fn build(self) -> Result<Config> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Default for Config {
@@ -93,6 +741,121 @@ impl std::fmt::Display for FairPriceMethod {
                 write!(f, "Volume-Weighted (top {} levels)", levels)
             }
             FairPriceMethod::MicroPrice => write!(f, "Micro-Price"),
+            FairPriceMethod::ExecutionVwap { notional } => {
+                write!(f, "Execution VWAP (${:.0} notional)", notional)
+            }
+            FairPriceMethod::BookPressure { levels } => {
+                write!(f, "Book Pressure (top {} levels)", levels)
+            }
+            FairPriceMethod::GeometricMid => write!(f, "Geometric Mid"),
+            FairPriceMethod::VolumeWeightedDecay { levels, k } => {
+                write!(f, "Volume-Weighted Decay (top {} levels, k={})", levels, k)
+            }
+            FairPriceMethod::Ensemble => write!(f, "Ensemble"),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_config_from_env() {
+        let vars: std::collections::HashMap<&str, &str> = [
+            ("ORDERFLOW_SYMBOL", "ethusdt"),
+            ("ORDERFLOW_METHOD", "micro-price"),
+            ("ORDERFLOW_RECONNECT_ATTEMPTS", "7"),
+            ("ORDERFLOW_SNAPSHOT_DEPTH", "500"),
+        ]
+        .into_iter()
+        .collect();
+        let config = Config::from_lookup(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+        
+        assert_eq!(config.symbol, "ETHUSDT");
+        assert_eq!(config.calculation_method, FairPriceMethod::MicroPrice);
+        assert_eq!(config.websocket.reconnect_attempts, 7);
+        assert_eq!(config.order_book.snapshot_depth, 500);
+        assert_eq!(config.websocket.ping_interval_ms, 30000); // Unset keeps the default
+        
+        // Malformed values are reported by name
+        let lookup = |name: &str| (name == "ORDERFLOW_READ_TIMEOUT_MS").then(|| "5s".to_string());
+        let err = Config::from_lookup(lookup).unwrap_err();
+        assert!(err.to_string().contains("ORDERFLOW_READ_TIMEOUT_MS"));
+        
+        let lookup = |name: &str| (name == "ORDERFLOW_METHOD").then(|| "best-guess".to_string());
+        assert!(Config::from_lookup(lookup).is_err());
+    }
+    
+    #[test]
+    fn test_builder_non_default() {
+        let config = Config::builder()
+            .symbol("ETHUSDT")
+            .method(FairPriceMethod::VolumeWeighted { levels: 10 })
+            .reconnect_attempts(3)
+            .reconnect_delay_ms(250)
+            .ping_interval_ms(10_000)
+            .read_timeout_ms(25_000)
+            .max_depth(50)
+            .update_threshold_us(5_000)
+            .crossed_book_policy(CrossedBookPolicy::RemoveCrossed)
+            .build()
+            .unwrap();
+        
+        assert_eq!(config.symbol, "ETHUSDT");
+        assert_eq!(config.calculation_method, FairPriceMethod::VolumeWeighted { levels: 10 });
+        assert_eq!(config.websocket.reconnect_attempts, 3);
+        assert_eq!(config.websocket.reconnect_delay_ms, 250);
+        assert_eq!(config.websocket.ping_interval_ms, 10_000);
+        assert_eq!(config.websocket.read_timeout_ms, 25_000);
+        assert_eq!(config.order_book.max_depth, 50);
+        assert_eq!(config.order_book.update_threshold_us, 5_000);
+        assert_eq!(config.order_book.crossed_book_policy, CrossedBookPolicy::RemoveCrossed);
+    }
+    
+    #[test]
+    fn test_builder_validation() {
+        assert!(Config::builder().symbol("").build().is_err());
+        assert!(Config::builder().max_depth(0).build().is_err());
+        assert!(Config::builder().snapshot_depth(37).build().is_err());
+        assert!(Config::builder().max_level_age_us(0).build().is_err());
+        assert!(Config::builder().max_price_divergence(-1.0).build().is_err());
+        assert!(Config::builder().snapshot_depth(1000).build().is_ok());
+        assert!(Config::builder()
+            .depth_stream(DepthStreamMode::Partial { levels: 15 })
+            .build()
+            .is_err());
+        assert!(Config::builder().depth_stream_speed_ms(500).build().is_err());
+        
+        // Testnet and regional hosts are spot only, whichever is set first
+        assert!(Config::builder().market(Market::UsdFutures).testnet().build().is_err());
+        assert!(Config::builder().testnet().market(Market::UsdFutures).build().is_err());
+        assert!(Config::builder().market(Market::UsdFutures).domain(ApiDomain::Us).build().is_err());
+        assert!(Config::builder().market(Market::UsdFutures).domain(ApiDomain::Global).build().is_err());
+        let config = Config::builder().testnet().market(Market::Spot).build().unwrap();
+        assert_eq!(config.websocket.rest_base_url, TESTNET_REST_URL);
+        let config = Config::builder().domain(ApiDomain::Us).market(Market::UsdFutures);
+        assert!(config.build().unwrap_err().to_string().contains("serves the Spot market"));
+        assert!(Config::builder().market(Market::UsdFutures).build().is_ok());
+        assert!(Config::builder().depth_stream_speed_ms(1000).build().is_ok());
+        assert!(Config::builder()
+            .method(FairPriceMethod::VolumeWeighted { levels: 0 })
+            .build()
+            .is_err());
+        
+        // Config::new matches the builder defaults
+        let config = Config::new("BTCUSDT".to_string(), "micro-price".to_string());
+        assert_eq!(config.calculation_method, FairPriceMethod::MicroPrice);
+        let config = Config::new("BTCUSDT".to_string(), "geometric-mid".to_string());
+        assert_eq!(config.calculation_method, FairPriceMethod::GeometricMid);
+        let config = Config::new("BTCUSDT".to_string(), "volume-weighted-decay".to_string());
+        assert_eq!(config.calculation_method.to_string(), "Volume-Weighted Decay (top 10 levels, k=1000)");
+        let config = Config::new("BTCUSDT".to_string(), "ensemble".to_string());
+        assert_eq!(config.calculation_method, FairPriceMethod::Ensemble);
+        assert!(Config::builder()
+            .method(FairPriceMethod::VolumeWeightedDecay { levels: 5, k: -1.0 })
+            .build()
+            .is_err());
+        assert_eq!(config.order_book.max_depth, 100);
+    }
+}
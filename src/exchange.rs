@@ -0,0 +1,71 @@
+use crate::config::DepthStreamMode;
+use crate::order_book::{OrderBookSnapshot, OrderBookUpdate, PartialDepth};
+use anyhow::Result;
+use std::future::Future;
+
+/// Venue-specific endpoints and message formats behind `WebSocketManager`
+///
+/// Only the diff-depth methods are required; partial-book streams, combined
+/// streams and live stream subscriptions are optional and disabled by the
+/// default methods.
+pub trait ExchangeConnector: Send + Sync {
+    /// REST URL for an order book depth snapshot
+    fn snapshot_url(&self, symbol: &str, limit: u32) -> String;
+    
+    /// WebSocket URL for incremental depth updates
+    fn diff_stream_url(&self, symbol: &str) -> String;
+    
+    /// Parse one incremental depth update message
+    fn parse_depth_message(&self, message: &str) -> Result<OrderBookUpdate>;
+    
+    /// Fetch an order book depth snapshot from `snapshot_url`
+    fn fetch_snapshot(
+        &self,
+        symbol: &str,
+        limit: u32,
+    ) -> impl Future<Output = Result<OrderBookSnapshot>> + Send;
+    
+    /// Check that the venue's REST API is reachable
+    fn ping(&self) -> impl Future<Output = Result<()>> + Send;
+    
+    /// WebSocket URL for partial-book depth, if the venue offers it
+    fn partial_stream_url(&self, _symbol: &str, _levels: u32) -> Option<String> {
+        None
+    }
+    
+    /// Parse one partial-book depth frame from `partial_stream_url`
+    fn parse_partial_depth_message(&self, message: &str) -> Result<PartialDepth> {
+        Ok(serde_json::from_str(message)?)
+    }
+    
+    /// Depth stream name for a combined URL or a subscription frame
+    fn depth_stream_name(&self, _symbol: &str, _depth_stream: DepthStreamMode) -> Option<String> {
+        None
+    }
+    
+    /// Trade stream name for subscribing on an open connection
+    fn trade_stream_name(&self, _symbol: &str) -> Option<String> {
+        None
+    }
+    
+    /// WebSocket URL carrying several streams, each frame tagged with its stream
+    fn combined_stream_url(&self, _streams: &[String]) -> Option<String> {
+        None
+    }
+    
+    /// Split a combined-stream frame into its uppercase symbol and payload;
+    /// `None` for frames that are not wrapped
+    fn unwrap_combined_frame<'a>(&self, _message: &'a str) -> Option<(String, &'a str)> {
+        None
+    }
+    
+    /// Frame subscribing to (or unsubscribing from) streams on an open connection
+    fn subscription_frame(&self, _subscribe: bool, _streams: Vec<String>, _id: u64) -> Option<String> {
+        None
+    }
+    
+    /// Application-level keepalive frame, if the venue has a cheap request for it
+    fn keepalive_frame(&self, _id: u64) -> Option<String> {
+        None
+    }
+}
@@ -1,22 +1,80 @@
 use crate::config::FairPriceMethod;
-use crate::order_book::OrderBook;
-use std::time::{SystemTime, UNIX_EPOCH};
-use tracing::{debug, warn};
+use crate::order_book::{BookValidity, OrderBook, OrderBookLevel, Side};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::debug;
 
 /// Fair price calculation result
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FairPriceResult {
     pub fair_price: f64,
     pub calculation_method: String,
     pub timestamp: u64,
     pub confidence: f64, // 0.0 to 1.0
     pub spread: f64,
+    pub spread_bps: f64, // spread / mid_price * 10_000
+    pub half_spread: f64,
     pub mid_price: f64,
+    pub smoothed_price: Option<f64>, // EMA of fair price, if enabled
     pub metadata: FairPriceMetadata,
+    pub signal_thresholds: SignalThresholds,
+}
+
+/// Which book imbalance drives `MarketSignal`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ImbalanceBasis {
+    /// Base-asset quantity (`order_flow_imbalance`)
+    #[default]
+    Quantity,
+    
+    /// Quote-asset notional (`notional_imbalance`), comparable across symbols
+    Notional,
+}
+
+/// Thresholds used to classify a result into a `MarketSignal`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SignalThresholds {
+    /// Absolute order flow imbalance above which pressure is signalled
+    pub imbalance: f64,
+    /// Minimum confidence required for a non-neutral signal
+    pub min_confidence: f64,
+    /// Imbalance compared against `imbalance`
+    #[serde(default)]
+    pub basis: ImbalanceBasis,
+}
+
+impl Default for SignalThresholds {
+    fn default() -> Self {
+        Self {
+            imbalance: 0.3,
+            min_confidence: 0.7,
+            basis: ImbalanceBasis::Quantity,
+        }
+    }
+}
+
+/// Minimum liquidity a book needs before `FairPriceCalculator` prices it
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct LiquidityThresholds {
+    /// Minimum resting quantity across both sides of the retained book
+    pub min_total_volume: f64,
+    /// Minimum price levels on each side
+    pub min_levels_per_side: usize,
+}
+
+impl LiquidityThresholds {
+    /// Whether the book is deep enough to price
+    pub fn is_met(&self, order_book: &OrderBook) -> bool {
+        order_book.bids.len() >= self.min_levels_per_side
+            && order_book.asks.len() >= self.min_levels_per_side
+            && order_book.total_bid_volume() + order_book.total_ask_volume() >= self.min_total_volume
+    }
 }
 
 /// Additional metadata for fair price calculation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FairPriceMetadata {
     pub bid_volume: f64,
     pub ask_volume: f64,
@@ -24,15 +82,64 @@ pub struct FairPriceMetadata {
     pub weighted_bid_price: f64,
     pub weighted_ask_price: f64,
     pub order_flow_imbalance: f64, // -1.0 to 1.0 (negative = sell pressure)
+    pub notional_imbalance: f64, // Same as order_flow_imbalance but weighted by price * quantity
     pub depth_ratio: f64, // bid_depth / ask_depth
     pub spread: f64, // Current spread
+    pub effective_spread: f64, // weighted_ask_price - weighted_bid_price over the metadata levels
+    pub book_quality: f64, // 0.0 to 1.0 from spread tightness, depth and balance
+}
+
+/// User-defined pricing model used by `FairPriceCalculator` in place of a `FairPriceMethod`
+///
+/// `WebSocketManager` owns one calculator and reuses it on every tick. Strategies
+/// are held behind an `Arc`, so cloning that calculator shares the model.
+pub trait FairPriceStrategy: Send + Sync {
+    /// Name reported as the result's calculation method
+    fn name(&self) -> String {
+        "Custom".to_string()
+    }
+    
+    /// Return `(fair_price, confidence)` for a valid book
+    fn compute(&self, order_book: &OrderBook, metadata: &FairPriceMetadata) -> (f64, f64);
 }
 
+/// Confidence of a volume-weighted estimate from a book with only one side
+pub const ONE_SIDED_CONFIDENCE: f64 = 0.05;
+
+/// Fair prices kept for trend analysis by default
+pub const DEFAULT_HISTORY_CAPACITY: usize = 1000;
+
+/// Lowest confidence reported for a priceable book by default
+pub const DEFAULT_CONFIDENCE_FLOOR: f64 = 0.1;
+
+/// Recent fair prices used for volatility-scaled confidence
+pub const VOLATILITY_WINDOW: usize = 20;
+
+/// Recent order flow imbalances kept for `imbalance_trend`
+pub const IMBALANCE_WINDOW: usize = 20;
+
+/// Standard deviation of ensemble estimates (bps of mid) that halves the consensus confidence
+pub const ENSEMBLE_DISAGREEMENT_BPS: f64 = 1.0;
+
+/// Levels per side summarized in `FairPriceMetadata` by default
+pub const DEFAULT_METADATA_LEVELS: usize = 5;
+
 /// Fair price calculator with multiple methods
+#[derive(Clone)]
 pub struct FairPriceCalculator {
     method: FairPriceMethod,
     price_history: Vec<f64>, // For trend analysis
     max_history: usize,
+    imbalance_history: VecDeque<f64>, // Last `IMBALANCE_WINDOW` order flow imbalances
+    ema_alpha: Option<f64>, // EMA smoothing factor (0.0, 1.0]
+    smoothed_price: Option<f64>,
+    trade_flow_imbalance: Option<f64>, // Realized flow from executed trades
+    signal_thresholds: SignalThresholds,
+    confidence_floor: f64, // Minimum confidence for a priceable book
+    metadata_levels: usize, // Levels per side summarized in metadata
+    strategy: Option<Arc<dyn FairPriceStrategy>>, // Overrides `method` when set
+    volatility_sensitivity: f64, // Confidence discount per bps of volatility, 0 disables
+    liquidity_thresholds: LiquidityThresholds, // Books below these are not priced
 }
 
 impl FairPriceCalculator {
@@ -40,14 +147,101 @@ impl FairPriceCalculator {
         Self {
             method,
             price_history: Vec::new(),
-            max_history: 1000,
+            max_history: DEFAULT_HISTORY_CAPACITY,
+            imbalance_history: VecDeque::with_capacity(IMBALANCE_WINDOW),
+            ema_alpha: None,
+            smoothed_price: None,
+            trade_flow_imbalance: None,
+            signal_thresholds: SignalThresholds::default(),
+            confidence_floor: DEFAULT_CONFIDENCE_FLOOR,
+            metadata_levels: DEFAULT_METADATA_LEVELS,
+            strategy: None,
+            volatility_sensitivity: 0.0,
+            liquidity_thresholds: LiquidityThresholds::default(),
+        }
+    }
+    
+    /// Create a calculator pricing with a user-defined strategy
+    pub fn with_strategy(strategy: Arc<dyn FairPriceStrategy>) -> Self {
+        Self {
+            strategy: Some(strategy),
+            ..Self::new(FairPriceMethod::MidPrice)
+        }
+    }
+    
+    /// Price with a user-defined strategy instead of the method, or go back to the method
+    pub fn set_strategy(&mut self, strategy: Option<Arc<dyn FairPriceStrategy>>) {
+        self.strategy = strategy;
+    }
+    
+    /// User-defined strategy in use, if any
+    pub fn strategy(&self) -> Option<&Arc<dyn FairPriceStrategy>> {
+        self.strategy.as_ref()
+    }
+    
+    /// Create a calculator keeping at most `capacity` fair prices (at least 1)
+    pub fn with_history_capacity(method: FairPriceMethod, capacity: usize) -> Self {
+        Self {
+            max_history: capacity.max(1),
+            ..Self::new(method)
+        }
+    }
+    
+    /// Maximum number of fair prices kept in history
+    pub fn history_capacity(&self) -> usize {
+        self.max_history
+    }
+    
+    /// Create a calculator summarizing the top `levels` per side in metadata (at least 1)
+    pub fn with_metadata_levels(method: FairPriceMethod, levels: usize) -> Self {
+        Self {
+            metadata_levels: levels.max(1),
+            ..Self::new(method)
+        }
+    }
+    
+    /// Levels per side summarized in metadata
+    pub fn metadata_levels(&self) -> usize {
+        self.metadata_levels
+    }
+    
+    /// Recorded fair prices, oldest first
+    pub fn price_history(&self) -> &[f64] {
+        &self.price_history
+    }
+    
+    /// Number of fair prices currently in history
+    pub fn history_len(&self) -> usize {
+        self.price_history.len()
+    }
+    
+    /// Create a calculator that also outputs an EMA-smoothed fair price
+    pub fn with_ema(method: FairPriceMethod, alpha: f64) -> Self {
+        Self {
+            ema_alpha: Some(alpha.clamp(f64::EPSILON, 1.0)),
+            ..Self::new(method)
         }
     }
     
     /// Calculate fair price from order book
+    ///
+    /// Returns `None` for books below the liquidity thresholds or that cannot
+    /// be priced, except that
+    /// `VolumeWeighted` still prices a book with only one side (see
+    /// `ONE_SIDED_CONFIDENCE`); such results report the side's VWAP as the mid
+    /// and a zero spread.
     pub fn calculate(&mut self, order_book: &OrderBook) -> Option<FairPriceResult> {
-        if !order_book.is_valid() {
-            warn!("Invalid order book state");
+        let validity = order_book.validity();
+        let one_sided = matches!(validity, BookValidity::NoBids | BookValidity::NoAsks)
+            && self.strategy.is_none()
+            && matches!(self.method, FairPriceMethod::VolumeWeighted { .. })
+            && (!order_book.bids.is_empty() || !order_book.asks.is_empty());
+        if validity != BookValidity::Valid && !one_sided {
+            debug!("Invalid order book state for {}: {}", order_book.symbol, validity);
+            return None;
+        }
+        if !self.liquidity_thresholds.is_met(order_book) {
+            debug!("Order book for {} is below the liquidity thresholds", order_book.symbol);
             return None;
         }
         
@@ -56,66 +250,215 @@ impl FairPriceCalculator {
             .unwrap()
             .as_micros() as u64;
         
-        let mid_price = order_book.mid_price()?;
-        let spread = order_book.spread()?;
+        let (mid_price, spread) = match (order_book.mid_price(), order_book.spread()) {
+            (Some(mid_price), Some(spread)) => (mid_price, spread),
+            // No touch on the missing side; the mid is replaced by the fair price below
+            _ if one_sided => (0.0, 0.0),
+            _ => return None,
+        };
+        let spread_bps = ratio_to_mid(spread, mid_price) * 10_000.0;
+        let half_spread = spread / 2.0;
         
         // Calculate metadata first
         let metadata = self.calculate_metadata(order_book, spread);
         
-        // Calculate fair price based on selected method
-        let (fair_price, confidence) = match &self.method {
-            FairPriceMethod::MidPrice => {
-                (mid_price, self.calculate_mid_price_confidence(&metadata))
-            }
-            FairPriceMethod::VolumeWeighted { levels } => {
-                self.calculate_volume_weighted(order_book, *levels)
-            }
-            FairPriceMethod::MicroPrice => {
-                self.calculate_micro_price(order_book, &metadata)
-            }
+        // Calculate fair price based on selected strategy or method
+        let (fair_price, confidence) = match &self.strategy {
+            Some(strategy) => strategy.compute(order_book, &metadata),
+            None => self.compute_method(&self.method, order_book, &metadata, mid_price),
+        };
+        
+        let mid_price = if one_sided { fair_price } else { mid_price };
+        
+        // Penalize books whose shallow and deep imbalance disagree, without
+        // dropping a floored confidence below the floor
+        let scaled_confidence =
+            confidence * Self::depth_consistency(order_book) * self.volatility_factor(mid_price);
+        let confidence = if confidence >= self.confidence_floor {
+            scaled_confidence.max(self.confidence_floor)
+        } else {
+            scaled_confidence
         };
         
-        // Update price history
+        // Update price and imbalance history
         self.update_price_history(fair_price);
+        self.update_imbalance_history(metadata.order_flow_imbalance);
+        let smoothed_price = self.update_ema(fair_price);
         
         let result = FairPriceResult {
             fair_price,
-            calculation_method: self.method.to_string(),
+            calculation_method: match &self.strategy {
+                Some(strategy) => strategy.name(),
+                None => self.method.to_string(),
+            },
             timestamp,
             confidence,
             spread,
+            spread_bps,
+            half_spread,
             mid_price,
+            smoothed_price,
             metadata,
+            signal_thresholds: self.signal_thresholds,
         };
         
-        debug!(
-            "Fair price calculated: {:.4} (method: {}, confidence: {:.2})",
-            result.fair_price, result.calculation_method, result.confidence
-        );
+        self.log_result(&result);
         
         Some(result)
     }
     
-    /// Calculate volume-weighted average price
-    fn calculate_volume_weighted(&self, order_book: &OrderBook, levels: usize) -> (f64, f64) {
-        let (top_bids, top_asks) = order_book.get_top_levels(levels);
-        
-        if top_bids.is_empty() || top_asks.is_empty() {
-            return (order_book.mid_price().unwrap_or(0.0), 0.0);
+    /// Raw `(fair_price, confidence)` of one method, before book-wide confidence scaling
+    fn compute_method(
+        &self,
+        method: &FairPriceMethod,
+        order_book: &OrderBook,
+        metadata: &FairPriceMetadata,
+        mid_price: f64,
+    ) -> (f64, f64) {
+        match method {
+            FairPriceMethod::MidPrice => {
+                (mid_price, self.calculate_mid_price_confidence(metadata))
+            }
+            FairPriceMethod::VolumeWeighted { levels } => {
+                self.calculate_volume_weighted(order_book, *levels)
+            }
+            FairPriceMethod::MicroPrice => {
+                self.calculate_micro_price(order_book, metadata)
+            }
+            FairPriceMethod::ExecutionVwap { notional } => {
+                self.calculate_execution_vwap(order_book, *notional)
+            }
+            FairPriceMethod::BookPressure { levels } => {
+                self.calculate_book_pressure(order_book, *levels)
+            }
+            FairPriceMethod::VolumeWeightedDecay { levels, k } => {
+                self.calculate_volume_weighted_decay(order_book, *levels, *k)
+            }
+            FairPriceMethod::GeometricMid => {
+                (self.calculate_geometric_mid(order_book), self.calculate_mid_price_confidence(metadata))
+            }
+            FairPriceMethod::Ensemble => {
+                // `all` lists only the concrete methods, so this never recurses further
+                let estimates: Vec<(f64, f64)> = FairPriceMethod::all()
+                    .iter()
+                    .map(|method| self.compute_method(method, order_book, metadata, mid_price))
+                    .collect();
+                Self::blend_estimates(&estimates, mid_price)
+                    .map_or((mid_price, 0.0), |(price, confidence)| (price, confidence.max(self.confidence_floor)))
+            }
+        }
+    }
+    
+    /// Blend `(fair_price, confidence)` estimates into one consensus
+    ///
+    /// The price is the confidence-weighted mean. The confidence is the mean
+    /// input confidence scaled by `1 / (1 + dispersion_bps / ENSEMBLE_DISAGREEMENT_BPS)`,
+    /// where dispersion is the weighted standard deviation of the prices in bps
+    /// of mid. Estimates with no confidence or no price are left out; `None`
+    /// when none remain.
+    fn blend_estimates(estimates: &[(f64, f64)], mid_price: f64) -> Option<(f64, f64)> {
+        let usable: Vec<(f64, f64)> = estimates
+            .iter()
+            .copied()
+            .filter(|(price, confidence)| price.is_finite() && *price > 0.0 && confidence.is_finite() && *confidence > 0.0)
+            .collect();
+        if usable.is_empty() {
+            return None;
         }
         
-        // Calculate volume-weighted bid price
-        let (bid_sum, bid_volume) = top_bids.iter().fold((0.0, 0.0), |acc, level| {
-            (acc.0 + level.price.0 * level.quantity, acc.1 + level.quantity)
-        });
+        let total_weight: f64 = usable.iter().map(|(_, confidence)| confidence).sum();
+        let consensus = usable.iter().map(|(price, confidence)| price * confidence).sum::<f64>() / total_weight;
+        let variance = usable
+            .iter()
+            .map(|(price, confidence)| confidence * (price - consensus).powi(2))
+            .sum::<f64>()
+            / total_weight;
         
-        // Calculate volume-weighted ask price
-        let (ask_sum, ask_volume) = top_asks.iter().fold((0.0, 0.0), |acc, level| {
-            (acc.0 + level.price.0 * level.quantity, acc.1 + level.quantity)
-        });
+        let dispersion_bps = ratio_to_mid(variance.sqrt(), mid_price) * 10_000.0;
+        let agreement = 1.0 / (1.0 + dispersion_bps / ENSEMBLE_DISAGREEMENT_BPS);
+        let mean_confidence = total_weight / usable.len() as f64;
         
-        if bid_volume == 0.0 || ask_volume == 0.0 {
-            return (order_book.mid_price().unwrap_or(0.0), 0.0);
+        Some((consensus, mean_confidence * agreement))
+    }
+    
+    /// Log a result under a per-method `fairprice.*` target
+    ///
+    /// Lets log filters such as `fairprice.micro=debug` pick out one method,
+    /// e.g. from `calculate_all`. Targets must be literals, hence the match.
+    fn log_result(&self, result: &FairPriceResult) {
+        macro_rules! log_to {
+            ($target:literal) => {
+                debug!(
+                    target: $target,
+                    "Fair price calculated: {:.4} (method: {}, confidence: {:.2})",
+                    result.fair_price, result.calculation_method, result.confidence
+                )
+            };
+        }
+        
+        match (&self.strategy, &self.method) {
+            (Some(_), _) => log_to!("fairprice.custom"),
+            (None, FairPriceMethod::MidPrice) => log_to!("fairprice.mid"),
+            (None, FairPriceMethod::VolumeWeighted { .. }) => log_to!("fairprice.vwap"),
+            (None, FairPriceMethod::MicroPrice) => log_to!("fairprice.micro"),
+            (None, FairPriceMethod::ExecutionVwap { .. }) => log_to!("fairprice.execution_vwap"),
+            (None, FairPriceMethod::BookPressure { .. }) => log_to!("fairprice.book_pressure"),
+            (None, FairPriceMethod::VolumeWeightedDecay { .. }) => log_to!("fairprice.vwap_decay"),
+            (None, FairPriceMethod::GeometricMid) => log_to!("fairprice.geometric_mid"),
+            (None, FairPriceMethod::Ensemble) => log_to!("fairprice.ensemble"),
+        }
+    }
+    
+    /// Calculate every method (`FairPriceMethod::all`) against the same book
+    ///
+    /// Uses fresh calculators sharing this one's trade flow and signal
+    /// thresholds, so the configured method and history are left untouched.
+    /// A user-defined strategy is not included.
+    pub fn calculate_all(&self, order_book: &OrderBook) -> Vec<FairPriceResult> {
+        FairPriceMethod::all()
+            .into_iter()
+            .filter_map(|method| {
+                let mut calculator = FairPriceCalculator::new(method);
+                calculator.trade_flow_imbalance = self.trade_flow_imbalance;
+                calculator.signal_thresholds = self.signal_thresholds;
+                calculator.confidence_floor = self.confidence_floor;
+                calculator.metadata_levels = self.metadata_levels;
+                calculator.volatility_sensitivity = self.volatility_sensitivity;
+                calculator.liquidity_thresholds = self.liquidity_thresholds;
+                calculator.calculate(order_book)
+            })
+            .collect()
+    }
+    
+    /// Geometric mean of the best bid and ask, falling back to the arithmetic mid
+    fn calculate_geometric_mid(&self, order_book: &OrderBook) -> f64 {
+        match (order_book.best_bid(), order_book.best_ask()) {
+            (Some(bid), Some(ask)) if bid.price.0 > 0.0 && ask.price.0 > 0.0 => {
+                (bid.price.0 * ask.price.0).sqrt()
+            }
+            _ => order_book.mid_price().unwrap_or(0.0),
+        }
+    }
+    
+    /// Calculate volume-weighted average price
+    ///
+    /// When only one side has liquidity, returns that side's VWAP with
+    /// `ONE_SIDED_CONFIDENCE`: informative, but too weak to drive a signal.
+    fn calculate_volume_weighted(&self, order_book: &OrderBook, levels: usize) -> (f64, f64) {
+        // Notional and quantity of each side's top levels
+        let side_sums = |side: Side| {
+            order_book.depth(side, levels).iter().fold((0.0, 0.0), |acc, level| {
+                (acc.0 + level.price.0 * level.quantity, acc.1 + level.quantity)
+            })
+        };
+        let (bid_sum, bid_volume) = side_sums(Side::Bid);
+        let (ask_sum, ask_volume) = side_sums(Side::Ask);
+        
+        match (bid_volume > 0.0, ask_volume > 0.0) {
+            (true, true) => {}
+            (true, false) => return (bid_sum / bid_volume, ONE_SIDED_CONFIDENCE),
+            (false, true) => return (ask_sum / ask_volume, ONE_SIDED_CONFIDENCE),
+            (false, false) => return (order_book.mid_price().unwrap_or(0.0), 0.0),
         }
         
         let weighted_bid = bid_sum / bid_volume;
@@ -129,7 +472,39 @@ impl FairPriceCalculator {
         let volume_balance = (bid_volume - ask_volume).abs() / total_volume;
         let confidence = 1.0 - volume_balance; // Higher confidence when volumes are balanced
         
-        (fair_price, confidence.max(0.1))
+        (fair_price, confidence.max(self.confidence_floor))
+    }
+    
+    /// Calculate volume-weighted price with levels decayed by relative distance from mid
+    fn calculate_volume_weighted_decay(&self, order_book: &OrderBook, levels: usize, k: f64) -> (f64, f64) {
+        let (top_bids, top_asks) = order_book.get_top_levels(levels);
+        let mid_price = match order_book.mid_price() {
+            Some(mid_price) if mid_price > 0.0 && !top_bids.is_empty() && !top_asks.is_empty() => mid_price,
+            _ => return (order_book.mid_price().unwrap_or(0.0), 0.0),
+        };
+        
+        let decayed = |levels: &[&OrderBookLevel]| {
+            levels.iter().fold((0.0, 0.0), |(price_sum, weight_sum), level| {
+                let distance = (level.price.0 - mid_price).abs() / mid_price;
+                let weight = level.quantity * (-k * distance).exp();
+                (price_sum + level.price.0 * weight, weight_sum + weight)
+            })
+        };
+        
+        let (bid_sum, bid_weight) = decayed(&top_bids);
+        let (ask_sum, ask_weight) = decayed(&top_asks);
+        let total_weight = bid_weight + ask_weight;
+        
+        if bid_weight == 0.0 || ask_weight == 0.0 {
+            return (mid_price, 0.0);
+        }
+        
+        let fair_price = (bid_sum + ask_sum) / total_weight;
+        
+        // Confidence based on decayed volume balance
+        let confidence = 1.0 - (bid_weight - ask_weight).abs() / total_weight;
+        
+        (fair_price, confidence.max(self.confidence_floor))
     }
     
     /// Calculate micro-price (considers order flow imbalance)
@@ -155,21 +530,107 @@ impl FairPriceCalculator {
         // Weight towards the side with more liquidity
         let micro_price = (ask_price * bid_qty + bid_price * ask_qty) / total_qty;
         
-        // Adjust for order flow imbalance
-        let imbalance_adjustment = metadata.order_flow_imbalance * (ask_price - bid_price) * 0.1;
+        // Adjust for order flow imbalance, preferring realized trade flow
+        let imbalance = self.trade_flow_imbalance.unwrap_or(metadata.order_flow_imbalance);
+        let imbalance_adjustment = imbalance * (ask_price - bid_price) * 0.1;
         let adjusted_price = micro_price + imbalance_adjustment;
         
         // Confidence based on liquidity balance and spread tightness
         let qty_balance = 1.0 - (bid_qty - ask_qty).abs() / total_qty;
-        let spread_tightness = 1.0 / (1.0 + metadata.spread / order_book.mid_price().unwrap_or(1.0));
-        let confidence = (qty_balance * 0.7 + spread_tightness * 0.3).max(0.1);
+        let relative_spread = ratio_to_mid(metadata.spread, order_book.mid_price().unwrap_or(0.0));
+        let spread_tightness = 1.0 / (1.0 + relative_spread);
+        let confidence = (qty_balance * 0.7 + spread_tightness * 0.3).max(self.confidence_floor);
         
         (adjusted_price, confidence)
     }
     
+    /// Calculate a pressure-adjusted price from the top N levels
+    ///
+    /// Each level is weighted by `quantity / distance_from_mid`, so liquidity
+    /// near the touch dominates and deep far-away orders barely move the price.
+    fn calculate_book_pressure(&self, order_book: &OrderBook, levels: usize) -> (f64, f64) {
+        let (top_bids, top_asks) = order_book.get_top_levels(levels);
+        let mid_price = match order_book.mid_price() {
+            Some(mid_price) if !top_bids.is_empty() && !top_asks.is_empty() => mid_price,
+            _ => return (order_book.mid_price().unwrap_or(0.0), 0.0),
+        };
+        
+        // Touch levels sit half a spread from mid; guard against a zero distance
+        let min_distance = f64::EPSILON * mid_price.abs().max(1.0);
+        let pressure = |levels: &[&OrderBookLevel]| {
+            levels.iter().fold((0.0, 0.0), |(price_sum, weight_sum), level| {
+                let weight = level.quantity / (level.price.0 - mid_price).abs().max(min_distance);
+                (price_sum + level.price.0 * weight, weight_sum + weight)
+            })
+        };
+        
+        let (bid_sum, bid_pressure) = pressure(&top_bids);
+        let (ask_sum, ask_pressure) = pressure(&top_asks);
+        let total_pressure = bid_pressure + ask_pressure;
+        
+        if total_pressure == 0.0 {
+            return (mid_price, 0.0);
+        }
+        
+        let fair_price = (bid_sum + ask_sum) / total_pressure;
+        
+        // Confidence based on how balanced the near-touch pressure is
+        let confidence = 1.0 - (bid_pressure - ask_pressure).abs() / total_pressure;
+        
+        (fair_price, confidence.max(self.confidence_floor))
+    }
+    
+    /// Calculate the average execution price for a target notional on each side
+    fn calculate_execution_vwap(&self, order_book: &OrderBook, notional: f64) -> (f64, f64) {
+        let bids: Vec<&OrderBookLevel> = order_book.bids.values().rev().collect();
+        let asks: Vec<&OrderBookLevel> = order_book.asks.values().collect();
+        
+        let (bid_vwap, bid_filled) = Self::vwap_to_fill(&bids, notional);
+        let (ask_vwap, ask_filled) = Self::vwap_to_fill(&asks, notional);
+        
+        if bid_filled == 0.0 || ask_filled == 0.0 || notional <= 0.0 {
+            return (order_book.mid_price().unwrap_or(0.0), 0.0);
+        }
+        
+        let fair_price = (bid_vwap + ask_vwap) / 2.0;
+        
+        // Confidence based on how much of the requested notional was fillable
+        let fill_ratio = (bid_filled + ask_filled) / (2.0 * notional);
+        
+        (fair_price, fill_ratio.clamp(self.confidence_floor, 1.0))
+    }
+    
+    /// Walk levels from the best price until `notional` is filled
+    ///
+    /// Returns `(vwap, filled_notional)`.
+    fn vwap_to_fill(levels: &[&OrderBookLevel], notional: f64) -> (f64, f64) {
+        let mut remaining = notional;
+        let mut filled_notional = 0.0;
+        let mut filled_qty = 0.0;
+        
+        for level in levels {
+            if remaining <= 0.0 {
+                break;
+            }
+            
+            let level_notional = level.price.0 * level.quantity;
+            let take = level_notional.min(remaining);
+            
+            filled_notional += take;
+            filled_qty += take / level.price.0;
+            remaining -= take;
+        }
+        
+        if filled_qty == 0.0 {
+            return (0.0, 0.0);
+        }
+        
+        (filled_notional / filled_qty, filled_notional)
+    }
+    
     /// Calculate metadata for fair price analysis
     fn calculate_metadata(&self, order_book: &OrderBook, spread: f64) -> FairPriceMetadata {
-        let (top_bids, top_asks) = order_book.get_top_levels(5);
+        let (top_bids, top_asks) = order_book.get_top_levels(self.metadata_levels);
         
         // Calculate volumes
         let bid_volume: f64 = top_bids.iter().map(|level| level.quantity).sum();
@@ -196,6 +657,15 @@ impl FairPriceCalculator {
             0.0
         };
         
+        // Same imbalance in quote notional, so pricier liquidity counts for more
+        let bid_notional: f64 = top_bids.iter().map(|level| level.price.0 * level.quantity).sum();
+        let ask_notional: f64 = top_asks.iter().map(|level| level.price.0 * level.quantity).sum();
+        let notional_imbalance = if bid_notional + ask_notional > 0.0 {
+            (bid_notional - ask_notional) / (bid_notional + ask_notional)
+        } else {
+            0.0
+        };
+        
         // Depth ratio
         let depth_ratio = if ask_volume > 0.0 {
             bid_volume / ask_volume
@@ -203,6 +673,20 @@ impl FairPriceCalculator {
             f64::INFINITY
         };
         
+        // Cost of sweeping the top `metadata_levels` levels rather than just the touch
+        let effective_spread = if bid_volume > 0.0 && ask_volume > 0.0 {
+            weighted_ask_price - weighted_bid_price
+        } else {
+            spread
+        };
+        
+        let book_quality = Self::book_quality(
+            effective_spread,
+            order_book.mid_price().unwrap_or(0.0),
+            total_volume,
+            order_flow_imbalance,
+        );
+        
         FairPriceMetadata {
             bid_volume,
             ask_volume,
@@ -210,9 +694,73 @@ impl FairPriceCalculator {
             weighted_bid_price,
             weighted_ask_price,
             order_flow_imbalance,
+            notional_imbalance,
             depth_ratio,
             spread,
+            effective_spread,
+            book_quality,
+        }
+    }
+    
+    /// Combine spread tightness, depth and balance into a 0-1 quality score
+    fn book_quality(effective_spread: f64, mid_price: f64, total_volume: f64, imbalance: f64) -> f64 {
+        if mid_price <= 0.0 {
+            return 0.0;
         }
+        
+        // 1. Tightness: 10 bps of effective spread halves the score
+        let effective_spread_bps = effective_spread.max(0.0) / mid_price * 10_000.0;
+        let tightness = 1.0 / (1.0 + effective_spread_bps / 10.0);
+        
+        // 2. Depth (same scale as the mid-price liquidity factor)
+        let depth = total_volume / (total_volume + 100.0);
+        
+        // 3. Balance between bid and ask depth
+        let balance = 1.0 - imbalance.abs();
+        
+        (tightness * 0.4 + depth * 0.3 + balance * 0.3).clamp(0.0, 1.0)
+    }
+    
+    /// Agreement of volume imbalance sampled at 1, 5 and 20 levels (0.0 to 1.0)
+    ///
+    /// A balanced touch over a heavily skewed deeper book makes for fragile
+    /// pricing, so the widest disagreement between the samples is penalized.
+    fn depth_consistency(order_book: &OrderBook) -> f64 {
+        let imbalances: Vec<f64> = [1, 5, 20]
+            .iter()
+            .filter_map(|&levels| {
+                let volume = |side: Side| order_book.depth(side, levels).iter().map(|level| level.quantity).sum::<f64>();
+                let (bid_volume, ask_volume) = (volume(Side::Bid), volume(Side::Ask));
+                let total_volume = bid_volume + ask_volume;
+                (total_volume > 0.0).then(|| (bid_volume - ask_volume) / total_volume)
+            })
+            .collect();
+            
+        if imbalances.is_empty() {
+            return 1.0;
+        }
+        
+        let max = imbalances.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let min = imbalances.iter().cloned().fold(f64::INFINITY, f64::min);
+        
+        // Imbalances span -1.0 to 1.0, so the range is at most 2.0
+        (1.0 - (max - min) / 2.0).clamp(0.0, 1.0)
+    }
+    
+    /// Confidence multiplier for the current volatility regime (0.0 to 1.0)
+    ///
+    /// Uses the volatility of the last `VOLATILITY_WINDOW` fair prices in bps
+    /// of mid: `1 / (1 + sensitivity × volatility_bps)`. Returns 1.0 when
+    /// disabled or with fewer than two prices in history.
+    fn volatility_factor(&self, mid_price: f64) -> f64 {
+        if self.volatility_sensitivity == 0.0 || self.price_history.len() < 2 {
+            return 1.0;
+        }
+        
+        let window = self.price_history.len().min(VOLATILITY_WINDOW);
+        let volatility = self.get_price_volatility(window).unwrap_or(0.0);
+        let volatility_bps = ratio_to_mid(volatility, mid_price) * 10_000.0;
+        1.0 / (1.0 + self.volatility_sensitivity * volatility_bps)
     }
     
     /// Calculate confidence for mid-price method
@@ -236,7 +784,7 @@ impl FairPriceCalculator {
         };
         
         // Weighted combination
-        (volume_balance * 0.4 + liquidity_factor * 0.3 + spread_factor * 0.3).max(0.1)
+        (volume_balance * 0.4 + liquidity_factor * 0.3 + spread_factor * 0.3).max(self.confidence_floor)
     }
     
     /// Update price history for trend analysis
@@ -247,6 +795,46 @@ impl FairPriceCalculator {
         }
     }
     
+    /// Update the EMA with a new fair price (the first sample seeds it)
+    fn update_ema(&mut self, price: f64) -> Option<f64> {
+        let alpha = self.ema_alpha?;
+        let smoothed = match self.smoothed_price {
+            Some(previous) => alpha * price + (1.0 - alpha) * previous,
+            None => price,
+        };
+        self.smoothed_price = Some(smoothed);
+        Some(smoothed)
+    }
+    
+    /// Get current EMA-smoothed fair price
+    pub fn get_smoothed_price(&self) -> Option<f64> {
+        self.smoothed_price
+    }
+    
+    /// Record an order flow imbalance, dropping the oldest past `IMBALANCE_WINDOW`
+    fn update_imbalance_history(&mut self, imbalance: f64) {
+        if self.imbalance_history.len() == IMBALANCE_WINDOW {
+            self.imbalance_history.pop_front();
+        }
+        self.imbalance_history.push_back(imbalance);
+    }
+    
+    /// Recent order flow imbalances, oldest first
+    pub fn imbalance_series(&self) -> Vec<f64> {
+        self.imbalance_history.iter().copied().collect()
+    }
+    
+    /// Change in order flow imbalance across the recent window (newest minus oldest)
+    ///
+    /// Positive when buy pressure is building, even if the level itself is
+    /// still modest. `None` until two imbalances have been recorded.
+    pub fn imbalance_trend(&self) -> Option<f64> {
+        if self.imbalance_history.len() < 2 {
+            return None;
+        }
+        Some(self.imbalance_history.back()? - self.imbalance_history.front()?)
+    }
+    
     /// Get price volatility from recent history
     pub fn get_price_volatility(&self, window: usize) -> Option<f64> {
         if self.price_history.len() < window {
@@ -293,11 +881,144 @@ impl FairPriceCalculator {
         Some((last_price - first_price) / first_price)
     }
     
+    /// Get least-squares trend slope over recent history
+    ///
+    /// Returns the fitted price change per sample relative to the window mean
+    /// (positive = upward), which is far less noise-sensitive than the
+    /// two-point `get_price_trend`.
+    pub fn get_price_trend_regression(&self, window: usize) -> Option<f64> {
+        if window < 2 || self.price_history.len() < window {
+            return None;
+        }
+        
+        // Oldest to newest
+        let recent_prices = &self.price_history[self.price_history.len() - window..];
+        
+        let n = window as f64;
+        let mean_x = (n - 1.0) / 2.0;
+        let mean_y = recent_prices.iter().sum::<f64>() / n;
+        
+        let (covariance, variance_x) = recent_prices
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |acc, (i, price)| {
+                let dx = i as f64 - mean_x;
+                (acc.0 + dx * (price - mean_y), acc.1 + dx * dx)
+            });
+            
+        if variance_x == 0.0 || mean_y == 0.0 {
+            return None;
+        }
+        
+        Some(covariance / variance_x / mean_y)
+    }
+    
+    /// Get z-score of the latest fair price against the recent window
+    ///
+    /// Returns `None` when the window is underfilled or volatility is zero.
+    pub fn price_zscore(&self, window: usize) -> Option<f64> {
+        let volatility = self.get_price_volatility(window)?;
+        if volatility == 0.0 || window == 0 {
+            return None;
+        }
+        
+        let recent_prices = &self.price_history[self.price_history.len() - window..];
+        let mean = recent_prices.iter().sum::<f64>() / window as f64;
+        let current = *self.price_history.last()?;
+        
+        Some((current - mean) / volatility)
+    }
+    
+    /// Get time-weighted average of the last `window` fair prices
+    ///
+    /// Assumes roughly regular calculation ticks, so each sample carries equal weight.
+    pub fn twap(&self, window: usize) -> Option<f64> {
+        if window == 0 || self.price_history.len() < window {
+            return None;
+        }
+        
+        let recent_prices = &self.price_history[self.price_history.len() - window..];
+        Some(recent_prices.iter().sum::<f64>() / window as f64)
+    }
+    
+    /// Set realized trade flow imbalance (-1.0 to 1.0) used by micro-price
+    pub fn set_trade_flow_imbalance(&mut self, imbalance: Option<f64>) {
+        self.trade_flow_imbalance = imbalance.map(|value| value.clamp(-1.0, 1.0));
+    }
+    
+    /// Set thresholds used to classify market signals on results
+    pub fn set_signal_thresholds(&mut self, thresholds: SignalThresholds) {
+        self.signal_thresholds = thresholds;
+    }
+    
+    /// Get current signal thresholds
+    pub fn get_signal_thresholds(&self) -> SignalThresholds {
+        self.signal_thresholds
+    }
+    
+    /// Drive market signals from quantity or quote-notional imbalance
+    ///
+    /// Notional imbalance means the same thing for BTC and DOGE, so a shared
+    /// threshold classifies different symbols consistently.
+    pub fn set_imbalance_basis(&mut self, basis: ImbalanceBasis) {
+        self.signal_thresholds.basis = basis;
+    }
+    
+    /// Get the imbalance basis used for market signals
+    pub fn get_imbalance_basis(&self) -> ImbalanceBasis {
+        self.signal_thresholds.basis
+    }
+    
+    /// Set the minimum confidence reported for a priceable book (0.0 to 1.0)
+    pub fn set_confidence_floor(&mut self, floor: f64) {
+        self.confidence_floor = if floor.is_nan() { DEFAULT_CONFIDENCE_FLOOR } else { floor.clamp(0.0, 1.0) };
+    }
+    
+    /// Get the confidence floor
+    pub fn get_confidence_floor(&self) -> f64 {
+        self.confidence_floor
+    }
+    
+    /// Set the liquidity a book needs before it is priced
+    pub fn set_liquidity_thresholds(&mut self, thresholds: LiquidityThresholds) {
+        self.liquidity_thresholds = thresholds;
+    }
+    
+    /// Get the liquidity thresholds
+    pub fn get_liquidity_thresholds(&self) -> LiquidityThresholds {
+        self.liquidity_thresholds
+    }
+    
+    /// Discount confidence by recent volatility; e.g. 0.1 halves it at 10 bps (0 disables)
+    pub fn set_volatility_sensitivity(&mut self, sensitivity: f64) {
+        self.volatility_sensitivity = if sensitivity.is_finite() { sensitivity.max(0.0) } else { 0.0 };
+    }
+    
+    /// Get the volatility sensitivity
+    pub fn get_volatility_sensitivity(&self) -> f64 {
+        self.volatility_sensitivity
+    }
+    
     /// Update calculation method
     pub fn set_method(&mut self, method: FairPriceMethod) {
         self.method = method;
     }
     
+    /// Update calculation method and discard history computed with the old one
+    pub fn set_method_and_reset(&mut self, method: FairPriceMethod) {
+        self.set_method(method);
+        self.reset();
+    }
+    
+    /// Clear price history and the EMA, keeping the method and settings
+    ///
+    /// Use after a stream reset so trend and volatility don't span the gap.
+    pub fn reset(&mut self) {
+        self.price_history.clear();
+        self.imbalance_history.clear();
+        self.smoothed_price = None;
+    }
+    
     /// Get current method
     pub fn get_method(&self) -> &FairPriceMethod {
         &self.method
@@ -308,27 +1029,44 @@ impl FairPriceResult {
     /// Get human-readable summary
     pub fn summary(&self) -> String {
         format!(
-            "Fair Price: ${:.4} | Method: {} | Confidence: {:.1}% | Spread: ${:.4} | Flow: {:.2}",
+            "Fair Price: ${:.4} | Method: {} | Confidence: {:.1}% | Spread: ${:.4} ({:.2} bps) | Flow: {:.2}",
             self.fair_price,
             self.calculation_method,
             self.confidence * 100.0,
             self.spread,
+            self.spread_bps,
             self.metadata.order_flow_imbalance
         )
     }
     
     /// Check if result indicates strong buy/sell signal
     pub fn market_signal(&self) -> MarketSignal {
-        let imbalance = self.metadata.order_flow_imbalance;
-        let confidence_threshold = 0.7;
+        self.market_signal_with(&self.signal_thresholds)
+    }
+    
+    /// Imbalance on the basis configured in the result's thresholds
+    pub fn signal_imbalance(&self) -> f64 {
+        self.imbalance_on(self.signal_thresholds.basis)
+    }
+    
+    fn imbalance_on(&self, basis: ImbalanceBasis) -> f64 {
+        match basis {
+            ImbalanceBasis::Quantity => self.metadata.order_flow_imbalance,
+            ImbalanceBasis::Notional => self.metadata.notional_imbalance,
+        }
+    }
+    
+    /// Classify the market signal using custom thresholds
+    pub fn market_signal_with(&self, thresholds: &SignalThresholds) -> MarketSignal {
+        let imbalance = self.imbalance_on(thresholds.basis);
         
-        if self.confidence < confidence_threshold {
+        if self.confidence < thresholds.min_confidence {
             return MarketSignal::Neutral;
         }
         
-        if imbalance > 0.3 {
+        if imbalance > thresholds.imbalance {
             MarketSignal::BuyPressure
-        } else if imbalance < -0.3 {
+        } else if imbalance < -thresholds.imbalance {
             MarketSignal::SellPressure
         } else {
             MarketSignal::Balanced
@@ -356,25 +1094,148 @@ impl std::fmt::Display for MarketSignal {
     }
 }
 
+/// Holds back a new `MarketSignal` until it persists, so marginal ticks don't flip it
+///
+/// A candidate signal is adopted after `min_ticks` consecutive ticks, or once
+/// it has lasted `min_dwell`, whichever comes first. Any other signal in
+/// between restarts the count.
+#[derive(Debug, Clone)]
+pub struct SignalDebouncer {
+    min_ticks: usize,
+    min_dwell: Option<Duration>,
+    reported: Option<MarketSignal>,
+    candidate: Option<(MarketSignal, usize, u64)>, // Signal, consecutive ticks, first seen (us)
+}
+
+impl SignalDebouncer {
+    /// Adopt a new signal after `min_ticks` consecutive ticks (1 reports every flip)
+    pub fn new(min_ticks: usize) -> Self {
+        Self {
+            min_ticks: min_ticks.max(1),
+            min_dwell: None,
+            reported: None,
+            candidate: None,
+        }
+    }
+    
+    /// Also adopt a new signal once it has persisted for `min_dwell`
+    pub fn with_min_dwell(mut self, min_dwell: Duration) -> Self {
+        self.min_dwell = Some(min_dwell);
+        self
+    }
+    
+    /// Forget the reported signal and any candidate, keeping the thresholds
+    pub fn reset(&mut self) {
+        self.reported = None;
+        self.candidate = None;
+    }
+    
+    /// Currently reported signal, `None` before the first tick
+    pub fn reported(&self) -> Option<&MarketSignal> {
+        self.reported.as_ref()
+    }
+    
+    /// Feed one tick's signal (timestamp in microseconds) and return the reported signal
+    pub fn update(&mut self, signal: MarketSignal, timestamp_us: u64) -> MarketSignal {
+        let reported = match &self.reported {
+            Some(reported) => reported.clone(),
+            None => {
+                self.reported = Some(signal.clone());
+                return signal;
+            }
+        };
+        if signal == reported {
+            self.candidate = None;
+            return reported;
+        }
+        
+        let (ticks, since_us) = match self.candidate.take() {
+            Some((candidate, ticks, since_us)) if candidate == signal => (ticks + 1, since_us),
+            _ => (1, timestamp_us),
+        };
+        let dwelled = self.min_dwell.is_some_and(|min_dwell| {
+            timestamp_us.saturating_sub(since_us) >= min_dwell.as_micros() as u64
+        });
+        
+        if ticks >= self.min_ticks || dwelled {
+            self.reported = Some(signal.clone());
+            signal
+        } else {
+            self.candidate = Some((signal, ticks, since_us));
+            reported
+        }
+    }
+}
+
+impl Default for SignalDebouncer {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+/// `value / mid_price`, or 0 when the mid is zero (degenerate book)
+pub(crate) fn ratio_to_mid(value: f64, mid_price: f64) -> f64 {
+    if mid_price == 0.0 {
+        debug!("Mid price is zero, using 0 instead of {} / mid", value);
+        return 0.0;
+    }
+    value / mid_price
+}
+
+/// Mid-price result for a balanced one-lot book quoted 1.0 wide around `fair_price`
+///
+/// Shared by the tests of result consumers (sinks, display, signals), which
+/// adjust individual fields as needed.
+#[cfg(test)]
+pub(crate) fn test_result(fair_price: f64) -> FairPriceResult {
+    FairPriceResult {
+        fair_price,
+        calculation_method: "Mid-Price".to_string(),
+        timestamp: 0,
+        confidence: 0.9,
+        spread: 1.0,
+        spread_bps: ratio_to_mid(1.0, fair_price) * 10_000.0,
+        half_spread: 0.5,
+        mid_price: fair_price,
+        smoothed_price: None,
+        metadata: FairPriceMetadata {
+            bid_volume: 1.0,
+            ask_volume: 1.0,
+            total_volume: 2.0,
+            weighted_bid_price: fair_price - 0.5,
+            weighted_ask_price: fair_price + 0.5,
+            order_flow_imbalance: 0.0,
+            notional_imbalance: 0.0,
+            depth_ratio: 1.0,
+            spread: 1.0,
+            effective_spread: 1.0,
+            book_quality: 0.5,
+        },
+        signal_thresholds: SignalThresholds::default(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::order_book::OrderBook;
+    use crate::order_book::{OrderBook, OrderBookLevel};
+    
+    /// BTCUSDT book with the given `(price, quantity)` levels on each side
+    fn book(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> OrderBook {
+        let mut order_book = OrderBook::new("BTCUSDT".to_string());
+        for &(price, quantity) in bids {
+            order_book.bids.insert(order_book.price_ticks(price), OrderBookLevel::new(price, quantity));
+        }
+        for &(price, quantity) in asks {
+            order_book.asks.insert(order_book.price_ticks(price), OrderBookLevel::new(price, quantity));
+        }
+        order_book
+    }
     
     #[test]
     fn test_fair_price_calculation() {
         let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
-        let mut order_book = OrderBook::new("BTCUSDT".to_string());
-        
-        // Add some test data
-        order_book.bids.insert(
-            crate::order_book::Price::new(50000.0),
-            crate::order_book::OrderBookLevel::new(50000.0, 1.0)
-        );
-        order_book.asks.insert(
-            crate::order_book::Price::new(50001.0),
-            crate::order_book::OrderBookLevel::new(50001.0, 1.0)
-        );
+        let order_book = book(&[(50000.0, 1.0)], &[(50001.0, 1.0)]);
         
         let result = calculator.calculate(&order_book);
         assert!(result.is_some());
@@ -383,4 +1244,595 @@ mod tests {
         assert_eq!(result.fair_price, 50000.5);
         assert!(result.confidence > 0.0);
     }
+    
+    #[test]
+    fn test_ensemble_rewards_agreement() {
+        // Confidence-weighted mean
+        let (price, _) = FairPriceCalculator::blend_estimates(&[(100.0, 0.9), (101.0, 0.1)], 100.0).unwrap();
+        assert!((price - 100.1).abs() < 1e-9);
+        
+        let (agreeing_price, agreeing) = FairPriceCalculator::blend_estimates(&[(100.0, 0.8), (100.0, 0.8)], 100.0).unwrap();
+        let (_, disagreeing) = FairPriceCalculator::blend_estimates(&[(99.9, 0.8), (100.1, 0.8)], 100.0).unwrap();
+        assert_eq!((agreeing_price, agreeing), (100.0, 0.8));
+        assert!(disagreeing < agreeing);
+        
+        // Worthless estimates carry no weight
+        assert_eq!(FairPriceCalculator::blend_estimates(&[(100.0, 0.0), (f64::NAN, 0.5)], 100.0), None);
+        
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::Ensemble);
+        let order_book = book(&[(100.0, 1.0)], &[(100.5, 1.0)]);
+        let result = calculator.calculate(&order_book).unwrap();
+        assert_eq!(result.calculation_method, "Ensemble");
+        assert!(result.fair_price > 100.0 && result.fair_price < 100.5);
+        assert!(result.confidence > 0.0);
+    }
+    
+    #[test]
+    fn test_imbalance_trend() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        let book_with_bid_qty = |bid_qty: f64| book(&[(100.0, bid_qty)], &[(101.0, 1.0)]);
+        
+        calculator.calculate(&book_with_bid_qty(1.0)).unwrap();
+        assert_eq!(calculator.imbalance_series(), vec![0.0]);
+        assert_eq!(calculator.imbalance_trend(), None);
+        
+        // Bids keep growing against a fixed ask
+        for bid_qty in [1.5, 2.0, 3.0] {
+            calculator.calculate(&book_with_bid_qty(bid_qty)).unwrap();
+        }
+        let series = calculator.imbalance_series();
+        assert!(series.windows(2).all(|pair| pair[1] > pair[0]));
+        assert!(calculator.imbalance_trend().unwrap() > 0.0);
+        assert_eq!(calculator.imbalance_trend(), Some(0.5));
+        
+        // The window only spans the latest IMBALANCE_WINDOW results
+        for _ in 0..IMBALANCE_WINDOW {
+            calculator.calculate(&book_with_bid_qty(1.0)).unwrap();
+        }
+        assert_eq!(calculator.imbalance_series().len(), IMBALANCE_WINDOW);
+        assert_eq!(calculator.imbalance_trend(), Some(0.0));
+        
+        calculator.reset();
+        assert!(calculator.imbalance_series().is_empty());
+    }
+    
+    #[test]
+    fn test_price_trend_regression() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        for price in [100.0, 101.0, 102.0, 103.0, 104.0] {
+            calculator.update_price_history(price);
+        }
+        
+        // Slope of 1.0 per sample over a mean of 102
+        let slope = calculator.get_price_trend_regression(5).unwrap();
+        assert!(slope > 0.0);
+        assert!((slope - 1.0 / 102.0).abs() < 1e-12);
+        assert_eq!(calculator.get_price_trend_regression(6), None);
+        
+        let mut flat = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        for _ in 0..10 {
+            flat.update_price_history(100.0);
+        }
+        assert!(flat.get_price_trend_regression(10).unwrap().abs() < 1e-12);
+    }
+    
+    #[test]
+    fn test_price_zscore() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        for _ in 0..9 {
+            calculator.update_price_history(100.0);
+        }
+        assert_eq!(calculator.price_zscore(9), None); // Zero volatility
+        assert_eq!(calculator.price_zscore(20), None); // Underfilled
+        
+        // Spike of +10: mean 101, population std dev 3, z = 9 / 3
+        calculator.update_price_history(110.0);
+        let zscore = calculator.price_zscore(10).unwrap();
+        assert!((zscore - 3.0).abs() < 1e-9);
+        
+        // A downward spike has a negative z-score
+        calculator.update_price_history(90.0);
+        assert!(calculator.price_zscore(10).unwrap() < 0.0);
+    }
+    
+    #[test]
+    fn test_calculate_all_methods() {
+        let calculator = FairPriceCalculator::new(FairPriceMethod::MicroPrice);
+        let order_book = book(&[(100.0, 2.0)], &[(101.0, 1.0)]);
+        
+        let results = calculator.calculate_all(&order_book);
+        assert_eq!(results.len(), FairPriceMethod::all().len());
+        
+        // Each method is also logged under its own `fairprice.*` target
+        let mut methods: Vec<&str> = results.iter().map(|r| r.calculation_method.as_str()).collect();
+        methods.sort();
+        methods.dedup();
+        assert_eq!(methods.len(), results.len());
+        
+        assert_eq!(calculator.get_method(), &FairPriceMethod::MicroPrice);
+        assert_eq!(calculator.twap(1), None); // History untouched
+    }
+    
+    #[test]
+    fn test_twap_on_ramp() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        for i in 1..=10 {
+            calculator.update_price_history(100.0 + i as f64);
+        }
+        
+        // Last 4 of 101..=110
+        assert_eq!(calculator.twap(4), Some(108.5));
+        assert_eq!(calculator.twap(10), Some(105.5));
+        assert_eq!(calculator.twap(1), Some(110.0));
+        assert_eq!(calculator.twap(11), None);
+        assert_eq!(calculator.twap(0), None);
+    }
+    
+    #[test]
+    fn test_signal_thresholds_boundary() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        let order_book = book(&[(100.0, 1.0)], &[(101.0, 1.0)]);
+        let mut result = calculator.calculate(&order_book).unwrap();
+        result.confidence = 0.9;
+        
+        // Exactly at the default threshold is still balanced
+        result.metadata.order_flow_imbalance = 0.3;
+        assert_eq!(result.market_signal(), MarketSignal::Balanced);
+        result.metadata.order_flow_imbalance = -0.3;
+        assert_eq!(result.market_signal(), MarketSignal::Balanced);
+        
+        // A tighter threshold flips the same imbalance into pressure
+        let sensitive = SignalThresholds { imbalance: 0.2, ..SignalThresholds::default() };
+        assert_eq!(result.market_signal_with(&sensitive), MarketSignal::SellPressure);
+        result.metadata.order_flow_imbalance = 0.3;
+        assert_eq!(result.market_signal_with(&sensitive), MarketSignal::BuyPressure);
+        
+        // Confidence gate
+        let strict = SignalThresholds { imbalance: 0.2, min_confidence: 0.95, ..SignalThresholds::default() };
+        assert_eq!(result.market_signal_with(&strict), MarketSignal::Neutral);
+        
+        // Thresholds set on the calculator are carried on results
+        calculator.set_signal_thresholds(sensitive);
+        let result = calculator.calculate(&order_book).unwrap();
+        assert_eq!(result.signal_thresholds, sensitive);
+    }
+    
+    #[test]
+    fn test_micro_price_uses_trade_flow() {
+        let order_book = book(&[(100.0, 1.0)], &[(101.0, 1.0)]);
+        
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MicroPrice);
+        let book_only = calculator.calculate(&order_book).unwrap();
+        assert_eq!(book_only.fair_price, 100.5);
+        
+        // All-buy trade flow pushes micro-price up by 10% of the spread
+        calculator.set_trade_flow_imbalance(Some(1.0));
+        let with_flow = calculator.calculate(&order_book).unwrap();
+        assert!((with_flow.fair_price - 100.6).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_spread_bps() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        let order_book = book(&[(49999.75, 1.0)], &[(50000.25, 1.0)]);
+        
+        let result = calculator.calculate(&order_book).unwrap();
+        assert_eq!(result.mid_price, 50000.0);
+        assert_eq!(result.half_spread, 0.25);
+        assert!((result.spread_bps - 0.1).abs() < 1e-9);
+        assert!(result.summary().contains("0.10 bps"));
+    }
+    
+    #[test]
+    fn test_ema_lags_step_change() {
+        let mut calculator = FairPriceCalculator::with_ema(FairPriceMethod::MidPrice, 0.5);
+        let order_book = book(&[(99.0, 1.0)], &[(101.0, 1.0)]);
+        
+        // First sample seeds the EMA
+        let result = calculator.calculate(&order_book).unwrap();
+        assert_eq!(result.smoothed_price, Some(100.0));
+        
+        // Step the mid from 100 to 110
+        let order_book = book(&[(109.0, 1.0)], &[(111.0, 1.0)]);
+        
+        let result = calculator.calculate(&order_book).unwrap();
+        assert_eq!(result.fair_price, 110.0);
+        assert_eq!(result.smoothed_price, Some(105.0));
+        
+        let result = calculator.calculate(&order_book).unwrap();
+        assert_eq!(result.smoothed_price, Some(107.5));
+        assert_eq!(calculator.get_smoothed_price(), Some(107.5));
+        
+        // Without EMA configured the field stays empty
+        let mut plain = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        assert_eq!(plain.calculate(&order_book).unwrap().smoothed_price, None);
+    }
+    
+    #[test]
+    fn test_book_pressure_discounts_far_depth() {
+        // Thin touch on both sides, heavy ask depth far from mid (100.5)
+        let order_book = book(&[(100.0, 1.0)], &[(101.0, 1.0), (120.0, 50.0)]);
+        
+        let pressure = FairPriceCalculator::new(FairPriceMethod::BookPressure { levels: 5 })
+            .calculate(&order_book)
+            .unwrap();
+        let volume_weighted = FairPriceCalculator::new(FairPriceMethod::VolumeWeighted { levels: 5 })
+            .calculate(&order_book)
+            .unwrap();
+        
+        // Weights: 1 / 0.5 on each touch, 50 / 19.5 on the far ask
+        let far_weight = 50.0 / 19.5;
+        let expected = (100.0 * 2.0 + 101.0 * 2.0 + 120.0 * far_weight) / (4.0 + far_weight);
+        assert!((pressure.fair_price - expected).abs() < 1e-9);
+        assert_eq!(pressure.calculation_method, "Book Pressure (top 5 levels)");
+        
+        // The far depth drags plain VWAP much further from mid
+        assert!((pressure.fair_price - 100.5).abs() < (volume_weighted.fair_price - 100.5).abs());
+        assert!(volume_weighted.fair_price > 119.0);
+    }
+    
+    #[test]
+    fn test_book_quality() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        
+        // Tight and deep: one-tick spread, 500 units on each of 5 levels
+        let mut tight = OrderBook::with_tick_size("BTCUSDT".to_string(), 0.01);
+        for i in 0..5 {
+            let bid = 100.0 - i as f64 * 0.01;
+            let ask = 100.01 + i as f64 * 0.01;
+            tight.bids.insert(tight.price_ticks(bid), OrderBookLevel::new(bid, 500.0));
+            tight.asks.insert(tight.price_ticks(ask), OrderBookLevel::new(ask, 500.0));
+        }
+        let tight = calculator.calculate(&tight).unwrap().metadata;
+        assert!((tight.effective_spread - 0.05).abs() < 1e-9);
+        assert!(tight.book_quality > 0.8);
+        
+        // Wide and thin: 20% spread with a fraction of a unit resting
+        let wide = book(&[(90.0, 0.1)], &[(110.0, 0.1)]);
+        let wide = calculator.calculate(&wide).unwrap().metadata;
+        assert_eq!(wide.effective_spread, 20.0);
+        assert!(wide.book_quality < 0.35);
+        assert!(wide.book_quality >= 0.0);
+    }
+    
+    #[test]
+    fn test_confidence_floor() {
+        // Nearly all the volume on one side
+        let order_book = book(&[(100.0, 1000.0)], &[(101.0, 0.001)]);
+        
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::VolumeWeighted { levels: 5 });
+        assert_eq!(calculator.get_confidence_floor(), DEFAULT_CONFIDENCE_FLOOR);
+        assert_eq!(calculator.calculate(&order_book).unwrap().confidence, DEFAULT_CONFIDENCE_FLOOR);
+        
+        calculator.set_confidence_floor(0.0);
+        let confidence = calculator.calculate(&order_book).unwrap().confidence;
+        assert!(confidence < 0.1);
+        assert!(confidence >= 0.0);
+        
+        calculator.set_confidence_floor(2.0);
+        assert_eq!(calculator.get_confidence_floor(), 1.0);
+    }
+    
+    #[test]
+    fn test_depth_consistency_penalizes_deep_skew() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        let twenty_levels = |deep_bid_quantity: f64| {
+            let bids: Vec<(f64, f64)> = (0..20)
+                .map(|i| (100.0 - i as f64, if i == 0 { 1.0 } else { deep_bid_quantity }))
+                .collect();
+            let asks: Vec<(f64, f64)> = (0..20).map(|i| (101.0 + i as f64, 1.0)).collect();
+            book(&bids, &asks)
+        };
+        
+        let balanced = twenty_levels(1.0);
+        let deep_skewed = twenty_levels(10.0);
+        assert_eq!(FairPriceCalculator::depth_consistency(&balanced), 1.0);
+        assert!(FairPriceCalculator::depth_consistency(&deep_skewed) < 0.6);
+        
+        let balanced = calculator.calculate(&balanced).unwrap();
+        let deep_skewed = calculator.calculate(&deep_skewed).unwrap();
+        assert!(deep_skewed.confidence < balanced.confidence);
+    }
+    
+    #[test]
+    fn test_volume_weighted_one_sided() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::VolumeWeighted { levels: 5 });
+        let bids_only = book(&[(100.0, 3.0), (99.0, 1.0)], &[]);
+        
+        // (100 * 3 + 99 * 1) / 4
+        let result = calculator.calculate(&bids_only).unwrap();
+        assert!((result.fair_price - 99.75).abs() < 1e-9);
+        assert_eq!(result.mid_price, result.fair_price);
+        assert_eq!(result.spread, 0.0);
+        assert!(result.confidence <= ONE_SIDED_CONFIDENCE);
+        assert_eq!(result.market_signal(), MarketSignal::Neutral);
+        
+        // Other methods still refuse a one-sided book, and nobody prices an empty one
+        assert!(FairPriceCalculator::new(FairPriceMethod::MidPrice).calculate(&bids_only).is_none());
+        assert!(calculator.calculate(&OrderBook::new("BTCUSDT".to_string())).is_none());
+    }
+    
+    #[test]
+    fn test_volume_weighted_decay_pulls_to_touch() {
+        // Heavy depth 10% away from the touch
+        let order_book = book(&[(100.0, 1.0)], &[(101.0, 1.0), (110.0, 10.0)]);
+        
+        let fair_price = |k: f64| {
+            FairPriceCalculator::new(FairPriceMethod::VolumeWeightedDecay { levels: 5, k })
+                .calculate(&order_book)
+                .unwrap()
+                .fair_price
+        };
+        let plain = FairPriceCalculator::new(FairPriceMethod::VolumeWeighted { levels: 5 })
+            .calculate(&order_book)
+            .unwrap()
+            .fair_price;
+        
+        // No decay is plain volume weighting
+        assert!((fair_price(0.0) - plain).abs() < 1e-9);
+        
+        let (low, high) = (fair_price(10.0), fair_price(100.0));
+        assert!(plain > low && low > high);
+        assert!((high - 100.5).abs() < 0.5);
+    }
+    
+    #[test]
+    fn test_reset_clears_history() {
+        let mut calculator = FairPriceCalculator::with_ema(FairPriceMethod::MidPrice, 0.5);
+        for i in 0..5 {
+            calculator.update_price_history(100.0 + i as f64);
+        }
+        calculator.update_ema(104.0);
+        assert!(calculator.get_price_volatility(5).is_some());
+        
+        calculator.reset();
+        assert_eq!(calculator.get_price_volatility(5), None);
+        assert_eq!(calculator.get_smoothed_price(), None);
+        assert_eq!(calculator.get_method(), &FairPriceMethod::MidPrice);
+        
+        calculator.update_price_history(200.0);
+        calculator.set_method_and_reset(FairPriceMethod::MicroPrice);
+        assert_eq!(calculator.twap(1), None);
+        assert_eq!(calculator.get_method(), &FairPriceMethod::MicroPrice);
+    }
+    
+    #[test]
+    fn test_history_capacity() {
+        let mut calculator = FairPriceCalculator::with_history_capacity(FairPriceMethod::MidPrice, 3);
+        assert_eq!(calculator.history_capacity(), 3);
+        for i in 0..5 {
+            calculator.update_price_history(100.0 + i as f64);
+        }
+        assert_eq!(calculator.price_history, vec![102.0, 103.0, 104.0]);
+        
+        assert_eq!(FairPriceCalculator::with_history_capacity(FairPriceMethod::MidPrice, 0).history_capacity(), 1);
+        assert_eq!(FairPriceCalculator::new(FairPriceMethod::MidPrice).history_capacity(), DEFAULT_HISTORY_CAPACITY);
+    }
+    
+    #[test]
+    fn test_price_history_accessor() {
+        let mut calculator = FairPriceCalculator::with_history_capacity(FairPriceMethod::MidPrice, 4);
+        let order_book = book(&[(99.0, 1.0)], &[(101.0, 1.0)]);
+        assert!(calculator.price_history().is_empty());
+        
+        for _ in 0..3 {
+            calculator.calculate(&order_book).unwrap();
+        }
+        assert_eq!(calculator.history_len(), 3);
+        assert_eq!(calculator.price_history(), &[100.0, 100.0, 100.0]);
+        
+        // Capped at the history capacity
+        for _ in 0..3 {
+            calculator.calculate(&order_book).unwrap();
+        }
+        assert_eq!(calculator.history_len(), 4);
+    }
+    
+    #[test]
+    fn test_metadata_levels() {
+        let bids: Vec<(f64, f64)> = (0..8).map(|i| (99.0 - i as f64, 1.0)).collect();
+        let asks: Vec<(f64, f64)> = (0..8).map(|i| (101.0 + i as f64, 1.0)).collect();
+        let order_book = book(&bids, &asks);
+        
+        let mut default = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        assert_eq!(default.metadata_levels(), DEFAULT_METADATA_LEVELS);
+        assert_eq!(default.calculate(&order_book).unwrap().metadata.total_volume, 10.0);
+        
+        let mut deep = FairPriceCalculator::with_metadata_levels(FairPriceMethod::MidPrice, 8);
+        assert_eq!(deep.calculate(&order_book).unwrap().metadata.total_volume, 16.0);
+        
+        let mut shallow = FairPriceCalculator::with_metadata_levels(FairPriceMethod::MidPrice, 0);
+        assert_eq!(shallow.metadata_levels(), 1);
+        assert_eq!(shallow.calculate(&order_book).unwrap().metadata.total_volume, 2.0);
+    }
+    
+    #[test]
+    fn test_geometric_mid() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::GeometricMid);
+        
+        // Wide: sqrt(50 * 200) = 100 against an arithmetic mid of 125
+        let wide = book(&[(50.0, 1.0)], &[(200.0, 1.0)]);
+        let result = calculator.calculate(&wide).unwrap();
+        assert!((result.fair_price - 100.0).abs() < 1e-9);
+        assert_eq!(result.mid_price, 125.0);
+        assert_eq!(result.calculation_method, "Geometric Mid");
+        
+        // Tight: one tick apart, the two means agree to well under a tick
+        let mut tight = OrderBook::with_tick_size("BTCUSDT".to_string(), 0.01);
+        tight.bids.insert(tight.price_ticks(50000.0), OrderBookLevel::new(50000.0, 1.0));
+        tight.asks.insert(tight.price_ticks(50000.01), OrderBookLevel::new(50000.01, 1.0));
+        let result = calculator.calculate(&tight).unwrap();
+        assert!((result.fair_price - result.mid_price).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn test_zero_mid_stays_finite() {
+        // Bid and ask symmetric around zero: the mid computes to exactly 0
+        let order_book = book(&[(-1.0, 2.0)], &[(1.0, 1.0)]);
+        assert_eq!(order_book.mid_price(), Some(0.0));
+        
+        let results = FairPriceCalculator::new(FairPriceMethod::MidPrice).calculate_all(&order_book);
+        assert_eq!(results.len(), FairPriceMethod::all().len());
+        for result in results {
+            assert!(result.fair_price.is_finite(), "{}", result.calculation_method);
+            assert!(result.confidence.is_finite(), "{}", result.calculation_method);
+            assert_eq!(result.spread_bps, 0.0);
+        }
+        assert_eq!(ratio_to_mid(2.0, 0.0), 0.0);
+    }
+    
+    #[test]
+    fn test_notional_imbalance_flips_sign() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        
+        // More units bid (10 vs 6), but more notional offered (900 vs 500)
+        let order_book = book(&[(50.0, 10.0)], &[(150.0, 6.0)]);
+        
+        let metadata = calculator.calculate(&order_book).unwrap().metadata;
+        assert!((metadata.order_flow_imbalance - 0.25).abs() < 1e-9);
+        assert!((metadata.notional_imbalance - (-400.0 / 1400.0)).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_notional_basis_compares_across_symbols() {
+        // Both books offer a third of the bid notional, at very different unit prices
+        let btc = book(&[(50000.0, 3.0)], &[(50000.5, 1.0)]);
+        let doge = book(&[(0.1, 30000.0)], &[(0.125, 8000.0)]);
+        
+        let thresholds = SignalThresholds { imbalance: 0.55, min_confidence: 0.0, ..SignalThresholds::default() };
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        calculator.set_signal_thresholds(thresholds);
+        
+        // Quantity imbalance disagrees: 0.5 for BTC, ~0.58 for DOGE
+        let btc_result = calculator.calculate(&btc).unwrap();
+        let doge_result = calculator.calculate(&doge).unwrap();
+        assert_eq!(btc_result.market_signal(), MarketSignal::Balanced);
+        assert_eq!(doge_result.market_signal(), MarketSignal::BuyPressure);
+        
+        calculator.set_imbalance_basis(ImbalanceBasis::Notional);
+        assert_eq!(calculator.get_imbalance_basis(), ImbalanceBasis::Notional);
+        let btc_result = calculator.calculate(&btc).unwrap();
+        let doge_result = calculator.calculate(&doge).unwrap();
+        assert!((btc_result.signal_imbalance() - 0.5).abs() < 1e-4);
+        assert!((doge_result.signal_imbalance() - 0.5).abs() < 1e-9);
+        assert_eq!(btc_result.market_signal(), MarketSignal::Balanced);
+        assert_eq!(doge_result.market_signal(), MarketSignal::Balanced);
+    }
+    
+    #[test]
+    fn test_signal_debouncer_ignores_flicker() {
+        use MarketSignal::*;
+        
+        let mut debouncer = SignalDebouncer::new(3);
+        assert_eq!(debouncer.reported(), None);
+        let flicker = [Balanced, BuyPressure, Balanced, BuyPressure, BuyPressure, Balanced, SellPressure];
+        let reported: Vec<MarketSignal> = flicker
+            .iter()
+            .enumerate()
+            .map(|(tick, signal)| debouncer.update(signal.clone(), tick as u64))
+            .collect();
+        assert!(reported.iter().all(|signal| *signal == Balanced));
+        
+        // SellPressure from the last flicker tick is adopted on its third tick
+        assert_eq!(debouncer.update(SellPressure, 7), Balanced);
+        assert_eq!(debouncer.update(SellPressure, 8), SellPressure);
+        
+        // Dwell time adopts a persistent signal before the tick count is reached
+        let mut debouncer = SignalDebouncer::new(100).with_min_dwell(Duration::from_millis(5));
+        assert_eq!(debouncer.update(Balanced, 0), Balanced);
+        assert_eq!(debouncer.update(BuyPressure, 1_000), Balanced);
+        assert_eq!(debouncer.update(BuyPressure, 3_000), Balanced);
+        assert_eq!(debouncer.update(BuyPressure, 6_000), BuyPressure);
+    }
+    
+    #[test]
+    fn test_custom_strategy() {
+        struct Constant;
+        
+        impl FairPriceStrategy for Constant {
+            fn name(&self) -> String {
+                "Constant".to_string()
+            }
+            
+            fn compute(&self, _order_book: &OrderBook, _metadata: &FairPriceMetadata) -> (f64, f64) {
+                (42.0, 0.9)
+            }
+        }
+        
+        let order_book = book(&[(100.0, 1.0)], &[(101.0, 1.0)]);
+        
+        let mut calculator = FairPriceCalculator::with_strategy(Arc::new(Constant));
+        let result = calculator.calculate(&order_book).unwrap();
+        assert_eq!(result.fair_price, 42.0);
+        assert_eq!(result.confidence, 0.9);
+        assert_eq!(result.calculation_method, "Constant");
+        assert_eq!(result.mid_price, 100.5);
+        assert_eq!(calculator.price_history(), &[42.0]);
+        
+        // Dropping the strategy falls back to the method
+        calculator.set_strategy(None);
+        assert_eq!(calculator.calculate(&order_book).unwrap().fair_price, 100.5);
+    }
+    
+    #[test]
+    fn test_volatility_lowers_confidence() {
+        let order_book = book(&[(100.0, 1.0)], &[(101.0, 1.0)]);
+        
+        let calculator_with = |history: &[f64]| {
+            let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+            calculator.set_volatility_sensitivity(0.1);
+            calculator.price_history.extend_from_slice(history);
+            calculator
+        };
+        let calm = calculator_with(&[100.5; 10]).calculate(&order_book).unwrap();
+        let volatile = calculator_with(&[99.0, 102.0, 98.5, 103.0, 100.5]).calculate(&order_book).unwrap();
+        
+        let mut unscaled = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        assert_eq!(calm.confidence, unscaled.calculate(&order_book).unwrap().confidence);
+        assert!(volatile.confidence < calm.confidence);
+        assert!(volatile.confidence >= unscaled.get_confidence_floor());
+        
+        unscaled.set_volatility_sensitivity(f64::NAN);
+        assert_eq!(unscaled.get_volatility_sensitivity(), 0.0);
+    }
+    
+    #[test]
+    fn test_liquidity_thresholds() {
+        let order_book = book(&[(100.0, 1.0)], &[(101.0, 1.0)]);
+        
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        assert!(calculator.calculate(&order_book).is_some());
+        
+        calculator.set_liquidity_thresholds(LiquidityThresholds { min_total_volume: 0.0, min_levels_per_side: 2 });
+        assert!(calculator.calculate(&order_book).is_none());
+        
+        calculator.set_liquidity_thresholds(LiquidityThresholds { min_total_volume: 5.0, min_levels_per_side: 1 });
+        assert!(calculator.calculate(&order_book).is_none());
+        
+        // Deep enough on both counts
+        let order_book = book(&[(100.0, 1.0), (99.0, 2.0)], &[(101.0, 1.0), (102.0, 2.0)]);
+        calculator.set_liquidity_thresholds(LiquidityThresholds { min_total_volume: 5.0, min_levels_per_side: 2 });
+        assert!(calculator.calculate(&order_book).is_some());
+    }
+    
+    #[test]
+    fn test_execution_vwap_partial_fill() {
+        let mut calculator = FairPriceCalculator::new(
+            FairPriceMethod::ExecutionVwap { notional: 300.0 }
+        );
+        
+        // Bids hold 200 notional in total, asks hold 303
+        let order_book = book(&[(100.0, 1.0), (50.0, 2.0)], &[(101.0, 3.0)]);
+        
+        let result = calculator.calculate(&order_book).unwrap();
+        
+        // Bid VWAP: 200 notional over 3 units; ask VWAP: 101
+        let expected = (200.0 / 3.0 + 101.0) / 2.0;
+        assert!((result.fair_price - expected).abs() < 1e-9);
+        
+        // 200 of 300 filled on bids, all 300 on asks; the touch (1 vs 3) disagrees
+        // with the balanced deeper book (3 vs 3)
+        let consistency = 1.0 - 0.5 / 2.0;
+        assert!((result.confidence - 500.0 / 600.0 * consistency).abs() < 1e-9);
+    }
 }
\ No newline at end of file
@@ -22,9 +22,7 @@
 //! async fn main() -> anyhow::Result<()> {
 //!     let config = Config::default();
 //!     let order_book_manager = Arc::new(OrderBookManager::new());
-//!     let fair_price_calculator = Arc::new(FairPriceCalculator::new(
-//!         config.calculation_method.clone()
-//!     ));
+//!     let fair_price_calculator = FairPriceCalculator::new(config.calculation_method.clone());
 //!     
 //!     let ws_manager = WebSocketManager::new(
 //!         config,
@@ -38,17 +36,40 @@
 //! ```
 
 pub mod binance;
+#[cfg(feature = "server")]
+pub mod broadcast;
 pub mod config;
+pub mod exchange;
 pub mod fair_price;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod order_book;
+pub mod output;
+pub mod rate_limit;
+pub mod replay;
+pub mod supervisor;
+pub mod trades;
 pub mod websocket;
 
 // Re-export main types for easy access
-pub use binance::{BinanceClient, SymbolInfo};
-pub use config::{Config, FairPriceMethod};
-pub use fair_price::{FairPriceCalculator, FairPriceResult, MarketSignal};
-pub use order_book::{OrderBook, OrderBookLevel, OrderBookManager, OrderBookUpdate};
-pub use websocket::{WebSocketManager, ConnectionStats};
+pub use binance::{ApiDomain, BinanceClient, ClientOptions, Kline, Market, RetryPolicy, SymbolInfo, SymbolNotTrading, Ticker24h};
+pub use config::{Config, ConfigBuilder, DepthStreamMode, DisplayMode, DEFAULT_DISPLAY_PRECISION, FairPriceMethod, KeepaliveMode};
+pub use exchange::ExchangeConnector;
+pub use fair_price::{FairPriceCalculator, FairPriceResult, FairPriceStrategy, ImbalanceBasis, LiquidityThresholds, MarketSignal, SignalDebouncer, SignalThresholds};
+pub use order_book::{
+    BookSummary, BookValidity, CrossedBookPolicy, DepthDivergence, DepthHistogram, LevelChange, OrderBook, OrderBookDiff, OrderBookError, OrderBookLevel,
+    OrderBookManager, OrderBookUpdate, PartialDepth, PriceTicks, Side, SideDiff, SpreadAlert, SpreadThreshold, TopOfBook, TopOfBookChange,
+};
+pub use output::CsvSink;
+pub use rate_limit::RateLimiter;
+pub use replay::{read_csv, read_records, realized_spread, ResultRecord};
+pub use trades::{AggTrade, TradeFlow};
+pub use supervisor::SymbolSupervisor;
+pub use websocket::{Command, WebSocketManager, ConnectionState, ConnectionStats, HealthStatus, SignalChange};
+#[cfg(feature = "metrics")]
+pub use metrics::Metrics;
+#[cfg(feature = "server")]
+pub use broadcast::BroadcastServer;
 
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -1,34 +1,85 @@
 use anyhow::Result;
 use clap::Parser;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::Notify;
 use tracing::{info, warn, error};
 
 mod binance;
+#[cfg(feature = "server")]
+mod broadcast;
+mod exchange;
 mod fair_price;
 mod order_book;
+mod output;
+mod rate_limit;
+mod trades;
 mod websocket;
 mod config;
+#[cfg(feature = "metrics")]
+mod metrics;
 
-use crate::binance::BinanceClient;
+use crate::binance::{BinanceClient, Market, SymbolNotTrading};
 use crate::fair_price::FairPriceCalculator;
 use crate::order_book::OrderBookManager;
-use crate::websocket::WebSocketManager;
-use crate::config::Config;
+use crate::output::CsvSink;
+use crate::websocket::{Command, WebSocketManager};
+use crate::config::{Config, DisplayMode};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Trading symbol (e.g., BTCUSDT)
-    #[arg(short, long, default_value = "BTCUSDT")]
-    symbol: String,
+    /// Trading symbols, comma-separated or repeated (e.g., BTCUSDT,ETHUSDT)
+    #[arg(short, long, value_delimiter = ',', default_value = "BTCUSDT")]
+    symbol: Vec<String>,
 
-    /// Log level
+    /// Log level, or filter directives (e.g. info,fairprice.micro=debug)
     #[arg(short, long, default_value = "info")]
     log_level: String,
 
     /// Fair price calculation method
     #[arg(short, long, default_value = "mid-price")]
     method: String,
+
+    /// Stop after this long (e.g. 90, 90s, 15m, 2h); runs until Ctrl+C if unset
+    #[arg(short, long, value_parser = parse_duration)]
+    duration: Option<Duration>,
+
+    /// Binance market: spot or futures (USD-M)
+    #[arg(long, default_value = "spot")]
+    market: String,
+
+    /// Per-result log output: box, one-line, json or silent
+    #[arg(long, default_value = "box")]
+    display: String,
+
+    /// Decimal places for logged prices (default: from the symbol's tick size)
+    #[arg(long)]
+    precision: Option<usize>,
+
+    /// Also output an EMA-smoothed fair price with this alpha (0, 1]
+    #[arg(long)]
+    ema_alpha: Option<f64>,
+
+    /// Connect even when a symbol's status is not TRADING (e.g. BREAK or HALT)
+    #[arg(long)]
+    allow_non_trading: bool,
+
+    /// Append every fair price result to this CSV file
+    #[arg(long)]
+    output_csv: Option<PathBuf>,
+
+    /// Address to serve Prometheus metrics on
+    #[cfg(feature = "metrics")]
+    #[arg(long, default_value = "127.0.0.1:9898")]
+    metrics_addr: std::net::SocketAddr,
+
+    /// Address to rebroadcast fair prices to WebSocket clients on
+    #[cfg(feature = "server")]
+    #[arg(long, default_value = "127.0.0.1:9899")]
+    serve_addr: std::net::SocketAddr,
 }
 
 #[tokio::main]
@@ -38,56 +89,265 @@ async fn main() -> Result<()> {
     // Initialize logging
     init_logging(&args.log_level)?;
     
+    let display_mode: DisplayMode = args.display.parse()?;
+    let market: Market = args.market.parse()?;
+    let symbols = normalize_symbols(&args.symbol);
+    if symbols.is_empty() {
+        return Err(anyhow::anyhow!("At least one symbol is required"));
+    }
+    
     info!("🚀 Starting OrderFlow-RS - Real-time Fair Price Calculator");
-    info!("Symbol: {}", args.symbol);
+    info!("Symbols: {}", symbols.join(", "));
     info!("Calculation method: {}", args.method);
+    info!("Market: {}", market);
     
-    // Initialize configuration
-    let config = Config::new(args.symbol.clone(), args.method);
+    // One REST client shared by all symbols so lookups hit the same exchangeInfo cache
+    // and the manager's snapshots draw on its request weight budget
+    let base_config = Config::new(symbols[0].clone(), args.method.clone()).with_market(market);
+    let binance_client = Arc::new(BinanceClient::from_config(&base_config));
     
-    // Initialize components
-    let binance_client = Arc::new(BinanceClient::new());
-    let order_book_manager = Arc::new(OrderBookManager::new());
-    let fair_price_calculator = Arc::new(FairPriceCalculator::new(config.calculation_method.clone()));
-    
-    // Try to verify symbol (optional)
-    info!("🔍 Attempting to verify symbol {}...", config.symbol);
-    match binance_client.get_symbol_info(&config.symbol).await {
-        Ok(info) => {
-            info!("✅ Symbol {} verified - Base: {}, Quote: {}", 
-                  config.symbol, info.base_asset, info.quote_asset);
+    // Set up each symbol's book and calculator; all of them share one connection
+    let mut pipelines = Vec::with_capacity(symbols.len());
+    for symbol in &symbols {
+        let mut config = Config::new(symbol.clone(), args.method.clone()).with_market(market);
+        config.display_mode = display_mode;
+        config.display_precision = args.precision;
+        let mut order_book_manager = OrderBookManager::from_config(&config.order_book);
+        let fair_price_calculator = match args.ema_alpha {
+            Some(alpha) => FairPriceCalculator::with_ema(config.calculation_method.clone(), alpha),
+            None => FairPriceCalculator::new(config.calculation_method.clone()),
+        };
+        
+        // Try to verify symbol (optional)
+        info!("🔍 Attempting to verify symbol {}...", config.symbol);
+        let lookup = if args.allow_non_trading {
+            binance_client.get_symbol_info(&config.symbol).await
+        } else {
+            binance_client.verify_tradeable(&config.symbol).await
+        };
+        match lookup {
+            Ok(info) => {
+                info!("✅ Symbol {} verified - Base: {}, Quote: {}", 
+                      config.symbol, info.base_asset, info.quote_asset);
+                if let Some(tick_size) = info.tick_size() {
+                    order_book_manager = order_book_manager.with_tick_size(tick_size);
+                }
+                config.display_precision.get_or_insert(info.display_precision());
+            }
+            // A halted or delisted symbol's stream is dead, unlike a failed lookup
+            Err(e) if e.downcast_ref::<SymbolNotTrading>().is_some() => {
+                return Err(anyhow::anyhow!("{}; use --allow-non-trading to connect anyway", e));
+            }
+            Err(e) => {
+                warn!("⚠️ Symbol verification failed for {} (continuing anyway): {}", config.symbol, e);
+                info!("📡 Proceeding with WebSocket connection...");
+            }
         }
-        Err(e) => {
-            warn!("⚠️ Symbol verification failed (continuing anyway): {}", e);
-            info!("📡 Proceeding with WebSocket connection...");
+        
+        // Log the 24hr ticker as a reference for the computed fair price
+        match binance_client.get_24h_ticker(&config.symbol).await {
+            Ok(ticker) => {
+                info!("📅 {} 24h - Last: ${:.4}, High: ${:.4}, Low: ${:.4}, Volume: {:.2}, Change: {:+.2}%",
+                      ticker.symbol, ticker.last_price, ticker.high_price, ticker.low_price,
+                      ticker.volume, ticker.price_change_percent);
+            }
+            Err(e) => warn!("⚠️ 24hr ticker unavailable for {}: {}", config.symbol, e),
         }
+        
+        pipelines.push((config, Arc::new(order_book_manager), fair_price_calculator));
     }
     
-    // Initialize WebSocket manager
-    let ws_manager = WebSocketManager::new(
-        config.clone(),
-        order_book_manager.clone(),
-        fair_price_calculator.clone(),
+    // Initialize the WebSocket manager, drawing on the shared REST weight budget
+    let mut pipelines = pipelines.into_iter();
+    let (config, order_book_manager, fair_price_calculator) = pipelines.next().unwrap();
+    let connector = BinanceClient::from_config(&config)
+        .with_rate_limiter(binance_client.rate_limiter().clone());
+    let ws_manager = WebSocketManager::with_connector(
+        config,
+        order_book_manager,
+        fair_price_calculator,
+        connector,
     );
     
-    // Start the WebSocket connection and processing
-    match ws_manager.start().await {
-        Ok(_) => info!("✅ WebSocket connection established"),
-        Err(e) => {
-            error!("❌ Failed to start WebSocket: {}", e);
-            return Err(e);
+    // Serve Prometheus metrics, one labelled series per symbol
+    #[cfg(feature = "metrics")]
+    let ws_manager = {
+        let metrics = Arc::new(metrics::Metrics::new()?);
+        let metrics_addr = args.metrics_addr;
+        let server_metrics = metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) = metrics::serve(server_metrics, metrics_addr).await {
+                error!("❌ Metrics server failed: {}", e);
+            }
+        });
+        ws_manager.with_metrics(metrics)
+    };
+    
+    // The other symbols ride on the same combined-stream connection
+    let ws_manager = pipelines.fold(ws_manager, |ws_manager, (config, order_book_manager, fair_price_calculator)| {
+        ws_manager.with_symbol(config, order_book_manager, fair_price_calculator)
+    });
+    let feeds = ws_manager.feeds();
+    let ws_managers: Vec<&WebSocketManager> = std::iter::once(&ws_manager)
+        .chain(feeds.iter().map(Arc::as_ref))
+        .collect();
+    
+    // Rebroadcast results to local WebSocket clients
+    #[cfg(feature = "server")]
+    {
+        let server = Arc::new(broadcast::BroadcastServer::new());
+        for ws_manager in &ws_managers {
+            let server = server.clone();
+            let symbol = ws_manager.symbol().to_string();
+            let results = ws_manager.subscribe_results();
+            tokio::spawn(async move { server.forward_results(symbol, results).await });
+        }
+        let serve_addr = args.serve_addr;
+        tokio::spawn(async move {
+            if let Err(e) = server.serve(serve_addr).await {
+                error!("❌ Broadcast server failed: {}", e);
+            }
+        });
+    }
+    
+    // Write results to CSV
+    if let Some(path) = &args.output_csv {
+        let sink = Arc::new(Mutex::new(CsvSink::create(path)?));
+        info!("📝 Writing results to {}", path.display());
+        for ws_manager in &ws_managers {
+            tokio::spawn(output::write_results(
+                sink.clone(),
+                ws_manager.symbol().to_string(),
+                ws_manager.subscribe_results(),
+            ));
+        }
+    }
+    
+    // Add or drop symbols while running, one `subscribe ETHUSDT` or `unsubscribe ETHUSDT` per stdin line
+    let commands = ws_manager.command_sender();
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse_command(&line) {
+                Ok(command) => {
+                    if commands.send(command).await.is_err() {
+                        break;
+                    }
+                }
+                Err(e) => warn!("⚠️ {}", e),
+            }
         }
+    });
+    
+    // Request a clean shutdown on Ctrl+C or once the run duration elapses
+    let shutdown = Arc::new(Notify::new());
+    let signal_shutdown = shutdown.clone();
+    let run_duration = args.duration;
+    tokio::spawn(async move {
+        let run_timer = async {
+            match run_duration {
+                Some(duration) => tokio::time::sleep(duration).await,
+                None => std::future::pending().await,
+            }
+        };
+        
+        tokio::select! {
+            Ok(()) = tokio::signal::ctrl_c() => {
+                info!("🛑 Shutting down...");
+            }
+            _ = run_timer => {
+                info!("⏱️ Run duration elapsed, shutting down...");
+            }
+        }
+        signal_shutdown.notify_one();
+    });
+    
+    // Start the WebSocket connections and processing
+    match run_duration {
+        Some(duration) => info!("🔄 Bot is running for {:?}... Press Ctrl+C to stop early", duration),
+        None => info!("🔄 Bot is running... Press Ctrl+C to stop"),
+    }
+    let result = ws_manager.start_with_shutdown(shutdown).await;
+    
+    for ws_manager in &ws_managers {
+        info!("📊 {}", ws_manager.get_stats());
+    }
+    if let Err(e) = result {
+        error!("❌ WebSocket manager for {} failed: {}", symbols.join(", "), e);
+        return Err(e);
     }
     
-    // Keep the application running
-    info!("🔄 Bot is running... Press Ctrl+C to stop");
-    tokio::signal::ctrl_c().await?;
-    info!("🛑 Shutting down...");
+    info!("✅ WebSocket connection closed");
     
     Ok(())
 }
 
+/// Parse a `subscribe SYMBOL` or `unsubscribe SYMBOL` line
+fn parse_command(line: &str) -> std::result::Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let (Some(action), Some(symbol), None) = (words.next(), words.next(), words.next()) else {
+        return Err(format!("invalid command '{}': expected e.g. subscribe ETHUSDT", line.trim()));
+    };
+    
+    match action.to_lowercase().as_str() {
+        "subscribe" => Ok(Command::Subscribe(symbol.to_uppercase())),
+        "unsubscribe" => Ok(Command::Unsubscribe(symbol.to_uppercase())),
+        _ => Err(format!("unknown command '{}': expected subscribe or unsubscribe", action)),
+    }
+}
+
+/// Uppercase, trim and de-duplicate symbols, keeping their order
+fn normalize_symbols(symbols: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for symbol in symbols {
+        let symbol = symbol.trim().to_uppercase();
+        if !symbol.is_empty() && !normalized.contains(&symbol) {
+            normalized.push(symbol);
+        }
+    }
+    normalized
+}
+
+/// Parse a run duration: plain seconds or a number with an `s`, `m` or `h` suffix
+fn parse_duration(value: &str) -> std::result::Result<Duration, String> {
+    let value = value.trim();
+    let (number, unit_secs) = match value.char_indices().last() {
+        Some((i, 's')) => (&value[..i], 1),
+        Some((i, 'm')) => (&value[..i], 60),
+        Some((i, 'h')) => (&value[..i], 3600),
+        _ => (value, 1),
+    };
+    
+    let number: u64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid duration '{}': expected e.g. 90, 90s, 15m or 2h", value))?;
+    if number == 0 {
+        return Err("duration must be greater than zero".to_string());
+    }
+    
+    number
+        .checked_mul(unit_secs)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("duration '{}' is too large", value))
+}
+
 fn init_logging(level: &str) -> Result<()> {
+    // Directives select levels per target, e.g. one method's `fairprice.*` logs
+    if level.contains('=') || level.contains(',') {
+        tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::try_new(level)?)
+            .with_target(false)
+            .with_thread_ids(false)
+            .with_file(false)
+            .with_line_number(false)
+            .init();
+        return Ok(());
+    }
+    
     let filter = match level.to_lowercase().as_str() {
         "trace" => tracing::Level::TRACE,
         "debug" => tracing::Level::DEBUG,
@@ -106,4 +366,46 @@ fn init_logging(level: &str) -> Result<()> {
         .init();
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("15m"), Ok(Duration::from_secs(900)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("1.5m").is_err());
+        assert!(parse_duration("ten").is_err());
+        
+        let args = Args::try_parse_from(["orderflow-rs", "--duration", "30s"]).unwrap();
+        assert_eq!(args.duration, Some(Duration::from_secs(30)));
+        assert_eq!(Args::try_parse_from(["orderflow-rs"]).unwrap().duration, None);
+    }
+    
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("subscribe ethusdt"), Ok(Command::Subscribe("ETHUSDT".to_string())));
+        assert_eq!(parse_command("  UNSUBSCRIBE BTCUSDT \n"), Ok(Command::Unsubscribe("BTCUSDT".to_string())));
+        assert!(parse_command("subscribe").is_err());
+        assert!(parse_command("subscribe ETHUSDT BNBUSDT").is_err());
+        assert!(parse_command("add ETHUSDT").is_err());
+    }
+    
+    #[test]
+    fn test_parse_symbols() {
+        let args = Args::try_parse_from(["orderflow-rs", "--symbol", "BTCUSDT,ethusdt"]).unwrap();
+        assert_eq!(normalize_symbols(&args.symbol), vec!["BTCUSDT", "ETHUSDT"]);
+        
+        let args = Args::try_parse_from(["orderflow-rs", "-s", "BTCUSDT", "-s", "bnbusdt, BTCUSDT"]).unwrap();
+        assert_eq!(normalize_symbols(&args.symbol), vec!["BTCUSDT", "BNBUSDT"]);
+        
+        let args = Args::try_parse_from(["orderflow-rs"]).unwrap();
+        assert_eq!(normalize_symbols(&args.symbol), vec!["BTCUSDT"]);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::fair_price::FairPriceResult;
+use anyhow::Result;
+use prometheus::{Encoder, GaugeVec, IntCounterVec, Opts, Registry, TextEncoder};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+/// Label distinguishing each symbol's series
+const SYMBOL_LABEL: &str = "symbol";
+
+/// Prometheus metrics for the running bot, labelled by symbol
+pub struct Metrics {
+    registry: Registry,
+    pub fair_price: GaugeVec,
+    pub mid_price: GaugeVec,
+    pub spread: GaugeVec,
+    pub confidence: GaugeVec,
+    pub messages_total: IntCounterVec,
+    pub reconnects_total: IntCounterVec,
+}
+
+impl Metrics {
+    /// Create and register all metrics
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+        
+        let gauge = |name: &str, help: &str| GaugeVec::new(Opts::new(name, help), &[SYMBOL_LABEL]);
+        let counter = |name: &str, help: &str| IntCounterVec::new(Opts::new(name, help), &[SYMBOL_LABEL]);
+        
+        let fair_price = gauge("orderflow_fair_price", "Latest calculated fair price")?;
+        let mid_price = gauge("orderflow_mid_price", "Latest mid price")?;
+        let spread = gauge("orderflow_spread", "Latest bid-ask spread")?;
+        let confidence = gauge("orderflow_confidence", "Latest fair price confidence (0-1)")?;
+        let messages_total = counter(
+            "orderflow_messages_total",
+            "WebSocket messages processed",
+        )?;
+        let reconnects_total = counter(
+            "orderflow_reconnects_total",
+            "WebSocket reconnection attempts",
+        )?;
+        
+        registry.register(Box::new(fair_price.clone()))?;
+        registry.register(Box::new(mid_price.clone()))?;
+        registry.register(Box::new(spread.clone()))?;
+        registry.register(Box::new(confidence.clone()))?;
+        registry.register(Box::new(messages_total.clone()))?;
+        registry.register(Box::new(reconnects_total.clone()))?;
+        
+        Ok(Self {
+            registry,
+            fair_price,
+            mid_price,
+            spread,
+            confidence,
+            messages_total,
+            reconnects_total,
+        })
+    }
+    
+    /// Update a symbol's gauges from a fair price result
+    pub fn observe(&self, symbol: &str, result: &FairPriceResult) {
+        self.fair_price.with_label_values(&[symbol]).set(result.fair_price);
+        self.mid_price.with_label_values(&[symbol]).set(result.mid_price);
+        self.spread.with_label_values(&[symbol]).set(result.spread);
+        self.confidence.with_label_values(&[symbol]).set(result.confidence);
+    }
+    
+    /// Render all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> Result<String> {
+        let encoder = TextEncoder::new();
+        let mut buffer = Vec::new();
+        encoder.encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+    
+    /// Build an HTTP response for a request line (e.g. `GET /metrics HTTP/1.1`)
+    pub fn handle_request(&self, request_line: &str) -> String {
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or("");
+        let path = parts.next().unwrap_or("");
+        
+        let (status, content_type, body) = match (method, path) {
+            ("GET", "/metrics") => match self.render() {
+                Ok(body) => ("200 OK", "text/plain; version=0.0.4", body),
+                Err(e) => ("500 Internal Server Error", "text/plain", e.to_string()),
+            },
+            _ => ("404 Not Found", "text/plain", "Not Found".to_string()),
+        };
+        
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        )
+    }
+}
+
+/// Serve `/metrics` over HTTP until the task is dropped
+pub async fn serve(metrics: Arc<Metrics>, addr: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    info!("📈 Serving metrics on http://{}/metrics", addr);
+    
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let metrics = metrics.clone();
+        
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &metrics).await {
+                warn!("Metrics request from {} failed: {}", peer, e);
+            }
+        });
+    }
+}
+
+/// Answer a single HTTP request
+async fn handle_connection(stream: TcpStream, metrics: &Metrics) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    debug!("Metrics request: {}", request_line.trim_end());
+    
+    let response = metrics.handle_request(&request_line);
+    writer.write_all(response.as_bytes()).await?;
+    writer.shutdown().await?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_metrics_handler() {
+        let metrics = Metrics::new().unwrap();
+        metrics.mid_price.with_label_values(&["BTCUSDT"]).set(50000.5);
+        metrics.mid_price.with_label_values(&["ETHUSDT"]).set(3000.5);
+        metrics.reconnects_total.with_label_values(&["BTCUSDT"]).inc();
+        
+        let response = metrics.handle_request("GET /metrics HTTP/1.1\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("# TYPE orderflow_mid_price gauge"));
+        assert!(response.contains(r#"orderflow_mid_price{symbol="BTCUSDT"} 50000.5"#));
+        assert!(response.contains(r#"orderflow_mid_price{symbol="ETHUSDT"} 3000.5"#));
+        assert!(response.contains("# TYPE orderflow_reconnects_total counter"));
+        assert!(response.contains(r#"orderflow_reconnects_total{symbol="BTCUSDT"} 1"#));
+        
+        let not_found = metrics.handle_request("GET / HTTP/1.1\r\n");
+        assert!(not_found.starts_with("HTTP/1.1 404"));
+    }
+}
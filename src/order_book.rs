@@ -1,12 +1,18 @@
-use std::collections::BTreeMap;
-use std::sync::RwLock;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::time::{SystemTime, UNIX_EPOCH};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use crate::config::{OrderBookConfig, DEFAULT_DISPLAY_PRECISION};
 use anyhow::Result;
+use thiserror::Error;
+use tokio::sync::broadcast;
 use tracing::{debug, warn};
 
 /// Ordered float wrapper for price precision
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize)]
+#[serde(transparent)]
 pub struct Price(pub f64);
 
 impl Price {
@@ -26,36 +32,343 @@ impl Ord for Price {
     }
 }
 
+/// Tick size used when the symbol's `tickSize` is unknown (Binance quotes at most 8 decimals)
+pub const DEFAULT_TICK_SIZE: f64 = 1e-8;
+
+/// Fixed-point order book key: price as a whole number of ticks
+///
+/// Equivalent price strings (e.g. `50000.10` and `50000.1000`) map to the
+/// same key, unlike exact `f64` comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PriceTicks(pub i64);
+
+/// Order book side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    /// Buy orders
+    Bid,
+    /// Sell orders
+    Ask,
+}
+
+/// Errors from building or updating an order book
+#[derive(Debug, Error)]
+pub enum OrderBookError {
+    #[error("Order book not initialized")]
+    NotInitialized,
+    
+    #[error("Invalid number {value:?}: {source}")]
+    ParseError {
+        value: String,
+        #[source]
+        source: std::num::ParseFloatError,
+    },
+    
+    /// Update IDs were skipped, so levels may be missing
+    #[error("Sequence gap: expected update {expected}, got first update {first_update_id}")]
+    SequenceGap { expected: u64, first_update_id: u64 },
+    
+    /// A futures update's `pu` does not name the last applied update
+    #[error("Sequence break: expected previous update {expected}, got {previous}")]
+    PreviousUpdateMismatch { expected: u64, previous: u64 },
+    
+    #[error("Invalid price value: {0}")]
+    InvalidPrice(String),
+    
+    #[error("Update for {update} cannot be applied to the {book} order book")]
+    SymbolMismatch { book: String, update: String },
+}
+
+impl OrderBookError {
+    /// Whether updates were lost and the book must be rebuilt from a snapshot
+    pub fn needs_resync(&self) -> bool {
+        matches!(self, OrderBookError::SequenceGap { .. } | OrderBookError::PreviousUpdateMismatch { .. })
+    }
+}
+
+/// Rank-by-rank comparison of the local book against a REST snapshot
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DepthDivergence {
+    /// Ranks compared across both sides
+    pub levels_compared: usize,
+    /// Ranks present in only one of the books
+    pub missing_levels: usize,
+    /// Largest price difference at the same rank
+    pub max_price_divergence: f64,
+    /// Largest quantity difference at the same rank, relative to the snapshot
+    pub max_quantity_divergence: f64,
+    pub local_update_id: u64,
+    pub snapshot_update_id: u64,
+}
+
+impl DepthDivergence {
+    /// Whether both books reflect the same update, so their levels should match
+    ///
+    /// A snapshot taken at another update ID differs from the live book by the
+    /// updates in between, which is not drift.
+    pub fn comparable(&self) -> bool {
+        self.local_update_id == self.snapshot_update_id
+    }
+    
+    /// The local book has drifted: a level is missing, a price differs by more
+    /// than `max_price_divergence`, or a quantity differs by more than
+    /// `max_quantity_divergence` (relative)
+    ///
+    /// Always `false` when the books are not `comparable`.
+    pub fn exceeds(&self, max_price_divergence: f64, max_quantity_divergence: f64) -> bool {
+        self.comparable()
+            && (self.missing_levels > 0
+                || self.max_price_divergence > max_price_divergence
+                || self.max_quantity_divergence > max_quantity_divergence)
+    }
+}
+
+/// Why an order book can or cannot be priced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookValidity {
+    Valid,
+    NoBids,
+    NoAsks,
+    /// Best bid above best ask
+    Crossed,
+    /// Best bid equal to best ask
+    ZeroSpread,
+}
+
+impl std::fmt::Display for BookValidity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BookValidity::Valid => write!(f, "valid"),
+            BookValidity::NoBids => write!(f, "no bids"),
+            BookValidity::NoAsks => write!(f, "no asks"),
+            BookValidity::Crossed => write!(f, "crossed"),
+            BookValidity::ZeroSpread => write!(f, "zero spread"),
+        }
+    }
+}
+
 /// Order book level (price and quantity)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct OrderBookLevel {
     pub price: Price,
     pub quantity: f64,
+    #[serde(skip)]
     pub timestamp: u64,
 }
 
+/// Quantity change at a price level present in both books
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelChange {
+    pub price: f64,
+    pub old_quantity: f64,
+    pub new_quantity: f64,
+}
+
+/// Level differences on one side of the book
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SideDiff {
+    /// Levels only in the other book
+    pub added: Vec<OrderBookLevel>,
+    /// Levels only in this book
+    pub removed: Vec<OrderBookLevel>,
+    /// Levels in both books with different quantities
+    pub changed: Vec<LevelChange>,
+}
+
+impl SideDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Differences between two order books, from `self` to `other`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderBookDiff {
+    pub bids: SideDiff,
+    pub asks: SideDiff,
+    /// Largest absolute move in best bid or best ask price between the books
+    pub max_price_deviation: f64,
+}
+
+impl OrderBookDiff {
+    /// The books hold identical levels
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+}
+
+/// Levels per side compared when checking for a top-of-book change
+pub const TOP_OF_BOOK_LEVELS: usize = 5;
+
+/// Depth updates summed by `OrderBookManager::recent_flow`
+pub const DEPTH_FLOW_WINDOW: usize = 100;
+
+/// Owned best bid and ask, safe to send across tasks
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TopOfBook {
+    pub bid_price: f64,
+    pub bid_qty: f64,
+    pub ask_price: f64,
+    pub ask_qty: f64,
+}
+
+impl TopOfBook {
+    fn from_book(order_book: &OrderBook) -> Option<Self> {
+        let (best_bid, best_ask) = (order_book.best_bid()?, order_book.best_ask()?);
+        Some(Self {
+            bid_price: best_bid.price.0,
+            bid_qty: best_bid.quantity,
+            ask_price: best_ask.price.0,
+            ask_qty: best_ask.quantity,
+        })
+    }
+}
+
+/// Spread below which `OrderBookManager` raises a `SpreadAlert`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SpreadThreshold {
+    /// Price units
+    Absolute(f64),
+    /// Basis points of mid
+    Bps(f64),
+}
+
+/// Raised when an update takes the spread below the alert threshold (or crosses the book)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpreadAlert {
+    pub symbol: String,
+    pub spread: f64,
+    pub top_of_book: TopOfBook,
+}
+
+/// Which parts of the top of book an update changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TopOfBookChange {
+    /// Best bid price or quantity changed
+    pub best_bid: bool,
+    /// Best ask price or quantity changed
+    pub best_ask: bool,
+    /// Any of the top `TOP_OF_BOOK_LEVELS` levels on either side changed
+    pub top_levels: bool,
+}
+
+impl TopOfBookChange {
+    /// Whether anything near the touch moved
+    pub fn changed(&self) -> bool {
+        self.best_bid || self.best_ask || self.top_levels
+    }
+}
+
+/// Liquidity binned into equal price bands spanning a basis-point range around mid
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DepthHistogram {
+    /// Mid price the bands are centred on (0 for an empty book)
+    pub mid_price: f64,
+    /// Lower price edge of the first band
+    pub lower_price: f64,
+    /// Width of each band in price units
+    pub bucket_width: f64,
+    /// Resting bid quantity per band, lowest price first
+    pub bid_volume: Vec<f64>,
+    /// Resting ask quantity per band, lowest price first
+    pub ask_volume: Vec<f64>,
+}
+
+/// Point-in-time status of a book for periodic logging
+///
+/// Price fields are `None` when the side they need is empty. `Display` prints
+/// prices with the formatter's precision, e.g. `{:.8}`, defaulting to
+/// `DEFAULT_DISPLAY_PRECISION`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BookSummary {
+    pub symbol: String,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub mid_price: Option<f64>,
+    pub spread: Option<f64>,
+    pub spread_bps: Option<f64>,
+    /// Quantity resting in the top `TOP_OF_BOOK_LEVELS` bid levels
+    pub top_bid_volume: f64,
+    /// Quantity resting in the top `TOP_OF_BOOK_LEVELS` ask levels
+    pub top_ask_volume: f64,
+    pub bid_levels: usize,
+    pub ask_levels: usize,
+    pub last_update: u64,
+}
+
+impl std::fmt::Display for BookSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let precision = f.precision().unwrap_or(DEFAULT_DISPLAY_PRECISION);
+        let price = |value: Option<f64>| value.map_or_else(|| "-".to_string(), |v| format!("{:.*}", precision, v));
+        write!(
+            f,
+            "{} bid={} ask={} mid={} spread={} ({}bps) top{} vol bid={:.4} ask={:.4} levels={}/{} last_update={}",
+            self.symbol,
+            price(self.best_bid),
+            price(self.best_ask),
+            price(self.mid_price),
+            price(self.spread),
+            self.spread_bps.map_or_else(|| "-".to_string(), |bps| format!("{:.2}", bps)),
+            TOP_OF_BOOK_LEVELS,
+            self.top_bid_volume,
+            self.top_ask_volume,
+            self.bid_levels,
+            self.ask_levels,
+            self.last_update,
+        )
+    }
+}
+
+/// How to resolve a crossed book (best bid >= best ask) after an update
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CrossedBookPolicy {
+    /// Leave crossed levels in place and warn (the book reports itself as invalid)
+    #[default]
+    Ignore,
+    /// Trust the latest update and remove opposite-side levels it crosses
+    RemoveCrossed,
+}
+
 /// Complete order book state
 #[derive(Debug, Clone)]
 pub struct OrderBook {
     /// Bids (buy orders) - price descending
-    pub bids: BTreeMap<Price, OrderBookLevel>,
+    pub bids: BTreeMap<PriceTicks, OrderBookLevel>,
     /// Asks (sell orders) - price ascending  
-    pub asks: BTreeMap<Price, OrderBookLevel>,
+    pub asks: BTreeMap<PriceTicks, OrderBookLevel>,
     /// Last update timestamp
     pub last_update: u64,
     /// Symbol
     pub symbol: String,
+    /// Crossed book resolution policy
+    pub crossed_policy: CrossedBookPolicy,
+    /// Minimum price increment used for level keys
+    tick_size: f64,
+    /// 10^(decimal places of `tick_size`), for rounding level prices
+    price_scale: f64,
+    /// Decimal places of `tick_size`
+    tick_decimals: u32,
+    /// `tick_size` in units of 10^-`tick_decimals`, for exact keys from price strings
+    tick_units: i128,
+    /// Whether `last_update` came from a stream update rather than a snapshot
+    stream_sequenced: bool,
 }
 
 /// Order book update from WebSocket
 #[derive(Debug, Deserialize)]
 pub struct OrderBookUpdate {
+    /// Exchange event time (milliseconds)
+    #[serde(rename = "E")]
+    pub event_time: u64,
     #[serde(rename = "s")]
     pub symbol: String,
     #[serde(rename = "U")]
     pub first_update_id: u64,
     #[serde(rename = "u")]
     pub final_update_id: u64,
+    /// Final update ID of the previous event (USD-M futures only)
+    #[serde(rename = "pu", default)]
+    pub prev_final_update_id: Option<u64>,
     #[serde(rename = "b")]
     pub bids: Vec<[String; 2]>,
     #[serde(rename = "a")]
@@ -63,7 +376,7 @@ pub struct OrderBookUpdate {
 }
 
 /// Order book snapshot from REST API
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct OrderBookSnapshot {
     #[serde(rename = "lastUpdateId")]
     pub last_update_id: u64,
@@ -71,10 +384,95 @@ pub struct OrderBookSnapshot {
     pub asks: Vec<[String; 2]>,
 }
 
+/// Partial-book depth frame (`<symbol>@depth<N>` streams)
+///
+/// Each frame carries the full top-N book, so no sequencing is needed.
+#[derive(Debug, Deserialize)]
+pub struct PartialDepth {
+    #[serde(rename = "lastUpdateId")]
+    pub last_update_id: u64,
+    pub bids: Vec<[String; 2]>,
+    pub asks: Vec<[String; 2]>,
+}
+
+/// JSON form of `OrderBook` for audit logging, best price first on each side
+#[derive(Serialize)]
+struct OrderBookView<'a> {
+    symbol: &'a str,
+    last_update: u64,
+    tick_size: f64,
+    bids: Vec<&'a OrderBookLevel>,
+    asks: Vec<&'a OrderBookLevel>,
+}
+
+/// Serializes as `{symbol, last_update, tick_size, bids, asks}` with levels as
+/// `{price, quantity}` objects, best price first
+///
+/// For a form that can be loaded back, use `to_snapshot_json`.
+impl Serialize for OrderBook {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        OrderBookView {
+            symbol: &self.symbol,
+            last_update: self.last_update,
+            tick_size: self.tick_size,
+            bids: self.bids.values().rev().collect(),
+            asks: self.asks.values().collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// On-disk form of a full retained order book
+///
+/// Levels use the REST `[price, quantity]` string layout, best price first.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredOrderBook {
+    symbol: String,
+    last_update: u64,
+    tick_size: f64,
+    bids: Vec<[String; 2]>,
+    asks: Vec<[String; 2]>,
+}
+
+impl OrderBookUpdate {
+    /// Whether the update changes no levels (Binance sends these as keepalives)
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+}
+
+impl From<PartialDepth> for OrderBookSnapshot {
+    fn from(partial: PartialDepth) -> Self {
+        Self {
+            last_update_id: partial.last_update_id,
+            bids: partial.bids,
+            asks: partial.asks,
+        }
+    }
+}
+
+/// USD-M futures partial-book frames use the diff layout; `u` is the book's update ID
+impl From<OrderBookUpdate> for PartialDepth {
+    fn from(update: OrderBookUpdate) -> Self {
+        Self {
+            last_update_id: update.final_update_id,
+            bids: update.bids,
+            asks: update.asks,
+        }
+    }
+}
+
 /// Thread-safe order book manager
 pub struct OrderBookManager {
     order_book: RwLock<Option<OrderBook>>,
     max_depth: usize,
+    crossed_policy: CrossedBookPolicy,
+    tick_size: f64,
+    max_level_age_us: Option<u64>,
+    depth_flow: Mutex<VecDeque<f64>>, // Signed top-level flow of the last DEPTH_FLOW_WINDOW updates
+    spread_threshold: Option<SpreadThreshold>,
+    spread_below_threshold: AtomicBool, // Alert raised and not yet re-armed by a wider spread
+    spread_alerts: broadcast::Sender<SpreadAlert>,
 }
 
 impl OrderBookLevel {
@@ -92,22 +490,210 @@ impl OrderBookLevel {
 
 impl OrderBook {
     pub fn new(symbol: String) -> Self {
+        Self::with_tick_size(symbol, DEFAULT_TICK_SIZE)
+    }
+    
+    /// Create an empty book keyed on the symbol's tick size
+    pub fn with_tick_size(symbol: String, tick_size: f64) -> Self {
+        let tick_size = if tick_size.is_finite() && tick_size > 0.0 {
+            tick_size
+        } else {
+            DEFAULT_TICK_SIZE
+        };
+        let decimals = tick_decimals(tick_size) as u32;
+        let price_scale = 10f64.powi(decimals as i32);
+        
         Self {
             bids: BTreeMap::new(),
             asks: BTreeMap::new(),
             last_update: 0,
             symbol,
+            crossed_policy: CrossedBookPolicy::default(),
+            tick_size,
+            price_scale,
+            tick_decimals: decimals,
+            tick_units: ((tick_size * price_scale).round() as i128).max(1),
+            stream_sequenced: false,
+        }
+    }
+    
+    /// Build a book from a REST depth snapshot, skipping zero-quantity levels
+    pub fn from_snapshot(symbol: &str, snapshot: &OrderBookSnapshot) -> Result<OrderBook, OrderBookError> {
+        Self::from_snapshot_with_tick_size(symbol, snapshot, DEFAULT_TICK_SIZE)
+    }
+    
+    /// Build a book from a REST depth snapshot keyed on the symbol's tick size
+    pub fn from_snapshot_with_tick_size(
+        symbol: &str,
+        snapshot: &OrderBookSnapshot,
+        tick_size: f64,
+    ) -> Result<OrderBook, OrderBookError> {
+        let mut order_book = OrderBook::with_tick_size(symbol.to_string(), tick_size);
+        
+        // Process bids
+        for bid in &snapshot.bids {
+            let (price_key, price, quantity) = order_book.parse_level(bid)?;
+            if quantity > 0.0 {
+                order_book.bids.insert(
+                    price_key, 
+                    OrderBookLevel::new(price, quantity)
+                );
+            }
+        }
+        
+        // Process asks
+        for ask in &snapshot.asks {
+            let (price_key, price, quantity) = order_book.parse_level(ask)?;
+            if quantity > 0.0 {
+                order_book.asks.insert(
+                    price_key, 
+                    OrderBookLevel::new(price, quantity)
+                );
+            }
+        }
+        
+        order_book.last_update = snapshot.last_update_id;
+        Ok(order_book)
+    }
+    
+    /// Get the tick size used for level keys
+    pub fn tick_size(&self) -> f64 {
+        self.tick_size
+    }
+    
+    /// Convert a price to its nearest whole tick
+    pub fn price_ticks(&self, price: f64) -> PriceTicks {
+        PriceTicks((price / self.tick_size).round() as i64)
+    }
+    
+    /// Convert a decimal price string to its nearest whole tick without going through `f64`
+    ///
+    /// `None` for anything but plain decimal notation, or digits beyond `i128`.
+    pub fn decimal_price_ticks(&self, price: &str) -> Option<PriceTicks> {
+        let (negative, digits) = match price.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, price.strip_prefix('+').unwrap_or(price)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let fraction = fraction.trim_end_matches('0');
+        if whole.is_empty() && fraction.is_empty() {
+            return None;
+        }
+        
+        let mut mantissa: i128 = 0;
+        for digit in whole.bytes().chain(fraction.bytes()) {
+            if !digit.is_ascii_digit() {
+                return None;
+            }
+            mantissa = mantissa.checked_mul(10)?.checked_add((digit - b'0') as i128)?;
+        }
+        
+        // price = mantissa / 10^fraction_digits; ticks = price / (tick_units / 10^tick_decimals)
+        let fraction_digits = fraction.len() as u32;
+        let (numerator, denominator) = if fraction_digits >= self.tick_decimals {
+            let scale = 10i128.checked_pow(fraction_digits - self.tick_decimals)?;
+            (mantissa, self.tick_units.checked_mul(scale)?)
+        } else {
+            let scale = 10i128.checked_pow(self.tick_decimals - fraction_digits)?;
+            (mantissa.checked_mul(scale)?, self.tick_units)
+        };
+        
+        // Half a tick rounds away from zero, as `f64::round` does
+        let ticks = numerator.checked_mul(2)?.checked_add(denominator)? / denominator.checked_mul(2)?;
+        let ticks = i64::try_from(ticks).ok()?;
+        Some(PriceTicks(if negative { -ticks } else { ticks }))
+    }
+    
+    /// Convert ticks back to a price
+    pub fn ticks_to_price(&self, ticks: PriceTicks) -> f64 {
+        // Rounding to the tick's decimals sheds 0.1-style representation error
+        (ticks.0 as f64 * self.tick_size * self.price_scale).round() / self.price_scale
+    }
+    
+    /// Serialize the full retained book to JSON
+    pub fn to_snapshot_json(&self) -> String {
+        let to_pair = |level: &OrderBookLevel| [level.price.0.to_string(), level.quantity.to_string()];
+        let stored = StoredOrderBook {
+            symbol: self.symbol.clone(),
+            last_update: self.last_update,
+            tick_size: self.tick_size,
+            bids: self.bids.values().rev().map(to_pair).collect(),
+            asks: self.asks.values().map(to_pair).collect(),
+        };
+        
+        serde_json::to_string_pretty(&stored).expect("order book snapshot is always serializable")
+    }
+    
+    /// Rebuild a book from `to_snapshot_json` output
+    pub fn from_snapshot_json(json: &str) -> Result<OrderBook> {
+        let stored: StoredOrderBook = serde_json::from_str(json)?;
+        let mut order_book = OrderBook::with_tick_size(stored.symbol, stored.tick_size);
+        
+        for bid in &stored.bids {
+            let (price_key, price, quantity) = order_book.parse_level(bid)?;
+            order_book.bids.insert(price_key, OrderBookLevel::new(price, quantity));
+        }
+        for ask in &stored.asks {
+            let (price_key, price, quantity) = order_book.parse_level(ask)?;
+            order_book.asks.insert(price_key, OrderBookLevel::new(price, quantity));
+        }
+        order_book.last_update = stored.last_update;
+        
+        Ok(order_book)
+    }
+    
+    /// Parse a `[price, quantity]` pair into its key, tick-aligned price and quantity
+    fn parse_level(&self, level: &[String; 2]) -> Result<(PriceTicks, f64, f64), OrderBookError> {
+        let parse = |value: &String| {
+            value.parse::<f64>().map_err(|source| OrderBookError::ParseError {
+                value: value.clone(),
+                source,
+            })
+        };
+        let price = parse(&level[0])?;
+        let quantity = parse(&level[1])?;
+        if !price.is_finite() {
+            return Err(OrderBookError::InvalidPrice(level[0].clone()));
+        }
+        
+        let ticks = self
+            .decimal_price_ticks(&level[0])
+            .unwrap_or_else(|| self.price_ticks(price));
+        Ok((ticks, self.ticks_to_price(ticks), quantity))
+    }
+    
+    /// Whether the update predates the book, e.g. an event buffered before the snapshot
+    ///
+    /// Follows Binance's sync rule: once sequenced, events with
+    /// `final_update_id <= last_update` are already reflected in the book.
+    pub fn is_stale(&self, update: &OrderBookUpdate) -> bool {
+        self.last_update > 0 && update.final_update_id <= self.last_update
+    }
+    
+    /// Get the best level of one side (highest bid or lowest ask)
+    pub fn best(&self, side: Side) -> Option<&OrderBookLevel> {
+        match side {
+            Side::Bid => self.bids.values().next_back(), // Last element (highest price)
+            Side::Ask => self.asks.values().next(), // First element (lowest price)
+        }
+    }
+    
+    /// Get the top N levels of one side, best price first
+    pub fn depth(&self, side: Side, n: usize) -> Vec<&OrderBookLevel> {
+        match side {
+            Side::Bid => self.bids.values().rev().take(n).collect(),
+            Side::Ask => self.asks.values().take(n).collect(),
         }
     }
     
     /// Get best bid (highest buy price)
     pub fn best_bid(&self) -> Option<&OrderBookLevel> {
-        self.bids.values().next_back() // Last element (highest price)
+        self.best(Side::Bid)
     }
     
     /// Get best ask (lowest sell price)
     pub fn best_ask(&self) -> Option<&OrderBookLevel> {
-        self.asks.values().next() // First element (lowest price)
+        self.best(Side::Ask)
     }
     
     /// Get bid-ask spread
@@ -126,34 +712,355 @@ impl OrderBook {
         }
     }
     
+    /// Collect the headline numbers of the book into one owned struct
+    pub fn summary(&self) -> BookSummary {
+        let mid_price = self.mid_price();
+        let spread = self.spread();
+        let spread_bps = match (spread, mid_price) {
+            (Some(spread), Some(mid)) if mid > 0.0 => Some(spread / mid * 10_000.0),
+            _ => None,
+        };
+        let (top_bids, top_asks) = self.get_top_levels(TOP_OF_BOOK_LEVELS);
+        
+        BookSummary {
+            symbol: self.symbol.clone(),
+            best_bid: self.best_bid().map(|level| level.price.0),
+            best_ask: self.best_ask().map(|level| level.price.0),
+            mid_price,
+            spread,
+            spread_bps,
+            top_bid_volume: top_bids.iter().map(|level| level.quantity).sum(),
+            top_ask_volume: top_asks.iter().map(|level| level.quantity).sum(),
+            bid_levels: self.bids.len(),
+            ask_levels: self.asks.len(),
+            last_update: self.last_update,
+        }
+    }
+    
     /// Get top N levels from each side
     pub fn get_top_levels(&self, n: usize) -> (Vec<&OrderBookLevel>, Vec<&OrderBookLevel>) {
-        let top_bids: Vec<&OrderBookLevel> = self.bids
+        (self.depth(Side::Bid, n), self.depth(Side::Ask, n))
+    }
+    
+    /// Signed quantity change `update` makes within the top `TOP_OF_BOOK_LEVELS` levels
+    ///
+    /// Added bids and consumed asks count as buying (positive), added asks and
+    /// consumed bids as selling (negative). Call before applying the update.
+    pub fn top_level_flow(&self, update: &OrderBookUpdate) -> Result<f64, OrderBookError> {
+        let bid_floor = self.bids.keys().rev().nth(TOP_OF_BOOK_LEVELS - 1).copied();
+        let ask_ceiling = self.asks.keys().nth(TOP_OF_BOOK_LEVELS - 1).copied();
+        let mut flow = 0.0;
+        
+        for bid in &update.bids {
+            let (price_key, _, quantity) = self.parse_level(bid)?;
+            if !matches!(bid_floor, Some(floor) if price_key < floor) {
+                let previous = self.bids.get(&price_key).map_or(0.0, |level| level.quantity);
+                flow += quantity - previous;
+            }
+        }
+        
+        for ask in &update.asks {
+            let (price_key, _, quantity) = self.parse_level(ask)?;
+            if !matches!(ask_ceiling, Some(ceiling) if price_key > ceiling) {
+                let previous = self.asks.get(&price_key).map_or(0.0, |level| level.quantity);
+                flow -= quantity - previous;
+            }
+        }
+        
+        Ok(flow)
+    }
+    
+    /// Price and quantity of the top `TOP_OF_BOOK_LEVELS` levels of one side, best first
+    fn top_of_book(&self, side: Side) -> Vec<(f64, f64)> {
+        self.depth(side, TOP_OF_BOOK_LEVELS)
+            .iter()
+            .map(|level| (level.price.0, level.quantity))
+            .collect()
+    }
+    
+    /// Get cumulative depth for the top N levels of one side
+    ///
+    /// Returns `(price, level_qty, cumulative_qty)` ordered from best price outward.
+    pub fn cumulative_depth(&self, side: Side, n: usize) -> Vec<(f64, f64, f64)> {
+        let mut cumulative_qty = 0.0;
+        self.depth(side, n)
+            .iter()
+            .map(|level| {
+                cumulative_qty += level.quantity;
+                (level.price.0, level.quantity, cumulative_qty)
+            })
+            .collect()
+    }
+    
+    /// Group levels into price buckets and return the top N buckets
+    ///
+    /// Bid prices round down and ask prices round up to multiples of `bucket`.
+    /// Returns `(bucket_price, total_qty)`; `bucket <= 0` returns raw levels.
+    pub fn group_levels(&self, side: Side, bucket: f64, n: usize) -> Vec<(f64, f64)> {
+        let levels: Box<dyn Iterator<Item = &OrderBookLevel>> = match side {
+            Side::Bid => Box::new(self.bids.values().rev()),
+            Side::Ask => Box::new(self.asks.values()),
+        };
+        
+        if bucket <= 0.0 {
+            return levels
+                .take(n)
+                .map(|level| (level.price.0, level.quantity))
+                .collect();
+        }
+        
+        let mut groups: Vec<(f64, f64)> = Vec::new();
+        for level in levels {
+            let bucket_price = match side {
+                Side::Bid => (level.price.0 / bucket).floor() * bucket,
+                Side::Ask => (level.price.0 / bucket).ceil() * bucket,
+            };
+            
+            // Levels are visited best-first, so buckets arrive in order
+            match groups.last_mut() {
+                Some((price, quantity)) if *price == bucket_price => *quantity += level.quantity,
+                _ => {
+                    if groups.len() == n {
+                        break;
+                    }
+                    groups.push((bucket_price, level.quantity));
+                }
+            }
+        }
+        
+        groups
+    }
+    
+    /// Get total bid and ask notional within a basis-point band of mid
+    pub fn notional_within_bps(&self, bps: f64) -> (f64, f64) {
+        let mid = match self.mid_price() {
+            Some(mid) if mid > 0.0 => mid,
+            _ => return (0.0, 0.0),
+        };
+        
+        let lower = mid * (1.0 - bps / 10_000.0);
+        let upper = mid * (1.0 + bps / 10_000.0);
+        
+        let bid_notional: f64 = self.bids
             .values()
-            .rev() // Reverse to get highest prices first
-            .take(n)
-            .collect();
+            .rev()
+            .take_while(|level| level.price.0 >= lower)
+            .map(|level| level.price.0 * level.quantity)
+            .sum();
             
-        let top_asks: Vec<&OrderBookLevel> = self.asks
+        let ask_notional: f64 = self.asks
             .values()
-            .take(n)
-            .collect();
+            .take_while(|level| level.price.0 <= upper)
+            .map(|level| level.price.0 * level.quantity)
+            .sum();
             
-        (top_bids, top_asks)
+        (bid_notional, ask_notional)
+    }
+    
+    /// Bin resting quantity into `buckets` bands spanning ±`range_bps` around mid
+    ///
+    /// Levels outside the range are left out. Books without a mid price, or a
+    /// non-positive range, give zeroed buckets.
+    pub fn depth_histogram(&self, buckets: usize, range_bps: f64) -> DepthHistogram {
+        let mut histogram = DepthHistogram {
+            bid_volume: vec![0.0; buckets],
+            ask_volume: vec![0.0; buckets],
+            ..DepthHistogram::default()
+        };
+        
+        let mid = match self.mid_price() {
+            Some(mid) if mid > 0.0 => mid,
+            _ => return histogram,
+        };
+        if buckets == 0 || range_bps.is_nan() || range_bps <= 0.0 {
+            return histogram;
+        }
+        
+        let lower = mid * (1.0 - range_bps / 10_000.0);
+        let upper = mid * (1.0 + range_bps / 10_000.0);
+        let width = (upper - lower) / buckets as f64;
+        histogram.mid_price = mid;
+        histogram.lower_price = lower;
+        histogram.bucket_width = width;
+        
+        // The upper edge belongs to the last band
+        let bucket = |price: f64| (((price - lower) / width) as usize).min(buckets - 1);
+        for level in self.bids.values().rev().take_while(|level| level.price.0 >= lower) {
+            if level.price.0 <= upper {
+                histogram.bid_volume[bucket(level.price.0)] += level.quantity;
+            }
+        }
+        for level in self.asks.values().take_while(|level| level.price.0 <= upper) {
+            if level.price.0 >= lower {
+                histogram.ask_volume[bucket(level.price.0)] += level.quantity;
+            }
+        }
+        
+        histogram
+    }
+    
+    /// Get resting quantity at a price level on either side
+    ///
+    /// The price is rounded to the nearest tick, so re-parsed prices still hit.
+    pub fn quantity_at_price(&self, price: f64) -> Option<f64> {
+        if !price.is_finite() {
+            return None;
+        }
+        
+        let ticks = self.price_ticks(price);
+        self.bids
+            .get(&ticks)
+            .or_else(|| self.asks.get(&ticks))
+            .map(|level| level.quantity)
+    }
+    
+    /// Get the resting level on one side closest to a target price
+    ///
+    /// Ties resolve toward the better price for the side.
+    pub fn nearest_level(&self, side: Side, price: f64) -> Option<&OrderBookLevel> {
+        if !price.is_finite() {
+            return None;
+        }
+        
+        let levels = match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        };
+        
+        let ticks = price / self.tick_size;
+        let below = levels
+            .range(..=PriceTicks(ticks.floor() as i64))
+            .next_back()
+            .map(|(_, level)| level);
+        let above = levels
+            .range(PriceTicks(ticks.ceil() as i64)..)
+            .next()
+            .map(|(_, level)| level);
+        
+        match (below, above) {
+            (Some(below), Some(above)) => {
+                let below_distance = price - below.price.0;
+                let above_distance = above.price.0 - price;
+                
+                if below_distance < above_distance {
+                    Some(below)
+                } else if above_distance < below_distance {
+                    Some(above)
+                } else {
+                    match side {
+                        Side::Bid => Some(above),
+                        Side::Ask => Some(below),
+                    }
+                }
+            }
+            (below, above) => below.or(above),
+        }
+    }
+    
+    /// Total bid quantity across all retained levels
+    pub fn total_bid_volume(&self) -> f64 {
+        self.bids.values().map(|level| level.quantity).sum()
+    }
+    
+    /// Total ask quantity across all retained levels
+    pub fn total_ask_volume(&self) -> f64 {
+        self.asks.values().map(|level| level.quantity).sum()
+    }
+    
+    /// Quantity-weighted average price across all retained levels of one side
+    pub fn full_book_vwap(&self, side: Side) -> Option<f64> {
+        let levels = match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        };
+        
+        let (notional, quantity) = levels
+            .values()
+            .fold((0.0, 0.0), |(notional, quantity), level| {
+                (notional + level.price.0 * level.quantity, quantity + level.quantity)
+            });
+        
+        if quantity > 0.0 {
+            Some(notional / quantity)
+        } else {
+            None
+        }
+    }
+    
+    /// Check if best bid is at or above best ask
+    pub fn is_crossed(&self) -> bool {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => bid.price >= ask.price,
+            _ => false,
+        }
     }
     
     /// Check if order book has valid data
     pub fn is_valid(&self) -> bool {
-        !self.bids.is_empty() && !self.asks.is_empty() && self.spread().unwrap_or(-1.0) > 0.0
+        self.validity() == BookValidity::Valid
+    }
+    
+    /// Classify the book, reporting why it cannot be priced
+    pub fn validity(&self) -> BookValidity {
+        match (self.best_bid(), self.best_ask()) {
+            (None, _) => BookValidity::NoBids,
+            (_, None) => BookValidity::NoAsks,
+            (Some(bid), Some(ask)) if bid.price.0 > ask.price.0 => BookValidity::Crossed,
+            (Some(bid), Some(ask)) if bid.price.0 == ask.price.0 => BookValidity::ZeroSpread,
+            _ => BookValidity::Valid,
+        }
     }
     
     /// Apply order book update
-    pub fn apply_update(&mut self, update: &OrderBookUpdate) -> Result<()> {
+    ///
+    /// Rejects updates for another symbol, and updates whose first ID skips
+    /// past `last_update + 1` once the book has been sequenced. Futures updates
+    /// carry `pu`, which must equal the previous update's final ID exactly
+    /// (their IDs are not contiguous); the first update after a snapshot
+    /// straddles it, so it gets the spot check instead. Stale updates (see
+    /// `is_stale`) are skipped without error.
+    pub fn apply_update(&mut self, update: &OrderBookUpdate) -> Result<(), OrderBookError> {
+        if !update.symbol.eq_ignore_ascii_case(&self.symbol) {
+            return Err(OrderBookError::SymbolMismatch {
+                book: self.symbol.clone(),
+                update: update.symbol.clone(),
+            });
+        }
+        
+        if self.is_stale(update) {
+            debug!(
+                "Skipping stale update {} for {} (book at {})",
+                update.final_update_id, self.symbol, self.last_update
+            );
+            return Ok(());
+        }
+        
+        match update.prev_final_update_id {
+            Some(previous) if self.stream_sequenced => {
+                if previous != self.last_update {
+                    return Err(OrderBookError::PreviousUpdateMismatch {
+                        expected: self.last_update,
+                        previous,
+                    });
+                }
+            }
+            _ => {
+                if self.last_update > 0 && update.first_update_id > self.last_update + 1 {
+                    return Err(OrderBookError::SequenceGap {
+                        expected: self.last_update + 1,
+                        first_update_id: update.first_update_id,
+                    });
+                }
+            }
+        }
+        
+        // Highest bid and lowest ask touched by this update
+        let mut update_best_bid: Option<f64> = None;
+        let mut update_best_ask: Option<f64> = None;
+        
         // Update bids
         for bid in &update.bids {
-            let price = bid[0].parse::<f64>()?;
-            let quantity = bid[1].parse::<f64>()?;
-            let price_key = Price::new(price);
+            let (price_key, price, quantity) = self.parse_level(bid)?;
             
             if quantity == 0.0 {
                 // Remove level if quantity is zero
@@ -161,14 +1068,13 @@ impl OrderBook {
             } else {
                 // Update or insert level
                 self.bids.insert(price_key, OrderBookLevel::new(price, quantity));
+                update_best_bid = Some(update_best_bid.map_or(price, |best| best.max(price)));
             }
         }
         
         // Update asks
         for ask in &update.asks {
-            let price = ask[0].parse::<f64>()?;
-            let quantity = ask[1].parse::<f64>()?;
-            let price_key = Price::new(price);
+            let (price_key, price, quantity) = self.parse_level(ask)?;
             
             if quantity == 0.0 {
                 // Remove level if quantity is zero
@@ -176,10 +1082,26 @@ impl OrderBook {
             } else {
                 // Update or insert level
                 self.asks.insert(price_key, OrderBookLevel::new(price, quantity));
+                update_best_ask = Some(update_best_ask.map_or(price, |best| best.min(price)));
             }
         }
         
-        self.last_update = update.final_update_id;
+        self.last_update = self.last_update.max(update.final_update_id);
+        self.stream_sequenced = true;
+        
+        if self.is_crossed() {
+            warn!(
+                "Crossed order book for {} - Best bid: {:.4}, Best ask: {:.4}",
+                self.symbol,
+                self.best_bid().map_or(0.0, |b| b.price.0),
+                self.best_ask().map_or(0.0, |a| a.price.0)
+            );
+            
+            if self.crossed_policy == CrossedBookPolicy::RemoveCrossed {
+                let removed = self.remove_crossed_levels(update_best_bid, update_best_ask);
+                debug!("Removed {} crossed levels", removed);
+            }
+        }
         
         debug!(
             "Order book updated - Bids: {}, Asks: {}, Spread: {:.4}",
@@ -190,103 +1112,462 @@ impl OrderBook {
         
         Ok(())
     }
-}
-
-impl OrderBookManager {
-    pub fn new() -> Self {
-        Self {
-            order_book: RwLock::new(None),
-            max_depth: 100,
+    
+    /// Compare this book against another, e.g. a local book against a fresh REST snapshot
+    pub fn diff(&self, other: &OrderBook) -> OrderBookDiff {
+        let top_deviation = |a: Option<&OrderBookLevel>, b: Option<&OrderBookLevel>| match (a, b) {
+            (Some(a), Some(b)) => (a.price.0 - b.price.0).abs(),
+            _ => 0.0,
+        };
+        
+        OrderBookDiff {
+            bids: self.diff_side(&self.bids, &other.bids),
+            asks: self.diff_side(&self.asks, &other.asks),
+            max_price_deviation: top_deviation(self.best_bid(), other.best_bid())
+                .max(top_deviation(self.best_ask(), other.best_ask())),
+        }
+    }
+    
+    fn diff_side(
+        &self,
+        ours: &BTreeMap<PriceTicks, OrderBookLevel>,
+        theirs: &BTreeMap<PriceTicks, OrderBookLevel>,
+    ) -> SideDiff {
+        // Re-key the other side on our tick size in case the books were built differently
+        let theirs: BTreeMap<PriceTicks, &OrderBookLevel> = theirs
+            .values()
+            .map(|level| (self.price_ticks(level.price.0), level))
+            .collect();
+        let mut diff = SideDiff::default();
+        
+        for (price, level) in ours {
+            match theirs.get(price) {
+                None => diff.removed.push(level.clone()),
+                Some(other) if other.quantity != level.quantity => diff.changed.push(LevelChange {
+                    price: level.price.0,
+                    old_quantity: level.quantity,
+                    new_quantity: other.quantity,
+                }),
+                Some(_) => {}
+            }
+        }
+        
+        for (price, level) in &theirs {
+            if !ours.contains_key(price) {
+                diff.added.push((*level).clone());
+            }
+        }
+        
+        diff
+    }
+    
+    /// Remove levels not updated within `max_age_micros` of `now` (microseconds)
+    ///
+    /// Returns the number of levels removed.
+    pub fn prune_stale_levels(&mut self, max_age_micros: u64, now: u64) -> usize {
+        let before = self.bids.len() + self.asks.len();
+        let is_fresh = |level: &OrderBookLevel| now.saturating_sub(level.timestamp) <= max_age_micros;
+        
+        self.bids.retain(|_, level| is_fresh(level));
+        self.asks.retain(|_, level| is_fresh(level));
+        
+        before - self.bids.len() - self.asks.len()
+    }
+    
+    /// Remove levels crossed by the latest update, trusting the newer prices
+    fn remove_crossed_levels(&mut self, update_best_bid: Option<f64>, update_best_ask: Option<f64>) -> usize {
+        let mut removed = 0;
+        
+        // A new ask invalidates resting bids at or above it
+        if let Some(ask_price) = update_best_ask {
+            let stale: Vec<PriceTicks> = self.bids
+                .range(self.price_ticks(ask_price)..)
+                .map(|(price, _)| *price)
+                .collect();
+            removed += stale.len();
+            for price in stale {
+                self.bids.remove(&price);
+            }
+        }
+        
+        // A new bid invalidates resting asks at or below it
+        if let Some(bid_price) = update_best_bid {
+            if self.is_crossed() {
+                let stale: Vec<PriceTicks> = self.asks
+                    .range(..=self.price_ticks(bid_price))
+                    .map(|(price, _)| *price)
+                    .collect();
+                removed += stale.len();
+                for price in stale {
+                    self.asks.remove(&price);
+                }
+            }
         }
+        
+        removed
+    }
+}
+
+impl OrderBookManager {
+    pub fn new() -> Self {
+        Self::with_max_depth(100)
     }
     
     pub fn with_max_depth(max_depth: usize) -> Self {
+        let (spread_alerts, _) = broadcast::channel(16);
         Self {
             order_book: RwLock::new(None),
             max_depth,
+            crossed_policy: CrossedBookPolicy::default(),
+            tick_size: DEFAULT_TICK_SIZE,
+            max_level_age_us: None,
+            depth_flow: Mutex::new(VecDeque::new()),
+            spread_threshold: None,
+            spread_below_threshold: AtomicBool::new(false),
+            spread_alerts,
+        }
+    }
+    
+    /// Create a manager from the order book configuration
+    pub fn from_config(config: &OrderBookConfig) -> Self {
+        Self {
+            crossed_policy: config.crossed_book_policy,
+            max_level_age_us: config.max_level_age_us,
+            ..Self::with_max_depth(config.max_depth)
         }
     }
     
+    /// Raise a `SpreadAlert` whenever an update takes the spread below `threshold`
+    ///
+    /// Fires once per crossing; the alert re-arms when the spread widens back
+    /// to the threshold.
+    pub fn with_spread_alert(mut self, threshold: SpreadThreshold) -> Self {
+        self.spread_threshold = Some(threshold);
+        self
+    }
+    
+    /// Subscribe to spread alerts
+    pub fn subscribe_spread_alerts(&self) -> broadcast::Receiver<SpreadAlert> {
+        self.spread_alerts.subscribe()
+    }
+    
+    /// Key levels on the symbol's tick size (from exchangeInfo `PRICE_FILTER`)
+    pub fn with_tick_size(mut self, tick_size: f64) -> Self {
+        self.tick_size = tick_size;
+        self
+    }
+    
+    /// Lock the book for reading
+    ///
+    /// A panic while the lock was held poisons it; the guard is recovered and
+    /// the poison cleared so one panic does not take every later caller down.
+    fn read_book(&self) -> RwLockReadGuard<'_, Option<OrderBook>> {
+        self.order_book.read().unwrap_or_else(|poisoned| {
+            warn!("Order book lock poisoned by a panic, recovering");
+            self.order_book.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+    
+    /// Lock the book for writing, recovering from poisoning like `read_book`
+    fn write_book(&self) -> RwLockWriteGuard<'_, Option<OrderBook>> {
+        self.order_book.write().unwrap_or_else(|poisoned| {
+            warn!("Order book lock poisoned by a panic, recovering");
+            self.order_book.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+    
+    /// Lock the depth flow window, recovering from poisoning like `read_book`
+    fn lock_depth_flow(&self) -> MutexGuard<'_, VecDeque<f64>> {
+        self.depth_flow.lock().unwrap_or_else(|poisoned| {
+            warn!("Depth flow lock poisoned by a panic, recovering");
+            self.depth_flow.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+    
     /// Initialize order book from snapshot
-    pub fn initialize_from_snapshot(&self, symbol: &str, snapshot: OrderBookSnapshot) -> Result<()> {
-        let mut order_book = OrderBook::new(symbol.to_string());
+    ///
+    /// The new book is built before taking the write lock and swapped in with
+    /// a single write, so readers see either the old book or the new one.
+    pub fn initialize_from_snapshot(&self, symbol: &str, snapshot: OrderBookSnapshot) -> Result<(), OrderBookError> {
+        let order_book = self.book_from_snapshot(symbol, &snapshot)?;
         
-        // Process bids
-        for bid in &snapshot.bids {
-            let price = bid[0].parse::<f64>()?;
-            let quantity = bid[1].parse::<f64>()?;
-            if quantity > 0.0 {
-                order_book.bids.insert(
-                    Price::new(price), 
-                    OrderBookLevel::new(price, quantity)
-                );
-            }
+        let mut book_guard = self.write_book();
+        *book_guard = Some(order_book);
+        self.lock_depth_flow().clear();
+        self.spread_below_threshold.store(false, Ordering::Relaxed);
+        
+        debug!("Order book initialized from snapshot");
+        Ok(())
+    }
+    
+    /// Replace the book with a snapshot unless the snapshot is behind it
+    ///
+    /// A snapshot older than the live book would rewind it and turn the next
+    /// diff into a sequence gap, so it is discarded and the book kept. The
+    /// check and the swap happen under one write lock. Returns whether the
+    /// book was replaced.
+    pub fn refresh_from_snapshot(&self, symbol: &str, snapshot: OrderBookSnapshot) -> Result<bool, OrderBookError> {
+        let order_book = self.book_from_snapshot(symbol, &snapshot)?;
+        
+        let mut book_guard = self.write_book();
+        if let Some(current) = book_guard.as_ref().filter(|current| snapshot.last_update_id < current.last_update) {
+            debug!(
+                "Discarding snapshot {} behind order book update {}",
+                snapshot.last_update_id, current.last_update
+            );
+            return Ok(false);
         }
+        *book_guard = Some(order_book);
+        self.lock_depth_flow().clear();
+        self.spread_below_threshold.store(false, Ordering::Relaxed);
         
-        // Process asks
-        for ask in &snapshot.asks {
-            let price = ask[0].parse::<f64>()?;
-            let quantity = ask[1].parse::<f64>()?;
-            if quantity > 0.0 {
-                order_book.asks.insert(
-                    Price::new(price), 
-                    OrderBookLevel::new(price, quantity)
-                );
+        debug!("Order book refreshed from snapshot");
+        Ok(true)
+    }
+    
+    /// Compare the local top levels against a fresh REST snapshot
+    ///
+    /// Levels are compared by rank down to the shallower of the snapshot and
+    /// the maintained depth.
+    pub fn verify_against_snapshot(&self, snapshot: &OrderBookSnapshot) -> Result<DepthDivergence, OrderBookError> {
+        let book_guard = self.read_book();
+        let local = book_guard.as_ref().ok_or(OrderBookError::NotInitialized)?;
+        let reference = self.book_from_snapshot(&local.symbol, snapshot)?;
+        
+        let mut divergence = DepthDivergence {
+            local_update_id: local.last_update,
+            snapshot_update_id: snapshot.last_update_id,
+            ..Default::default()
+        };
+        
+        let sides = [
+            (
+                local.bids.values().rev().collect::<Vec<_>>(),
+                reference.bids.values().rev().collect::<Vec<_>>(),
+            ),
+            (
+                local.asks.values().collect::<Vec<_>>(),
+                reference.asks.values().collect::<Vec<_>>(),
+            ),
+        ];
+        for (local_levels, snapshot_levels) in sides {
+            let depth = snapshot_levels.len().min(self.max_depth);
+            let local_depth = local_levels.len().min(depth);
+            divergence.levels_compared += depth;
+            divergence.missing_levels += depth - local_depth;
+            
+            for (ours, theirs) in local_levels.iter().zip(&snapshot_levels).take(depth) {
+                let price_divergence = (ours.price.0 - theirs.price.0).abs();
+                let quantity_divergence = (ours.quantity - theirs.quantity).abs() / theirs.quantity;
+                divergence.max_price_divergence = divergence.max_price_divergence.max(price_divergence);
+                divergence.max_quantity_divergence = divergence.max_quantity_divergence.max(quantity_divergence);
             }
         }
         
-        order_book.last_update = snapshot.last_update_id;
+        Ok(divergence)
+    }
+    
+    /// Build a book from a REST snapshot using this manager's settings
+    fn book_from_snapshot(&self, symbol: &str, snapshot: &OrderBookSnapshot) -> Result<OrderBook, OrderBookError> {
+        let mut order_book = OrderBook::from_snapshot_with_tick_size(symbol, snapshot, self.tick_size)?;
+        order_book.crossed_policy = self.crossed_policy;
         
         // Trim to max depth
         self.trim_to_depth(&mut order_book);
         
-        let mut book_guard = self.order_book.write().unwrap();
-        *book_guard = Some(order_book);
-        
-        debug!("Order book initialized from snapshot");
-        Ok(())
+        Ok(order_book)
+    }
+    
+    /// Replace the whole book with a partial-depth frame
+    pub fn replace_from_partial_depth(&self, symbol: &str, partial: PartialDepth) -> Result<(), OrderBookError> {
+        self.initialize_from_snapshot(symbol, partial.into())
     }
     
     /// Apply incremental update
-    pub fn apply_update(&self, update: OrderBookUpdate) -> Result<()> {
-        let mut book_guard = self.order_book.write().unwrap();
+    ///
+    /// Reports which parts of the top of book changed, so callers can skip
+    /// work when only levels far from the touch moved.
+    pub fn apply_update(&self, update: OrderBookUpdate) -> Result<TopOfBookChange, OrderBookError> {
+        let mut book_guard = self.write_book();
         
         match book_guard.as_mut() {
+            // Only advance the sequence so the next update doesn't look like a gap;
+            // stale updates are skipped before they can count as flow
+            Some(order_book) if update.is_empty() || order_book.is_stale(&update) => {
+                order_book.apply_update(&update)?;
+                Ok(TopOfBookChange::default())
+            }
             Some(order_book) => {
+                let bids_before = order_book.top_of_book(Side::Bid);
+                let asks_before = order_book.top_of_book(Side::Ask);
+                let flow = order_book.top_level_flow(&update)?;
                 order_book.apply_update(&update)?;
+                self.record_flow(flow);
                 self.trim_to_depth(order_book);
-                Ok(())
+                
+                if let Some(max_age_us) = self.max_level_age_us {
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |elapsed| elapsed.as_micros() as u64);
+                    let pruned = order_book.prune_stale_levels(max_age_us, now);
+                    if pruned > 0 {
+                        debug!("Pruned {} stale levels", pruned);
+                    }
+                }
+                
+                self.check_spread_alert(order_book);
+                
+                let bids_after = order_book.top_of_book(Side::Bid);
+                let asks_after = order_book.top_of_book(Side::Ask);
+                Ok(TopOfBookChange {
+                    best_bid: bids_before.first() != bids_after.first(),
+                    best_ask: asks_before.first() != asks_after.first(),
+                    top_levels: bids_before != bids_after || asks_before != asks_after,
+                })
             }
             None => {
                 warn!("Received update before initialization");
-                Err(anyhow::anyhow!("Order book not initialized"))
+                Err(OrderBookError::NotInitialized)
+            }
+        }
+    }
+    
+    /// Raise a spread alert if the spread just dropped below the threshold
+    fn check_spread_alert(&self, order_book: &OrderBook) {
+        let (Some(threshold), Some(spread), Some(mid)) =
+            (self.spread_threshold, order_book.spread(), order_book.mid_price())
+        else {
+            return;
+        };
+        let below = match threshold {
+            SpreadThreshold::Absolute(max_spread) => spread < max_spread,
+            SpreadThreshold::Bps(max_bps) => mid > 0.0 && spread / mid * 10_000.0 < max_bps,
+        } || spread <= 0.0;
+        
+        let was_below = self.spread_below_threshold.swap(below, Ordering::Relaxed);
+        if below && !was_below {
+            if let Some(top_of_book) = TopOfBook::from_book(order_book) {
+                debug!("Spread for {} dropped to {}", order_book.symbol, spread);
+                
+                // No receivers just means nobody is listening for alerts
+                let _ = self.spread_alerts.send(SpreadAlert {
+                    symbol: order_book.symbol.clone(),
+                    spread,
+                    top_of_book,
+                });
+            }
+        }
+    }
+    
+    /// Add one update's top-level flow to the rolling window
+    fn record_flow(&self, flow: f64) {
+        let mut depth_flow = self.lock_depth_flow();
+        depth_flow.push_back(flow);
+        if depth_flow.len() > DEPTH_FLOW_WINDOW {
+            depth_flow.pop_front();
+        }
+    }
+    
+    /// Book-derived order flow over the last `DEPTH_FLOW_WINDOW` updates
+    ///
+    /// Positive when bids were added or asks consumed near the touch, negative
+    /// for the reverse. Unlike `TradeFlow` this needs no trade stream.
+    pub fn recent_flow(&self) -> f64 {
+        self.lock_depth_flow().iter().sum()
+    }
+    
+    /// Drop the current book so `is_ready` reports false until re-initialized
+    pub fn clear(&self) {
+        let mut book_guard = self.write_book();
+        self.lock_depth_flow().clear();
+        self.spread_below_threshold.store(false, Ordering::Relaxed);
+        if book_guard.take().is_some() {
+            debug!("Order book cleared");
+        }
+    }
+    
+    /// Drop the current book only if it belongs to `symbol`
+    ///
+    /// Returns whether a book was cleared.
+    pub fn clear_symbol(&self, symbol: &str) -> bool {
+        let mut book_guard = self.write_book();
+        match book_guard.as_ref() {
+            Some(order_book) if order_book.symbol.eq_ignore_ascii_case(symbol) => {
+                *book_guard = None;
+                self.lock_depth_flow().clear();
+                self.spread_below_threshold.store(false, Ordering::Relaxed);
+                debug!("Order book for {} cleared", symbol);
+                true
             }
+            _ => false,
         }
     }
     
     /// Get current order book snapshot
     pub fn get_order_book(&self) -> Option<OrderBook> {
-        let book_guard = self.order_book.read().unwrap();
+        let book_guard = self.read_book();
         book_guard.clone()
     }
     
+    /// Run a closure against the current order book under the read lock
+    ///
+    /// Avoids cloning the whole book; returns `None` if not initialized.
+    pub fn with_order_book<R>(&self, f: impl FnOnce(&OrderBook) -> R) -> Option<R> {
+        let book_guard = self.read_book();
+        book_guard.as_ref().map(f)
+    }
+    
+    /// Write the current book to a JSON file
+    pub fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<()> {
+        let json = self
+            .with_order_book(|order_book| order_book.to_snapshot_json())
+            .ok_or(OrderBookError::NotInitialized)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+    
+    /// Replace the current book with one saved by `save_snapshot`
+    pub fn load_snapshot(&self, path: impl AsRef<Path>) -> Result<()> {
+        let json = std::fs::read_to_string(path)?;
+        let mut order_book = OrderBook::from_snapshot_json(&json)?;
+        order_book.crossed_policy = self.crossed_policy;
+        self.trim_to_depth(&mut order_book);
+        
+        let mut book_guard = self.write_book();
+        *book_guard = Some(order_book);
+        self.lock_depth_flow().clear();
+        self.spread_below_threshold.store(false, Ordering::Relaxed);
+        
+        debug!("Order book loaded from snapshot file");
+        Ok(())
+    }
+    
     /// Get current mid price
     pub fn get_mid_price(&self) -> Option<f64> {
-        let book_guard = self.order_book.read().unwrap();
+        let book_guard = self.read_book();
         book_guard.as_ref()?.mid_price()
     }
     
     /// Get current spread
     pub fn get_spread(&self) -> Option<f64> {
-        let book_guard = self.order_book.read().unwrap();
+        let book_guard = self.read_book();
         book_guard.as_ref()?.spread()
     }
     
+    /// Get an owned copy of the best bid and ask; `None` until both sides exist
+    pub fn top_of_book(&self) -> Option<TopOfBook> {
+        let book_guard = self.read_book();
+        TopOfBook::from_book(book_guard.as_ref()?)
+    }
+    
     /// Trim order book to maximum depth
     fn trim_to_depth(&self, order_book: &mut OrderBook) {
         // Keep only top N bids (highest prices)
         if order_book.bids.len() > self.max_depth {
-            let keys_to_remove: Vec<Price> = order_book.bids
+            let keys_to_remove: Vec<PriceTicks> = order_book.bids
                 .keys()
                 .cloned()
                 .take(order_book.bids.len() - self.max_depth)
@@ -299,7 +1580,7 @@ impl OrderBookManager {
         
         // Keep only top N asks (lowest prices)
         if order_book.asks.len() > self.max_depth {
-            let keys_to_remove: Vec<Price> = order_book.asks
+            let keys_to_remove: Vec<PriceTicks> = order_book.asks
                 .keys()
                 .cloned()
                 .skip(self.max_depth)
@@ -313,7 +1594,7 @@ impl OrderBookManager {
     
     /// Check if order book is ready
     pub fn is_ready(&self) -> bool {
-        let book_guard = self.order_book.read().unwrap();
+        let book_guard = self.read_book();
         book_guard.as_ref().map_or(false, |book| book.is_valid())
     }
 }
@@ -322,4 +1603,975 @@ impl Default for OrderBookManager {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// Decimal places in a tick size, as `SymbolInfo::display_precision` counts them
+fn tick_decimals(tick_size: f64) -> usize {
+    let formatted = tick_size.to_string();
+    formatted.split_once('.').map_or(0, |(_, decimals)| decimals.trim_end_matches('0').len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn update_with_ids(first: u64, last: u64, bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderBookUpdate {
+        OrderBookUpdate {
+            event_time: 0,
+            symbol: "BTCUSDT".to_string(),
+            first_update_id: first,
+            final_update_id: last,
+            prev_final_update_id: None,
+            bids: bids.iter().map(|(p, q)| [p.to_string(), q.to_string()]).collect(),
+            asks: asks.iter().map(|(p, q)| [p.to_string(), q.to_string()]).collect(),
+        }
+    }
+    
+    /// The update directly after `book`'s last one
+    fn update(book: &OrderBook, bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderBookUpdate {
+        let next = book.last_update + 1;
+        update_with_ids(next, next, bids, asks)
+    }
+    
+    /// The update directly after the last one applied to `manager`'s book
+    fn manager_update(manager: &OrderBookManager, bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderBookUpdate {
+        let next = manager.with_order_book(|book| book.last_update).unwrap_or(0) + 1;
+        update_with_ids(next, next, bids, asks)
+    }
+    
+    fn book(policy: CrossedBookPolicy) -> OrderBook {
+        let mut order_book = OrderBook::new("BTCUSDT".to_string());
+        order_book.crossed_policy = policy;
+        order_book
+            .apply_update(&update(
+                &order_book,
+                &[("100.0", "1.0"), ("99.0", "2.0")],
+                &[("101.0", "1.0"), ("102.0", "2.0")],
+            ))
+            .unwrap();
+        order_book
+    }
+    
+    #[test]
+    fn test_parse_depth_update() {
+        let payload = r#"{
+            "e": "depthUpdate", "E": 1672515782136, "s": "BNBBTC",
+            "U": 157, "u": 160,
+            "b": [["0.0024", "10"]],
+            "a": [["0.0026", "100"]]
+        }"#;
+        
+        let update: OrderBookUpdate = serde_json::from_str(payload).unwrap();
+        assert_eq!(update.event_time, 1672515782136);
+        assert_eq!(update.symbol, "BNBBTC");
+        assert_eq!(update.first_update_id, 157);
+        assert_eq!(update.final_update_id, 160);
+    }
+    
+    #[test]
+    fn test_parse_partial_depth() {
+        let payload = r#"{
+            "lastUpdateId": 160,
+            "bids": [["0.0024", "10"], ["0.0023", "5"]],
+            "asks": [["0.0026", "100"]]
+        }"#;
+        
+        let partial: PartialDepth = serde_json::from_str(payload).unwrap();
+        assert_eq!(partial.last_update_id, 160);
+        assert_eq!(partial.bids.len(), 2);
+        assert_eq!(partial.bids[0], ["0.0024".to_string(), "10".to_string()]);
+        assert_eq!(partial.asks[0], ["0.0026".to_string(), "100".to_string()]);
+        
+        let manager = OrderBookManager::new();
+        manager.replace_from_partial_depth("BNBBTC", partial).unwrap();
+        let order_book = manager.get_order_book().unwrap();
+        assert_eq!(order_book.best_bid().unwrap().price.0, 0.0024);
+        assert_eq!(order_book.best_ask().unwrap().quantity, 100.0);
+        assert_eq!(order_book.last_update, 160);
+        
+        // The next frame replaces the book entirely
+        let next: PartialDepth = serde_json::from_str(
+            r#"{"lastUpdateId": 161, "bids": [["0.0025", "1"]], "asks": [["0.0027", "2"]]}"#
+        ).unwrap();
+        manager.replace_from_partial_depth("BNBBTC", next).unwrap();
+        let order_book = manager.get_order_book().unwrap();
+        assert_eq!(order_book.bids.len(), 1);
+        assert_eq!(order_book.best_bid().unwrap().price.0, 0.0025);
+    }
+    
+    #[test]
+    fn test_with_order_book() {
+        let manager = OrderBookManager::new();
+        assert_eq!(manager.with_order_book(|book| book.bids.len()), None);
+        
+        manager
+            .initialize_from_snapshot("BTCUSDT", OrderBookSnapshot {
+                last_update_id: 1,
+                bids: vec![["100.0".to_string(), "1.0".to_string()]],
+                asks: vec![["101.0".to_string(), "2.0".to_string()]],
+            })
+            .unwrap();
+        
+        let mid = manager.with_order_book(|book| book.mid_price());
+        assert_eq!(mid, Some(Some(100.5)));
+    }
+    
+    #[test]
+    fn test_side_queries() {
+        let order_book = book(CrossedBookPolicy::Ignore);
+        let prices = |levels: Vec<&OrderBookLevel>| levels.iter().map(|level| level.price.0).collect::<Vec<_>>();
+        
+        assert_eq!(order_book.best(Side::Bid).map(|level| level.price.0), Some(100.0));
+        assert_eq!(order_book.best(Side::Ask).map(|level| level.price.0), Some(101.0));
+        assert_eq!(order_book.best(Side::Bid), order_book.best_bid());
+        assert_eq!(order_book.best(Side::Ask), order_book.best_ask());
+        
+        assert_eq!(prices(order_book.depth(Side::Bid, 5)), vec![100.0, 99.0]);
+        assert_eq!(prices(order_book.depth(Side::Ask, 1)), vec![101.0]);
+        assert!(order_book.depth(Side::Ask, 0).is_empty());
+        
+        let empty = OrderBook::new("BTCUSDT".to_string());
+        assert!(empty.best(Side::Bid).is_none() && empty.depth(Side::Ask, 5).is_empty());
+    }
+    
+    #[test]
+    fn test_cumulative_depth() {
+        let order_book = book(CrossedBookPolicy::Ignore);
+        
+        let bids = order_book.cumulative_depth(Side::Bid, 5);
+        assert_eq!(bids, vec![(100.0, 1.0, 1.0), (99.0, 2.0, 3.0)]);
+        
+        let asks = order_book.cumulative_depth(Side::Ask, 1);
+        assert_eq!(asks, vec![(101.0, 1.0, 1.0)]);
+    }
+    
+    #[test]
+    fn test_group_levels() {
+        let mut order_book = OrderBook::new("BTCUSDT".to_string());
+        order_book
+            .apply_update(&update(
+                &order_book,
+                &[("1009.5", "1.0"), ("1001.0", "2.0"), ("999.0", "3.0"), ("985.0", "4.0")],
+                &[("1010.5", "1.0"), ("1019.0", "2.0"), ("1020.5", "3.0")],
+            ))
+            .unwrap();
+        
+        let bids = order_book.group_levels(Side::Bid, 10.0, 5);
+        assert_eq!(bids, vec![(1000.0, 3.0), (990.0, 3.0), (980.0, 4.0)]);
+        
+        let asks = order_book.group_levels(Side::Ask, 10.0, 1);
+        assert_eq!(asks, vec![(1020.0, 3.0)]);
+        
+        let raw = order_book.group_levels(Side::Ask, 0.0, 2);
+        assert_eq!(raw, vec![(1010.5, 1.0), (1019.0, 2.0)]);
+    }
+    
+    #[test]
+    fn test_depth_histogram() {
+        let mut order_book = OrderBook::new("BTCUSDT".to_string());
+        order_book
+            .apply_update(&update(
+                &order_book,
+                &[("99.5", "1.0"), ("99.0", "2.0"), ("98.0", "4.0"), ("90.0", "8.0")],
+                &[("100.5", "1.5"), ("101.0", "2.5"), ("110.0", "5.0")],
+            ))
+            .unwrap();
+        
+        // Mid is 100.0; 200 bps band is [98, 102] in four 1.0-wide buckets
+        let histogram = order_book.depth_histogram(4, 200.0);
+        assert_eq!(histogram.mid_price, 100.0);
+        assert_eq!(histogram.bid_volume, vec![4.0, 3.0, 0.0, 0.0]);
+        assert_eq!(histogram.ask_volume, vec![0.0, 0.0, 1.5, 2.5]);
+        
+        let in_range = |levels: &BTreeMap<PriceTicks, OrderBookLevel>| -> f64 {
+            levels
+                .values()
+                .filter(|level| (98.0..=102.0).contains(&level.price.0))
+                .map(|level| level.quantity)
+                .sum()
+        };
+        assert_eq!(histogram.bid_volume.iter().sum::<f64>(), in_range(&order_book.bids));
+        assert_eq!(histogram.ask_volume.iter().sum::<f64>(), in_range(&order_book.asks));
+        
+        let empty = OrderBook::new("BTCUSDT".to_string()).depth_histogram(3, 200.0);
+        assert_eq!(empty.bid_volume, vec![0.0; 3]);
+        assert_eq!(empty.ask_volume, vec![0.0; 3]);
+    }
+    
+    #[test]
+    fn test_pre_snapshot_events_are_skipped() {
+        let manager = OrderBookManager::new();
+        manager
+            .initialize_from_snapshot("BTCUSDT", OrderBookSnapshot {
+                last_update_id: 10,
+                bids: vec![["100.0".to_string(), "1.0".to_string()]],
+                asks: vec![["101.0".to_string(), "1.0".to_string()]],
+            })
+            .unwrap();
+        let levels = |manager: &OrderBookManager| {
+            manager
+                .with_order_book(|book| {
+                    let quantities = |side: Side| book.depth(side, 10).iter().map(|level| (level.price.0, level.quantity)).collect::<Vec<_>>();
+                    (book.last_update, quantities(Side::Bid), quantities(Side::Ask))
+                })
+                .unwrap()
+        };
+        let before = levels(&manager);
+        
+        // Buffered while the snapshot was fetched; already reflected in it
+        let buffered = update_with_ids(8, 10, &[("100.0", "7.0"), ("99.0", "2.0")], &[("101.0", "0")]);
+        assert_eq!(manager.apply_update(buffered).unwrap(), TopOfBookChange::default());
+        assert_eq!(levels(&manager), before);
+        assert_eq!(manager.recent_flow(), 0.0);
+        
+        // The straddling event is applied and advances the sequence
+        let next = update_with_ids(9, 12, &[("100.0", "3.0")], &[]);
+        manager.apply_update(next).unwrap();
+        assert_eq!(levels(&manager).0, 12);
+        
+        let older = update_with_ids(11, 11, &[("100.0", "9.0")], &[]);
+        manager.apply_update(older).unwrap();
+        assert_eq!(levels(&manager).0, 12);
+        assert_eq!(manager.with_order_book(|book| book.best_bid().unwrap().quantity), Some(3.0));
+    }
+    
+    #[test]
+    fn test_futures_previous_update_id() {
+        let snapshot = || OrderBookSnapshot {
+            last_update_id: 10,
+            bids: vec![["100.0".to_string(), "1.0".to_string()]],
+            asks: vec![["101.0".to_string(), "1.0".to_string()]],
+        };
+        let futures_update = |first: u64, last: u64, previous: u64| {
+            let mut update = update_with_ids(first, last, &[("100.0", "2.0")], &[]);
+            update.prev_final_update_id = Some(previous);
+            update
+        };
+        
+        let json = r#"{"e":"depthUpdate","E":1,"T":1,"s":"BTCUSDT","U":157,"u":160,"pu":149,"b":[],"a":[]}"#;
+        let parsed: OrderBookUpdate = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.prev_final_update_id, Some(149));
+        
+        // The first update straddles the snapshot, so its pu predates it
+        let mut order_book = OrderBook::from_snapshot("BTCUSDT", &snapshot()).unwrap();
+        order_book.apply_update(&futures_update(8, 12, 7)).unwrap();
+        order_book.apply_update(&futures_update(15, 18, 12)).unwrap();
+        
+        // Futures IDs skip (15 after 12) without that being a gap; pu is what counts
+        match order_book.apply_update(&futures_update(19, 20, 17)) {
+            Err(OrderBookError::PreviousUpdateMismatch { expected, previous }) => {
+                assert_eq!((expected, previous), (18, 17));
+            }
+            other => panic!("expected PreviousUpdateMismatch, got {:?}", other),
+        }
+        
+        // Spot updates without pu fall back to first_update_id continuity
+        let mut spot = OrderBook::from_snapshot("BTCUSDT", &snapshot()).unwrap();
+        let mut next = update_with_ids(11, 12, &[("100.0", "2.0")], &[]);
+        spot.apply_update(&next).unwrap();
+        next.first_update_id = 15;
+        next.final_update_id = 16;
+        assert!(matches!(spot.apply_update(&next), Err(OrderBookError::SequenceGap { .. })));
+    }
+    
+    #[test]
+    fn test_summary() {
+        let mut order_book = OrderBook::new("BTCUSDT".to_string());
+        order_book
+            .apply_update(&update(
+                &order_book,
+                &[("99.5", "1.0"), ("99.0", "2.0"), ("98.5", "1.0"), ("98.0", "1.0"), ("97.5", "1.0"), ("90.0", "8.0")],
+                &[("100.5", "1.5"), ("101.0", "2.5")],
+            ))
+            .unwrap();
+        order_book.last_update = 42;
+        
+        let summary = order_book.summary();
+        assert_eq!(summary.symbol, "BTCUSDT");
+        assert_eq!(summary.best_bid, Some(99.5));
+        assert_eq!(summary.best_ask, Some(100.5));
+        assert_eq!(summary.mid_price, Some(100.0));
+        assert_eq!(summary.spread, Some(1.0));
+        assert_eq!(summary.spread_bps, Some(100.0));
+        // The sixth bid level (90.0) is outside the top five
+        assert_eq!(summary.top_bid_volume, 6.0);
+        assert_eq!(summary.top_ask_volume, 4.0);
+        assert_eq!(summary.bid_levels, 6);
+        assert_eq!(summary.ask_levels, 2);
+        assert_eq!(summary.last_update, 42);
+        assert!(summary.to_string().contains("mid=100.0000 spread=1.0000 (100.00bps)"));
+        
+        let empty = OrderBook::new("BTCUSDT".to_string()).summary();
+        assert_eq!((empty.best_bid, empty.mid_price, empty.spread_bps), (None, None, None));
+        assert!(empty.to_string().contains("bid=- ask=-"));
+        assert!(format!("{:.1}", summary).contains("mid=100.0 spread=1.0 "));
+    }
+    
+    #[test]
+    fn test_notional_within_bps() {
+        let order_book = book(CrossedBookPolicy::Ignore);
+        // Mid is 100.5; 100 bps band is [99.495, 101.505]
+        let (bid_notional, ask_notional) = order_book.notional_within_bps(100.0);
+        assert_eq!(bid_notional, 100.0);
+        assert_eq!(ask_notional, 101.0);
+        
+        // Mid is 100.0; 100 bps band edges land exactly on both levels
+        let mut order_book = OrderBook::new("BTCUSDT".to_string());
+        order_book
+            .apply_update(&update(&order_book, &[("99.0", "1.0")], &[("101.0", "2.0")]))
+            .unwrap();
+        let (bid_notional, ask_notional) = order_book.notional_within_bps(100.0);
+        assert_eq!(bid_notional, 99.0);
+        assert_eq!(ask_notional, 202.0);
+        
+        let (bid_notional, ask_notional) = order_book.notional_within_bps(99.0);
+        assert_eq!(bid_notional, 0.0);
+        assert_eq!(ask_notional, 0.0);
+    }
+    
+    #[test]
+    fn test_equivalent_prices_collapse() {
+        let mut order_book = OrderBook::with_tick_size("BTCUSDT".to_string(), 0.01);
+        order_book
+            .apply_update(&update(&order_book, &[("50000.10", "1.0"), ("50000.1000", "2.0")], &[]))
+            .unwrap();
+        order_book
+            .apply_update(&update(&order_book, &[("50000.0999999", "3.0")], &[("50000.20", "1.0")]))
+            .unwrap();
+        
+        assert_eq!(order_book.bids.len(), 1);
+        let best_bid = order_book.best_bid().unwrap();
+        assert_eq!(best_bid.price.0, 50000.1);
+        assert_eq!(best_bid.quantity, 3.0);
+        assert_eq!(order_book.quantity_at_price(50000.1), Some(3.0));
+        
+        // Removing via an equivalent string clears the level
+        order_book.apply_update(&update(&order_book, &[("50000.100", "0")], &[])).unwrap();
+        assert!(order_book.bids.is_empty());
+        
+        // The default tick still collapses trailing-zero variants
+        let manager = OrderBookManager::new();
+        manager
+            .initialize_from_snapshot("BTCUSDT", OrderBookSnapshot {
+                last_update_id: 1,
+                bids: vec![["0.10000000".to_string(), "1".to_string()], ["0.1".to_string(), "2".to_string()]],
+                asks: vec![["0.3".to_string(), "1".to_string()]],
+            })
+            .unwrap();
+        assert_eq!(manager.with_order_book(|ob| ob.bids.len()), Some(1));
+    }
+    
+    #[test]
+    fn test_non_reciprocal_tick_prices() {
+        let mut order_book = OrderBook::with_tick_size("BTCUSDT".to_string(), 0.0003);
+        order_book
+            .apply_update(&update(&order_book, &[("1.0002", "1.0"), ("0.9999", "2.0")], &[("1.0005", "1.0")]))
+            .unwrap();
+        
+        assert_eq!(order_book.best_bid().unwrap().price.0, 1.0002);
+        assert_eq!(order_book.bids.values().next().unwrap().price.0, 0.9999);
+        assert_eq!(order_book.best_ask().unwrap().price.0, 1.0005);
+        
+        let order_book = OrderBook::with_tick_size("BTCUSDT".to_string(), 0.3);
+        assert_eq!(order_book.ticks_to_price(PriceTicks(7)), 2.1);
+        assert_eq!(order_book.ticks_to_price(order_book.price_ticks(100.2)), 100.2);
+        assert_eq!(order_book.decimal_price_ticks("100.2"), Some(PriceTicks(334)));
+    }
+    
+    #[test]
+    fn test_decimal_prices_near_tick_boundary() {
+        // 0.145 / 0.01 is 14.4999... as floats; the exact half rounds up
+        let mut order_book = OrderBook::with_tick_size("BTCUSDT".to_string(), 0.01);
+        assert_eq!(order_book.price_ticks(0.145), PriceTicks(14));
+        assert_eq!(order_book.decimal_price_ticks("0.145"), Some(PriceTicks(15)));
+        assert_eq!(order_book.decimal_price_ticks(".14499999"), Some(PriceTicks(14)));
+        assert_eq!(order_book.decimal_price_ticks("-0.145"), Some(PriceTicks(-15)));
+        order_book.apply_update(&update(&order_book, &[("0.145", "1.0")], &[])).unwrap();
+        assert_eq!(order_book.best_bid().unwrap().price.0, 0.15);
+        
+        // Adjacent satoshi-tick prices past 2^53 ticks share one f64 but keep their own levels
+        let mut order_book = OrderBook::new("BTCUSDT".to_string());
+        assert_eq!(order_book.price_ticks(90071992.54740993), order_book.price_ticks(90071992.54740994));
+        order_book
+            .apply_update(&update(&order_book, &[("90071992.54740993", "1.0"), ("90071992.54740994", "2.0")], &[]))
+            .unwrap();
+        assert_eq!(order_book.bids.len(), 2);
+        assert_eq!(order_book.bids.keys().next_back(), Some(&PriceTicks(9007199254740994)));
+        
+        // Other notations fall back to the float path
+        assert_eq!(order_book.decimal_price_ticks("1e-5"), None);
+        assert_eq!(order_book.decimal_price_ticks("."), None);
+        assert_eq!(order_book.decimal_price_ticks("99999999999999999999999999999999999999999"), None);
+    }
+    
+    #[test]
+    fn test_from_snapshot() {
+        let snapshot: OrderBookSnapshot = serde_json::from_str(
+            r#"{
+                "lastUpdateId": 42,
+                "bids": [["50000.00", "1.5"], ["49999.50", "0.00"], ["49999.00", "2.0"]],
+                "asks": [["50001.00", "0.8"], ["50002.50", "3.0"]]
+            }"#,
+        )
+        .unwrap();
+        
+        let order_book = OrderBook::from_snapshot("BTCUSDT", &snapshot).unwrap();
+        assert_eq!(order_book.symbol, "BTCUSDT");
+        assert_eq!(order_book.last_update, 42);
+        let best_bid = order_book.best_bid().unwrap();
+        let best_ask = order_book.best_ask().unwrap();
+        assert_eq!((best_bid.price.0, best_bid.quantity), (50000.0, 1.5));
+        assert_eq!((best_ask.price.0, best_ask.quantity), (50001.0, 0.8));
+        assert_eq!(order_book.bids.len(), 2); // Zero-quantity level skipped
+        
+        let bad = OrderBookSnapshot {
+            last_update_id: 1,
+            bids: vec![["abc".to_string(), "1.0".to_string()]],
+            asks: vec![],
+        };
+        assert!(matches!(
+            OrderBook::from_snapshot("BTCUSDT", &bad),
+            Err(OrderBookError::ParseError { .. })
+        ));
+    }
+    
+    #[test]
+    fn test_serialize_order_book() {
+        let mut order_book = OrderBook::with_tick_size("BTCUSDT".to_string(), 0.5);
+        order_book
+            .apply_update(&update(&order_book, &[("99.5", "2.0"), ("100.0", "1.0")], &[("101.0", "3.0")]))
+            .unwrap();
+        
+        let json = serde_json::to_value(&order_book).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "symbol": "BTCUSDT",
+                "last_update": order_book.last_update,
+                "tick_size": 0.5,
+                "bids": [{"price": 100.0, "quantity": 1.0}, {"price": 99.5, "quantity": 2.0}],
+                "asks": [{"price": 101.0, "quantity": 3.0}],
+            })
+        );
+    }
+    
+    #[test]
+    fn test_snapshot_json_round_trip() {
+        let mut order_book = OrderBook::with_tick_size("BTCUSDT".to_string(), 0.01);
+        order_book
+            .apply_update(&update(
+                &order_book,
+                &[("50000.10", "1.5"), ("49999.99", "2.0"), ("49990.00", "0.25")],
+                &[("50000.20", "1.0"), ("50001.00", "3.0")],
+            ))
+            .unwrap();
+        
+        let restored = OrderBook::from_snapshot_json(&order_book.to_snapshot_json()).unwrap();
+        assert_eq!(restored.symbol, "BTCUSDT");
+        assert_eq!(restored.tick_size(), 0.01);
+        assert_eq!(restored.last_update, order_book.last_update);
+        assert_eq!(restored.bids.len(), 3);
+        assert_eq!(restored.asks.len(), 2);
+        assert_eq!(restored.best_bid().unwrap().price.0, 50000.1);
+        assert_eq!(restored.best_bid().unwrap().quantity, 1.5);
+        assert_eq!(restored.best_ask().unwrap().price.0, 50000.2);
+        
+        // Through a file via the manager
+        let path = std::env::temp_dir().join(format!("orderflow-book-{}.json", std::process::id()));
+        let manager = OrderBookManager::new();
+        assert!(manager.save_snapshot(&path).is_err());
+        manager.replace_from_partial_depth("BTCUSDT", PartialDepth {
+            last_update_id: 7,
+            bids: vec![["100.5".to_string(), "1".to_string()], ["100.0".to_string(), "2".to_string()]],
+            asks: vec![["101.0".to_string(), "3".to_string()]],
+        }).unwrap();
+        manager.save_snapshot(&path).unwrap();
+        
+        let reloaded = OrderBookManager::new();
+        reloaded.load_snapshot(&path).unwrap();
+        
+        // Flow built on the replaced book does not carry over
+        manager.apply_update(update_with_ids(8, 8, &[("100.6", "4")], &[])).unwrap();
+        assert!(manager.recent_flow() != 0.0);
+        manager.load_snapshot(&path).unwrap();
+        assert_eq!(manager.recent_flow(), 0.0);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reloaded.get_mid_price(), Some(100.75));
+        assert_eq!(reloaded.with_order_book(|ob| (ob.bids.len(), ob.last_update)), Some((2, 7)));
+    }
+    
+    #[test]
+    fn test_clear_resets_readiness() {
+        let manager = OrderBookManager::new();
+        let snapshot = || OrderBookSnapshot {
+            last_update_id: 1,
+            bids: vec![["100.0".to_string(), "1".to_string()]],
+            asks: vec![["101.0".to_string(), "1".to_string()]],
+        };
+        
+        manager.initialize_from_snapshot("BTCUSDT", snapshot()).unwrap();
+        assert!(manager.is_ready());
+        manager.clear();
+        assert!(!manager.is_ready());
+        assert!(manager.get_order_book().is_none());
+        
+        // Per-symbol variant leaves other symbols' books alone
+        manager.initialize_from_snapshot("BTCUSDT", snapshot()).unwrap();
+        assert!(!manager.clear_symbol("ETHUSDT"));
+        assert!(manager.is_ready());
+        assert!(manager.clear_symbol("btcusdt"));
+        assert!(!manager.is_ready());
+    }
+    
+    #[test]
+    fn test_price_lookups() {
+        let order_book = book(CrossedBookPolicy::Ignore);
+        
+        assert_eq!(order_book.quantity_at_price(99.0), Some(2.0));
+        assert_eq!(order_book.quantity_at_price(102.0), Some(2.0));
+        assert_eq!(order_book.quantity_at_price(0.1 + 98.9), Some(2.0)); // Off by float rounding
+        assert_eq!(order_book.quantity_at_price(100.5), None);
+        assert_eq!(order_book.quantity_at_price(f64::NAN), None);
+        
+        assert_eq!(order_book.nearest_level(Side::Bid, 99.2).unwrap().price.0, 99.0);
+        assert_eq!(order_book.nearest_level(Side::Bid, 150.0).unwrap().price.0, 100.0);
+        assert_eq!(order_book.nearest_level(Side::Ask, 101.9).unwrap().price.0, 102.0);
+        assert_eq!(order_book.nearest_level(Side::Ask, 101.5).unwrap().price.0, 101.0); // Tie
+        assert_eq!(order_book.nearest_level(Side::Bid, 99.5).unwrap().price.0, 100.0); // Tie
+        assert!(OrderBook::new("BTCUSDT".to_string()).nearest_level(Side::Bid, 100.0).is_none());
+    }
+    
+    #[test]
+    fn test_full_book_aggregates() {
+        let order_book = book(CrossedBookPolicy::Ignore);
+        assert_eq!(order_book.total_bid_volume(), 3.0);
+        assert_eq!(order_book.total_ask_volume(), 3.0);
+        
+        // (100 * 1 + 99 * 2) / 3 and (101 * 1 + 102 * 2) / 3
+        assert!((order_book.full_book_vwap(Side::Bid).unwrap() - 298.0 / 3.0).abs() < 1e-9);
+        assert!((order_book.full_book_vwap(Side::Ask).unwrap() - 305.0 / 3.0).abs() < 1e-9);
+        
+        let empty = OrderBook::new("BTCUSDT".to_string());
+        assert_eq!(empty.total_bid_volume(), 0.0);
+        assert_eq!(empty.full_book_vwap(Side::Ask), None);
+    }
+    
+    #[test]
+    fn test_update_for_other_symbol_rejected() {
+        let mut order_book = OrderBook::new("ETHUSDT".to_string());
+        let err = order_book
+            .apply_update(&update(&order_book, &[("100.0", "1.0")], &[]))
+            .unwrap_err();
+        assert!(err.to_string().contains("BTCUSDT"));
+        assert!(order_book.bids.is_empty());
+        assert_eq!(order_book.last_update, 0);
+    }
+    
+    #[test]
+    fn test_verify_against_snapshot() {
+        let snapshot = |last_update_id: u64, bids: &[(&str, &str)], asks: &[(&str, &str)]| OrderBookSnapshot {
+            last_update_id,
+            bids: bids.iter().map(|(p, q)| [p.to_string(), q.to_string()]).collect(),
+            asks: asks.iter().map(|(p, q)| [p.to_string(), q.to_string()]).collect(),
+        };
+        let fresh = snapshot(10, &[("100.0", "1.0"), ("99.0", "2.0")], &[("101.0", "1.0"), ("102.0", "2.0")]);
+        
+        let manager = OrderBookManager::new();
+        assert!(manager.verify_against_snapshot(&fresh).is_err());
+        
+        manager
+            .initialize_from_snapshot("BTCUSDT", snapshot(
+                10,
+                &[("100.0", "1.0"), ("99.0", "2.0")],
+                &[("101.0", "1.0"), ("102.0", "2.0")],
+            ))
+            .unwrap();
+        let in_sync = manager.verify_against_snapshot(&fresh).unwrap();
+        assert_eq!(in_sync.levels_compared, 4);
+        assert!(!in_sync.exceeds(0.0, 0.0));
+        
+        // Drift: a missed removal of the 99.0 bid and a resized 102.0 ask
+        let drifted = update_with_ids(11, 11, &[], &[("102.0", "3.0")]);
+        manager.apply_update(drifted).unwrap();
+        let fresh = snapshot(11, &[("100.0", "1.0")], &[("101.0", "1.0"), ("102.0", "2.0")]);
+        
+        let divergence = manager.verify_against_snapshot(&fresh).unwrap();
+        assert_eq!(divergence.levels_compared, 3);
+        assert_eq!(divergence.missing_levels, 0);
+        assert_eq!(divergence.max_price_divergence, 0.0);
+        assert!((divergence.max_quantity_divergence - 0.5).abs() < 1e-9);
+        assert_eq!((divergence.local_update_id, divergence.snapshot_update_id), (11, 11));
+        assert!(divergence.exceeds(0.0, 0.25));
+        assert!(!divergence.exceeds(0.0, 0.5));
+        
+        // A level the local book lost shifts every deeper rank
+        let fresh = snapshot(11, &[("100.5", "1.0"), ("100.0", "1.0")], &[("101.0", "1.0"), ("102.0", "3.0")]);
+        let divergence = manager.verify_against_snapshot(&fresh).unwrap();
+        assert_eq!(divergence.max_price_divergence, 1.0); // 99.0 against 100.0 at rank two
+        assert!(divergence.exceeds(0.5, 1.0));
+        assert!(!divergence.exceeds(1.0, 1.0));
+        
+        // A snapshot a few updates behind the book differs by those updates, not by drift
+        let lagging = snapshot(8, &[("100.0", "1.0"), ("99.0", "2.0")], &[("101.0", "1.0"), ("102.0", "2.0")]);
+        let divergence = manager.verify_against_snapshot(&lagging).unwrap();
+        assert!(divergence.max_quantity_divergence > 0.0);
+        assert!(!divergence.comparable());
+        assert!(!divergence.exceeds(0.0, 0.0));
+    }
+    
+    #[test]
+    fn test_order_book_diff() {
+        let local = book(CrossedBookPolicy::Ignore);
+        assert!(local.diff(&local.clone()).is_empty());
+        
+        // Snapshot lost the 99.0 bid, resized the 102.0 ask and added a tighter ask
+        let mut snapshot = book(CrossedBookPolicy::Ignore);
+        snapshot
+            .apply_update(&update(&snapshot, &[("99.0", "0")], &[("100.5", "3.0"), ("102.0", "5.0")]))
+            .unwrap();
+        
+        let diff = local.diff(&snapshot);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.bids.removed.len(), 1);
+        assert_eq!(diff.bids.removed[0].price.0, 99.0);
+        assert!(diff.bids.added.is_empty() && diff.bids.changed.is_empty());
+        
+        assert_eq!(diff.asks.added.len(), 1);
+        assert_eq!(diff.asks.added[0].price.0, 100.5);
+        assert_eq!(
+            diff.asks.changed,
+            vec![LevelChange { price: 102.0, old_quantity: 2.0, new_quantity: 5.0 }]
+        );
+        assert!((diff.max_price_deviation - 0.5).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_order_book_error_variants() {
+        let manager = OrderBookManager::new();
+        assert!(matches!(
+            manager.apply_update(manager_update(&manager, &[("100.0", "1.0")], &[])),
+            Err(OrderBookError::NotInitialized)
+        ));
+        
+        let bad_snapshot = OrderBookSnapshot {
+            last_update_id: 1,
+            bids: vec![["100.0".to_string(), "lots".to_string()]],
+            asks: vec![],
+        };
+        match manager.initialize_from_snapshot("BTCUSDT", bad_snapshot) {
+            Err(OrderBookError::ParseError { value, .. }) => assert_eq!(value, "lots"),
+            other => panic!("expected ParseError, got {:?}", other),
+        }
+        
+        manager
+            .initialize_from_snapshot("BTCUSDT", OrderBookSnapshot {
+                last_update_id: 10,
+                bids: vec![["100.0".to_string(), "1.0".to_string()]],
+                asks: vec![["101.0".to_string(), "1.0".to_string()]],
+            })
+            .unwrap();
+        
+        assert!(matches!(
+            manager.apply_update(manager_update(&manager, &[("inf", "1.0")], &[])),
+            Err(OrderBookError::InvalidPrice(_))
+        ));
+        
+        let mut other_symbol = manager_update(&manager, &[("100.0", "2.0")], &[]);
+        other_symbol.symbol = "ETHUSDT".to_string();
+        assert!(matches!(
+            manager.apply_update(other_symbol),
+            Err(OrderBookError::SymbolMismatch { .. })
+        ));
+        
+        // Updates 11-12 were missed
+        let gapped = update_with_ids(13, 14, &[("100.0", "2.0")], &[]);
+        match manager.apply_update(gapped) {
+            Err(OrderBookError::SequenceGap { expected, first_update_id }) => {
+                assert_eq!((expected, first_update_id), (11, 13));
+            }
+            other => panic!("expected SequenceGap, got {:?}", other),
+        }
+        
+        assert!(OrderBookError::SequenceGap { expected: 11, first_update_id: 13 }.needs_resync());
+        assert!(!OrderBookError::NotInitialized.needs_resync());
+        
+        // Errors still convert into anyhow for callers that don't care
+        let error: anyhow::Error = OrderBookError::NotInitialized.into();
+        assert_eq!(error.to_string(), "Order book not initialized");
+    }
+    
+    #[test]
+    fn test_poisoned_lock_recovers() {
+        let manager = std::sync::Arc::new(OrderBookManager::new());
+        manager
+            .initialize_from_snapshot("BTCUSDT", OrderBookSnapshot {
+                last_update_id: 1,
+                bids: vec![["100.0".to_string(), "1.0".to_string()]],
+                asks: vec![["101.0".to_string(), "1.0".to_string()]],
+            })
+            .unwrap();
+        
+        // Panic while holding the write lock
+        let poisoner = manager.clone();
+        let panicked = std::thread::spawn(move || {
+            let _guard = poisoner.order_book.write().unwrap();
+            panic!("poison the order book lock");
+        })
+        .join();
+        assert!(panicked.is_err());
+        assert!(manager.order_book.is_poisoned());
+        
+        assert_eq!(manager.get_mid_price(), Some(100.5));
+        assert!(!manager.order_book.is_poisoned());
+        assert!(manager.is_ready());
+        manager.apply_update(manager_update(&manager, &[("100.5", "1.0")], &[])).unwrap();
+        assert_eq!(manager.get_spread(), Some(0.5));
+    }
+    
+    #[test]
+    fn test_owned_top_of_book() {
+        let manager = OrderBookManager::new();
+        assert_eq!(manager.top_of_book(), None);
+        
+        manager
+            .initialize_from_snapshot("BTCUSDT", OrderBookSnapshot {
+                last_update_id: 1,
+                bids: vec![["100.0".to_string(), "2.0".to_string()], ["99.0".to_string(), "5.0".to_string()]],
+                asks: vec![["101.0".to_string(), "1.5".to_string()]],
+            })
+            .unwrap();
+        
+        let top = manager.top_of_book().unwrap();
+        let expected = manager
+            .with_order_book(|ob| {
+                let (bid, ask) = (ob.best_bid().unwrap(), ob.best_ask().unwrap());
+                TopOfBook {
+                    bid_price: bid.price.0,
+                    bid_qty: bid.quantity,
+                    ask_price: ask.price.0,
+                    ask_qty: ask.quantity,
+                }
+            })
+            .unwrap();
+        assert_eq!(top, expected);
+        assert_eq!((top.bid_price, top.bid_qty, top.ask_price, top.ask_qty), (100.0, 2.0, 101.0, 1.5));
+        
+        // Owned data outlives the lock and can move to another thread
+        std::thread::spawn(move || assert_eq!(top.ask_price, 101.0)).join().unwrap();
+    }
+    
+    #[test]
+    fn test_spread_alert_fires_once_per_crossing() {
+        let manager = OrderBookManager::new().with_spread_alert(SpreadThreshold::Absolute(0.5));
+        let mut alerts = manager.subscribe_spread_alerts();
+        manager
+            .initialize_from_snapshot("BTCUSDT", OrderBookSnapshot {
+                last_update_id: 1,
+                bids: vec![["100.0".to_string(), "1.0".to_string()]],
+                asks: vec![["101.0".to_string(), "1.0".to_string()]],
+            })
+            .unwrap();
+        
+        let mut next_id = 2;
+        let mut apply = |bids: &[(&str, &str)], asks: &[(&str, &str)]| {
+            let update = update_with_ids(next_id, next_id, bids, asks);
+            next_id += 1;
+            manager.apply_update(update).unwrap();
+        };
+        
+        apply(&[("100.5", "1.0")], &[]); // Spread 0.5, not below
+        assert!(alerts.try_recv().is_err());
+        
+        apply(&[("100.75", "2.0")], &[]); // Spread 0.25
+        let alert = alerts.try_recv().unwrap();
+        assert_eq!(alert.spread, 0.25);
+        assert_eq!(alert.top_of_book.bid_price, 100.75);
+        assert_eq!(alert.top_of_book.bid_qty, 2.0);
+        
+        apply(&[("100.8", "1.0")], &[]); // Still below, no repeat
+        assert!(alerts.try_recv().is_err());
+        
+        apply(&[("100.8", "0"), ("100.75", "0")], &[]); // Widens back to 0.5, re-arms
+        apply(&[("100.9", "1.0")], &[]); // Second crossing
+        assert_eq!(alerts.try_recv().unwrap().top_of_book.bid_price, 100.9);
+        assert!(alerts.try_recv().is_err());
+    }
+    
+    #[test]
+    fn test_spread_alert_rearms_after_clear() {
+        let manager = OrderBookManager::new().with_spread_alert(SpreadThreshold::Absolute(0.5));
+        let mut alerts = manager.subscribe_spread_alerts();
+        let snapshot = |last_update_id| OrderBookSnapshot {
+            last_update_id,
+            bids: vec![["100.0".to_string(), "1.0".to_string()]],
+            asks: vec![["101.0".to_string(), "1.0".to_string()]],
+        };
+        
+        manager.initialize_from_snapshot("BTCUSDT", snapshot(1)).unwrap();
+        manager.apply_update(update_with_ids(2, 2, &[("100.75", "1.0")], &[])).unwrap();
+        assert!(alerts.try_recv().is_ok());
+        
+        // A reconnect clears the book while the spread is still tight
+        manager.clear();
+        manager.initialize_from_snapshot("BTCUSDT", snapshot(10)).unwrap();
+        manager.apply_update(update_with_ids(11, 11, &[("100.75", "1.0")], &[])).unwrap();
+        assert_eq!(alerts.try_recv().unwrap().spread, 0.25);
+        
+        assert!(manager.clear_symbol("BTCUSDT"));
+        manager.initialize_from_snapshot("BTCUSDT", snapshot(20)).unwrap();
+        manager.apply_update(update_with_ids(21, 21, &[("100.75", "1.0")], &[])).unwrap();
+        assert!(alerts.try_recv().is_ok());
+    }
+    
+    #[test]
+    fn test_top_of_book_change() {
+        let manager = OrderBookManager::new();
+        let bids: Vec<[String; 2]> = (0..10)
+            .map(|i| [format!("{}.0", 100 - i), "1.0".to_string()])
+            .collect();
+        let asks: Vec<[String; 2]> = (0..10)
+            .map(|i| [format!("{}.0", 101 + i), "1.0".to_string()])
+            .collect();
+        manager
+            .initialize_from_snapshot("BTCUSDT", OrderBookSnapshot { last_update_id: 1, bids, asks })
+            .unwrap();
+        
+        // Eighth bid level and a new far ask: nothing near the touch moved
+        let deep = manager.apply_update(manager_update(&manager, &[("93.0", "5.0")], &[("150.0", "1.0")])).unwrap();
+        assert!(!deep.changed());
+        
+        // Fourth ask level resized
+        let near = manager.apply_update(manager_update(&manager, &[], &[("104.0", "2.0")])).unwrap();
+        assert_eq!(near, TopOfBookChange { best_bid: false, best_ask: false, top_levels: true });
+        
+        // Best bid consumed
+        let touch = manager.apply_update(manager_update(&manager, &[("100.0", "0")], &[])).unwrap();
+        assert!(touch.best_bid && !touch.best_ask && touch.top_levels);
+    }
+    
+    #[test]
+    fn test_recent_flow_from_depth_deltas() {
+        let manager = OrderBookManager::new();
+        manager
+            .initialize_from_snapshot("BTCUSDT", OrderBookSnapshot {
+                last_update_id: 1,
+                bids: (0..10).map(|i| [format!("{}.0", 100 - i), "1.0".to_string()]).collect(),
+                asks: (0..10).map(|i| [format!("{}.0", 101 + i), "1.0".to_string()]).collect(),
+            })
+            .unwrap();
+        assert_eq!(manager.recent_flow(), 0.0);
+        
+        // Bids stacked at the touch and the best ask lifted: buying
+        manager.apply_update(manager_update(&manager, &[("100.0", "3.0")], &[])).unwrap();
+        manager.apply_update(manager_update(&manager, &[], &[("101.0", "0")])).unwrap();
+        assert!((manager.recent_flow() - 3.0).abs() < 1e-9);
+        
+        // Deep levels do not count
+        manager.apply_update(manager_update(&manager, &[("92.0", "50.0")], &[("110.0", "50.0")])).unwrap();
+        assert!((manager.recent_flow() - 3.0).abs() < 1e-9);
+        
+        // Heavy offers and the best bid hit: net selling
+        manager.apply_update(manager_update(&manager, &[("100.0", "0")], &[("102.0", "4.0")])).unwrap();
+        assert!((manager.recent_flow() - -3.0).abs() < 1e-9);
+        
+        manager.clear();
+        assert_eq!(manager.recent_flow(), 0.0);
+        
+        // Dropping the symbol's book drops its flow too
+        manager
+            .initialize_from_snapshot("BTCUSDT", OrderBookSnapshot {
+                last_update_id: 1,
+                bids: vec![["100.0".to_string(), "1.0".to_string()]],
+                asks: vec![["101.0".to_string(), "1.0".to_string()]],
+            })
+            .unwrap();
+        manager.apply_update(manager_update(&manager, &[("100.0", "3.0")], &[])).unwrap();
+        assert!(manager.recent_flow() > 0.0);
+        assert!(manager.clear_symbol("BTCUSDT"));
+        assert_eq!(manager.recent_flow(), 0.0);
+    }
+    
+    #[test]
+    fn test_prune_stale_levels() {
+        let mut order_book = book(CrossedBookPolicy::Ignore);
+        let stale_bid = order_book.price_ticks(100.0);
+        for (price, level) in order_book.bids.iter_mut().chain(order_book.asks.iter_mut()) {
+            level.timestamp = if *price == stale_bid { 1_000 } else { 8_000 };
+        }
+        
+        // Nothing is older than the window yet
+        assert_eq!(order_book.prune_stale_levels(10_000, 10_000), 0);
+        
+        assert_eq!(order_book.prune_stale_levels(3_000, 10_000), 1);
+        assert_eq!(order_book.bids.len(), 1);
+        assert_eq!(order_book.asks.len(), 2);
+        assert_eq!(order_book.best_bid().unwrap().price.0, 99.0);
+    }
+    
+    #[test]
+    fn test_book_validity_reasons() {
+        let mut order_book = OrderBook::new("BTCUSDT".to_string());
+        assert_eq!(order_book.validity(), BookValidity::NoBids);
+        
+        order_book.apply_update(&update(&order_book, &[("100.0", "1.0")], &[])).unwrap();
+        assert_eq!(order_book.validity(), BookValidity::NoAsks);
+        
+        order_book.apply_update(&update(&order_book, &[], &[("100.0", "1.0")])).unwrap();
+        assert_eq!(order_book.validity(), BookValidity::ZeroSpread);
+        assert!(!order_book.is_valid());
+        
+        order_book.apply_update(&update(&order_book, &[("100.5", "1.0")], &[])).unwrap();
+        assert_eq!(order_book.validity(), BookValidity::Crossed);
+        
+        order_book.apply_update(&update(&order_book, &[("100.5", "0"), ("100.0", "0"), ("99.0", "1.0")], &[])).unwrap();
+        assert_eq!(order_book.validity(), BookValidity::Valid);
+        assert!(order_book.is_valid());
+        
+        // Asks alone still report missing bids
+        let mut asks_only = OrderBook::new("BTCUSDT".to_string());
+        asks_only.apply_update(&update(&asks_only, &[], &[("101.0", "1.0")])).unwrap();
+        assert_eq!(asks_only.validity(), BookValidity::NoBids);
+    }
+    
+    #[test]
+    fn test_crossed_policy_default_matches_config() {
+        let config = crate::config::Config::new("BTCUSDT".to_string(), "mid-price".to_string());
+        assert_eq!(config.order_book.crossed_book_policy, CrossedBookPolicy::Ignore);
+        assert_eq!(OrderBook::new("BTCUSDT".to_string()).crossed_policy, CrossedBookPolicy::Ignore);
+    }
+    
+    #[test]
+    fn test_crossed_book_ignored_when_configured() {
+        let mut order_book = book(CrossedBookPolicy::Ignore);
+        assert!(!order_book.is_crossed());
+        
+        order_book.apply_update(&update(&order_book, &[("101.5", "1.0")], &[])).unwrap();
+        assert!(order_book.is_crossed());
+        assert!(!order_book.is_valid());
+    }
+    
+    #[test]
+    fn test_crossed_book_removes_stale_asks() {
+        let mut order_book = book(CrossedBookPolicy::RemoveCrossed);
+        
+        // New bid at 101.5 crosses the resting ask at 101.0
+        order_book.apply_update(&update(&order_book, &[("101.5", "1.0")], &[])).unwrap();
+        
+        assert!(!order_book.is_crossed());
+        assert_eq!(order_book.best_bid().unwrap().price.0, 101.5);
+        assert_eq!(order_book.best_ask().unwrap().price.0, 102.0);
+        assert_eq!(order_book.asks.len(), 1);
+    }
+    
+    #[test]
+    fn test_crossed_book_removes_stale_bids() {
+        let mut order_book = book(CrossedBookPolicy::RemoveCrossed);
+        
+        // New ask at 99.0 crosses both resting bids (at or above it)
+        order_book.apply_update(&update(&order_book, &[], &[("99.0", "1.0")])).unwrap();
+        
+        assert!(!order_book.is_crossed());
+        assert!(order_book.bids.is_empty());
+        assert_eq!(order_book.best_ask().unwrap().price.0, 99.0);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::fair_price::FairPriceResult;
+use anyhow::Result;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::{debug, error, warn};
+
+/// CSV column header
+pub const CSV_HEADER: &str = "timestamp,symbol,fair_price,mid_price,spread,confidence,imbalance,signal";
+
+/// Flush at least this often while rows are arriving
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Buffered CSV writer for fair price results
+pub struct CsvSink<W: Write> {
+    writer: BufWriter<W>,
+    last_flush: Instant,
+    rows: u64,
+}
+
+impl CsvSink<File> {
+    /// Create (or truncate) a CSV file and write the header
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(File::create(path)?)
+    }
+}
+
+impl<W: Write> CsvSink<W> {
+    /// Wrap a writer and write the header
+    pub fn new(writer: W) -> Result<Self> {
+        let mut writer = BufWriter::new(writer);
+        writeln!(writer, "{}", CSV_HEADER)?;
+        
+        Ok(Self {
+            writer,
+            last_flush: Instant::now(),
+            rows: 0,
+        })
+    }
+    
+    /// Append one result row, flushing if the flush interval has passed
+    pub fn write_result(&mut self, symbol: &str, result: &FairPriceResult) -> Result<()> {
+        writeln!(
+            self.writer,
+            "{},{},{},{},{},{},{},{:?}",
+            result.timestamp,
+            symbol,
+            result.fair_price,
+            result.mid_price,
+            result.spread,
+            result.confidence,
+            result.metadata.order_flow_imbalance,
+            result.market_signal(),
+        )?;
+        self.rows += 1;
+        
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Flush buffered rows to the underlying writer
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        self.last_flush = Instant::now();
+        Ok(())
+    }
+    
+    /// Number of rows written (excluding the header)
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+    
+    /// Flush and return the underlying writer
+    pub fn into_inner(self) -> Result<W> {
+        self.writer.into_inner().map_err(|e| e.into_error().into())
+    }
+}
+
+/// Write every result from a result stream until the stream closes
+pub async fn write_results<W: Write>(
+    sink: Arc<Mutex<CsvSink<W>>>,
+    symbol: String,
+    mut results: broadcast::Receiver<FairPriceResult>,
+) {
+    loop {
+        match results.recv().await {
+            Ok(result) => {
+                if let Err(e) = sink.lock().unwrap().write_result(&symbol, &result) {
+                    error!("Failed to write CSV row for {}: {}", symbol, e);
+                    return;
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!("CSV sink for {} fell behind, skipped {} results", symbol, skipped);
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+    
+    let mut sink = sink.lock().unwrap();
+    if let Err(e) = sink.flush() {
+        error!("Failed to flush CSV sink: {}", e);
+    }
+    debug!("CSV sink for {} closed after {} rows", symbol, sink.rows());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_price::test_result;
+    
+    fn result(timestamp: u64, fair_price: f64) -> FairPriceResult {
+        FairPriceResult {
+            timestamp,
+            ..test_result(fair_price)
+        }
+    }
+    
+    #[test]
+    fn test_csv_sink_rows() {
+        let mut sink = CsvSink::new(Vec::new()).unwrap();
+        for i in 0..3 {
+            sink.write_result("BTCUSDT", &result(1_000 + i, 50000.5)).unwrap();
+        }
+        assert_eq!(sink.rows(), 3);
+        
+        let output = String::from_utf8(sink.into_inner().unwrap()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "1000,BTCUSDT,50000.5,50000.5,1,0.9,0,Balanced");
+    }
+}
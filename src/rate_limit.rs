@@ -0,0 +1,118 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Binance REST request weight limit per minute
+pub const DEFAULT_WEIGHT_PER_MINUTE: u32 = 1200;
+
+/// Token-bucket limiter for Binance REST request weight
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `capacity` weight per `window`
+    pub fn new(capacity: u32, window: Duration) -> Self {
+        let capacity = capacity.max(1) as f64;
+        Self {
+            capacity,
+            refill_per_sec: capacity / window.as_secs_f64().max(f64::EPSILON),
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until `weight` is available, then consume it
+    pub async fn acquire(&self, weight: u32) {
+        // Requests heavier than the bucket can only ever wait for a full bucket
+        let weight = (weight as f64).min(self.capacity);
+
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap();
+                self.refill(&mut bucket);
+
+                if bucket.tokens >= weight {
+                    bucket.tokens -= weight;
+                    return;
+                }
+
+                Duration::from_secs_f64((weight - bucket.tokens) / self.refill_per_sec)
+            };
+
+            debug!("Rate limit reached, waiting {:?} for {} weight", wait, weight);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Correct the bucket from the server-reported used weight
+    /// (`X-MBX-USED-WEIGHT-1M` header)
+    pub fn sync_used_weight(&self, used_weight: u32) {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+
+        let remaining = (self.capacity - used_weight as f64).max(0.0);
+        if remaining < bucket.tokens {
+            bucket.tokens = remaining;
+        }
+    }
+
+    /// Currently available weight
+    pub fn available(&self) -> f64 {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+        bucket.tokens
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.last_refill = now;
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_WEIGHT_PER_MINUTE, Duration::from_secs(60))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_limiter_delays_nth_call() {
+        // 2 weight per 200ms: refills one unit every 100ms
+        let limiter = RateLimiter::new(2, Duration::from_millis(200));
+
+        let start = Instant::now();
+        limiter.acquire(1).await;
+        limiter.acquire(1).await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        limiter.acquire(1).await;
+        assert!(start.elapsed() >= Duration::from_millis(80));
+    }
+
+    #[test]
+    fn test_sync_used_weight() {
+        let limiter = RateLimiter::new(1200, Duration::from_secs(60));
+        limiter.sync_used_weight(1000);
+        assert!(limiter.available() <= 201.0);
+
+        // A lower server count never grants extra tokens
+        limiter.sync_used_weight(0);
+        assert!(limiter.available() <= 201.0);
+    }
+}
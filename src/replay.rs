@@ -0,0 +1,180 @@
+use crate::fair_price::{FairPriceResult, MarketSignal};
+use crate::output::CSV_HEADER;
+use anyhow::{Result, anyhow};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// One row of `CsvSink` output
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultRecord {
+    pub timestamp: u64,
+    pub symbol: String,
+    pub fair_price: f64,
+    pub mid_price: f64,
+    pub spread: f64,
+    pub confidence: f64,
+    pub imbalance: f64,
+    pub signal: MarketSignal,
+}
+
+impl ResultRecord {
+    /// The row `CsvSink::write_result` would write for `result`
+    pub fn from_result(symbol: &str, result: &FairPriceResult) -> Self {
+        Self {
+            timestamp: result.timestamp,
+            symbol: symbol.to_string(),
+            fair_price: result.fair_price,
+            mid_price: result.mid_price,
+            spread: result.spread,
+            confidence: result.confidence,
+            imbalance: result.metadata.order_flow_imbalance,
+            signal: result.market_signal(),
+        }
+    }
+    
+    /// Parse one data row
+    fn parse(line: &str) -> Result<Self> {
+        let fields: Vec<&str> = line.split(',').collect();
+        let [timestamp, symbol, fair_price, mid_price, spread, confidence, imbalance, signal] = fields[..] else {
+            return Err(anyhow!("expected 8 fields, got {}", fields.len()));
+        };
+        let number = |name: &str, value: &str| {
+            value.parse::<f64>().map_err(|_| anyhow!("invalid {} '{}'", name, value))
+        };
+        
+        Ok(Self {
+            timestamp: timestamp.parse().map_err(|_| anyhow!("invalid timestamp '{}'", timestamp))?,
+            symbol: symbol.to_string(),
+            fair_price: number("fair_price", fair_price)?,
+            mid_price: number("mid_price", mid_price)?,
+            spread: number("spread", spread)?,
+            confidence: number("confidence", confidence)?,
+            imbalance: number("imbalance", imbalance)?,
+            signal: match signal {
+                "BuyPressure" => MarketSignal::BuyPressure,
+                "SellPressure" => MarketSignal::SellPressure,
+                "Balanced" => MarketSignal::Balanced,
+                "Neutral" => MarketSignal::Neutral,
+                other => return Err(anyhow!("invalid signal '{}'", other)),
+            },
+        })
+    }
+}
+
+/// Read a CSV file written by `CsvSink`
+pub fn read_csv(path: impl AsRef<Path>) -> Result<Vec<ResultRecord>> {
+    read_records(BufReader::new(File::open(path)?))
+}
+
+/// Read `CsvSink` output back into records, in file order
+///
+/// Rows of every symbol are returned; filter by `symbol` before analysing a
+/// single series.
+pub fn read_records<R: BufRead>(reader: R) -> Result<Vec<ResultRecord>> {
+    let mut lines = reader.lines();
+    match lines.next().transpose()? {
+        Some(header) if header.trim_end() == CSV_HEADER => {}
+        Some(header) => return Err(anyhow!("Unexpected CSV header '{}'", header)),
+        None => return Err(anyhow!("CSV input is empty")),
+    }
+    
+    let mut records = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Line 1 is the header
+        let record = ResultRecord::parse(line.trim_end())
+            .map_err(|e| anyhow!("CSV line {}: {}", index + 2, e))?;
+        records.push(record);
+    }
+    
+    Ok(records)
+}
+
+/// Average realized spread of a recorded result series
+///
+/// Compares each fair price with the mid price `lookahead` results later and
+/// averages the signed difference (future mid minus fair price). Values near
+/// zero mean the method anticipated where the market went; a persistent sign
+/// means it lagged a drift. `None` when the series is too short.
+pub fn realized_spread(records: &[ResultRecord], lookahead: usize) -> Option<f64> {
+    let pairs = records.len().checked_sub(lookahead).filter(|&pairs| pairs > 0)?;
+    let total: f64 = records
+        .iter()
+        .zip(&records[lookahead..])
+        .map(|(now, later)| later.mid_price - now.fair_price)
+        .sum();
+    Some(total / pairs as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_price::test_result;
+    use crate::output::CsvSink;
+    
+    /// Mid drifting up by 2 per tick, priced at the mid
+    fn drifting(ticks: u64) -> Vec<FairPriceResult> {
+        (0..ticks)
+            .map(|i| FairPriceResult {
+                timestamp: 1_000 + i,
+                mid_price: 100.0 + 2.0 * i as f64,
+                ..test_result(100.0 + 2.0 * i as f64)
+            })
+            .collect()
+    }
+    
+    #[test]
+    fn test_csv_round_trip() {
+        let results = drifting(10);
+        let mut sink = CsvSink::new(Vec::new()).unwrap();
+        for result in &results {
+            sink.write_result("BTCUSDT", result).unwrap();
+        }
+        let output = sink.into_inner().unwrap();
+        
+        let records = read_records(output.as_slice()).unwrap();
+        let expected: Vec<ResultRecord> = results
+            .iter()
+            .map(|result| ResultRecord::from_result("BTCUSDT", result))
+            .collect();
+        assert_eq!(records, expected);
+        assert_eq!(records[0].signal, MarketSignal::Balanced);
+        
+        // The fair price lags the drift by the lookahead
+        assert_eq!(realized_spread(&records, 0), Some(0.0));
+        assert_eq!(realized_spread(&records, 3), Some(6.0));
+        assert_eq!(realized_spread(&records, 10), None);
+    }
+    
+    #[test]
+    fn test_realized_spread_leading_price() {
+        // A fair price one tick ahead of the mid anticipates the drift
+        let leading: Vec<ResultRecord> = drifting(10)
+            .iter()
+            .map(|result| {
+                let mut record = ResultRecord::from_result("BTCUSDT", result);
+                record.fair_price = record.mid_price + 2.0;
+                record
+            })
+            .collect();
+        assert_eq!(realized_spread(&leading, 1), Some(0.0));
+        assert_eq!(realized_spread(&[], 0), None);
+    }
+    
+    #[test]
+    fn test_malformed_csv() {
+        assert!(read_records("".as_bytes()).is_err());
+        assert!(read_records("time,price\n".as_bytes()).is_err());
+        
+        let bad_row = format!("{}\n1000,BTCUSDT,1,1,0,0.9,0,Balanced\n1001,BTCUSDT,x,1,0,0.9,0,Balanced\n", CSV_HEADER);
+        let error = read_records(bad_row.as_bytes()).unwrap_err().to_string();
+        assert!(error.contains("line 3") && error.contains("fair_price"), "{}", error);
+        
+        let bad_signal = format!("{}\n1000,BTCUSDT,1,1,0,0.9,0,Sideways\n", CSV_HEADER);
+        assert!(read_records(bad_signal.as_bytes()).is_err());
+    }
+}
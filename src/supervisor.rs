@@ -0,0 +1,224 @@
+use crate::binance::BinanceClient;
+use crate::exchange::ExchangeConnector;
+use crate::websocket::{Command, WebSocketManager};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Notify};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Builds the manager for a newly subscribed symbol
+type ManagerFactory<C> = Box<dyn Fn(&str) -> WebSocketManager<C> + Send + Sync>;
+
+/// One running per-symbol pipeline
+struct Pipeline<C: ExchangeConnector> {
+    manager: Arc<WebSocketManager<C>>,
+    shutdown: Arc<Notify>,
+    task: JoinHandle<Result<()>>,
+}
+
+/// Adds and drops symbols on a long-running service without a restart
+///
+/// Each subscribed symbol runs its own `WebSocketManager` on its own
+/// connection, built by the factory, so a gap or resync on one symbol never
+/// stalls the others; `WebSocketManager::command_sender` instead adds symbols
+/// to one shared connection. `Command::Subscribe` starts a pipeline and
+/// `Command::Unsubscribe` shuts it down and drops its book.
+pub struct SymbolSupervisor<C: ExchangeConnector + 'static = BinanceClient> {
+    factory: ManagerFactory<C>,
+    pipelines: HashMap<String, Pipeline<C>>,
+    command_sender: mpsc::Sender<Command>,
+    command_receiver: mpsc::Receiver<Command>,
+}
+
+impl<C: ExchangeConnector + 'static> SymbolSupervisor<C> {
+    /// Create a supervisor that builds each symbol's manager with `factory`
+    ///
+    /// The factory is called with the uppercase symbol and should return a
+    /// manager configured for it.
+    pub fn new(factory: impl Fn(&str) -> WebSocketManager<C> + Send + Sync + 'static) -> Self {
+        let (command_sender, command_receiver) = mpsc::channel(16);
+        Self {
+            factory: Box::new(factory),
+            pipelines: HashMap::new(),
+            command_sender,
+            command_receiver,
+        }
+    }
+    
+    /// Get a handle for subscribing to or dropping symbols while `run` is going
+    pub fn command_sender(&self) -> mpsc::Sender<Command> {
+        self.command_sender.clone()
+    }
+    
+    /// Currently subscribed symbols, sorted
+    pub fn symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self.pipelines.keys().cloned().collect();
+        symbols.sort();
+        symbols
+    }
+    
+    /// The manager streaming `symbol`, e.g. to attach result or signal sinks
+    pub fn manager(&self, symbol: &str) -> Option<Arc<WebSocketManager<C>>> {
+        self.pipelines
+            .get(&symbol.to_uppercase())
+            .map(|pipeline| pipeline.manager.clone())
+    }
+    
+    /// Start or stop a symbol's pipeline; returns whether anything changed
+    ///
+    /// Subscribing to a running symbol or unsubscribing from an unknown one
+    /// is ignored. A symbol whose pipeline has exited, e.g. after running out
+    /// of reconnect attempts, can be subscribed to again.
+    pub fn apply(&mut self, command: Command) -> bool {
+        match command {
+            Command::Subscribe(symbol) => {
+                let symbol = symbol.trim().to_uppercase();
+                if symbol.is_empty() {
+                    warn!("Ignoring subscribe without a symbol");
+                    return false;
+                }
+                if self.pipelines.get(&symbol).is_some_and(|pipeline| !pipeline.task.is_finished()) {
+                    warn!("Ignoring subscribe: {} is already streaming", symbol);
+                    return false;
+                }
+                
+                info!("📡 Subscribing to {}", symbol);
+                let manager = Arc::new((self.factory)(&symbol));
+                let shutdown = Arc::new(Notify::new());
+                let task = tokio::spawn({
+                    let manager = manager.clone();
+                    let shutdown = shutdown.clone();
+                    let symbol = symbol.clone();
+                    async move {
+                        let result = manager.start_with_shutdown(shutdown).await;
+                        if let Err(e) = &result {
+                            error!("❌ WebSocket manager for {} failed: {}", symbol, e);
+                        }
+                        result
+                    }
+                });
+                self.pipelines.insert(symbol, Pipeline { manager, shutdown, task });
+                true
+            }
+            Command::Unsubscribe(symbol) => {
+                let symbol = symbol.trim().to_uppercase();
+                match self.pipelines.remove(&symbol) {
+                    Some(pipeline) => {
+                        // The task closes its socket and exits on its own
+                        info!("📡 Unsubscribing from {}", symbol);
+                        pipeline.shutdown.notify_one();
+                        true
+                    }
+                    None => {
+                        warn!("Ignoring unsubscribe: {} is not streaming", symbol);
+                        false
+                    }
+                }
+            }
+        }
+    }
+    
+    /// Apply commands until `shutdown` is notified, then stop every pipeline
+    pub async fn run(&mut self, shutdown: &Notify) -> Result<()> {
+        loop {
+            tokio::select! {
+                _ = shutdown.notified() => break,
+                Some(command) = self.command_receiver.recv() => {
+                    self.apply(command);
+                }
+            }
+        }
+        
+        info!("🛑 Stopping {} symbol pipelines", self.pipelines.len());
+        let pipelines: Vec<Pipeline<C>> = self.pipelines.drain().map(|(_, pipeline)| pipeline).collect();
+        for pipeline in &pipelines {
+            pipeline.shutdown.notify_one();
+        }
+        for pipeline in pipelines {
+            // Failures were already logged by the pipeline itself
+            let _ = pipeline.task.await;
+        }
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, DepthStreamMode, FairPriceMethod};
+    use crate::fair_price::FairPriceCalculator;
+    use crate::order_book::OrderBookManager;
+    use crate::websocket::ConnectionState;
+    use std::time::Duration;
+    
+    /// Supervisor whose managers retry a dead local port `reconnect_attempts` times
+    fn supervisor(reconnect_attempts: u32) -> SymbolSupervisor {
+        SymbolSupervisor::new(move |symbol| {
+            let config = Config::builder()
+                .symbol(symbol)
+                .ws_base_url("ws://127.0.0.1:1")
+                .depth_stream(DepthStreamMode::Partial { levels: 5 })
+                .reconnect_attempts(reconnect_attempts)
+                .reconnect_delay_ms(60_000)
+                .build()
+                .unwrap();
+            WebSocketManager::new(
+                config,
+                Arc::new(OrderBookManager::new()),
+                FairPriceCalculator::new(FairPriceMethod::MidPrice),
+            )
+        })
+    }
+    
+    #[tokio::test]
+    async fn test_commands_add_and_drop_symbols() {
+        let mut supervisor = supervisor(10);
+        
+        assert!(supervisor.apply(Command::Subscribe("ethusdt".to_string())));
+        assert!(supervisor.apply(Command::Subscribe("BTCUSDT".to_string())));
+        assert!(!supervisor.apply(Command::Subscribe("ETHUSDT".to_string())));
+        assert_eq!(supervisor.symbols(), vec!["BTCUSDT", "ETHUSDT"]);
+        
+        // Each symbol gets its own manager
+        let eth = supervisor.manager("ETHUSDT").unwrap();
+        assert_eq!(eth.symbol(), "ETHUSDT");
+        assert_eq!(supervisor.manager("btcusdt").unwrap().symbol(), "BTCUSDT");
+        
+        // Unsubscribing drops the symbol and shuts its manager down
+        assert!(supervisor.apply(Command::Unsubscribe("ETHUSDT".to_string())));
+        assert!(!supervisor.apply(Command::Unsubscribe("ETHUSDT".to_string())));
+        assert_eq!(supervisor.symbols(), vec!["BTCUSDT"]);
+        assert!(supervisor.manager("ETHUSDT").is_none());
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while eth.connection_state() != ConnectionState::Closed {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_resubscribe_after_pipeline_exits() {
+        // One failed attempt and the manager gives up
+        let mut supervisor = supervisor(1);
+        assert!(supervisor.apply(Command::Subscribe("ETHUSDT".to_string())));
+        let first = supervisor.manager("ETHUSDT").unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !supervisor.pipelines["ETHUSDT"].task.is_finished() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        
+        // The dead pipeline is replaced rather than blocking the symbol
+        assert!(supervisor.apply(Command::Subscribe("ETHUSDT".to_string())));
+        let second = supervisor.manager("ETHUSDT").unwrap();
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert_eq!(supervisor.symbols(), vec!["ETHUSDT"]);
+    }
+}
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Deserializer};
+use std::collections::VecDeque;
+
+/// Aggregated trade from the `<symbol>@aggTrade` WebSocket stream
+#[derive(Debug, Clone, Deserialize)]
+pub struct AggTrade {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "a")]
+    pub trade_id: u64,
+    #[serde(rename = "p", deserialize_with = "f64_from_str")]
+    pub price: f64,
+    #[serde(rename = "q", deserialize_with = "f64_from_str")]
+    pub quantity: f64,
+    /// True when the buyer was the maker (i.e. the aggressor sold)
+    #[serde(rename = "m")]
+    pub buyer_is_maker: bool,
+    /// Trade time (milliseconds)
+    #[serde(rename = "T")]
+    pub timestamp: u64,
+}
+
+/// Deserialize a Binance decimal string (e.g. `"0.001"`) as `f64`
+pub(crate) fn f64_from_str<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    value.parse::<f64>().map_err(serde::de::Error::custom)
+}
+
+impl AggTrade {
+    /// Quantity signed by aggressor side (positive = buy, negative = sell)
+    pub fn signed_quantity(&self) -> f64 {
+        if self.buyer_is_maker {
+            -self.quantity
+        } else {
+            self.quantity
+        }
+    }
+}
+
+/// Rolling window of executed trade volume split by aggressor side
+#[derive(Debug, Clone)]
+pub struct TradeFlow {
+    window_ms: u64,
+    trades: VecDeque<(u64, f64)>, // (timestamp_ms, signed quantity)
+    buy_volume: f64,
+    sell_volume: f64,
+}
+
+impl TradeFlow {
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            trades: VecDeque::new(),
+            buy_volume: 0.0,
+            sell_volume: 0.0,
+        }
+    }
+    
+    /// Record a trade and drop trades that fell out of the window
+    pub fn record(&mut self, trade: &AggTrade) {
+        let signed_quantity = trade.signed_quantity();
+        self.trades.push_back((trade.timestamp, signed_quantity));
+        self.add_volume(signed_quantity, 1.0);
+        
+        let cutoff = trade.timestamp.saturating_sub(self.window_ms);
+        while let Some(&(timestamp, quantity)) = self.trades.front() {
+            if timestamp >= cutoff {
+                break;
+            }
+            self.trades.pop_front();
+            self.add_volume(quantity, -1.0);
+        }
+    }
+    
+    fn add_volume(&mut self, signed_quantity: f64, sign: f64) {
+        if signed_quantity >= 0.0 {
+            self.buy_volume = (self.buy_volume + sign * signed_quantity).max(0.0);
+        } else {
+            self.sell_volume = (self.sell_volume - sign * signed_quantity).max(0.0);
+        }
+    }
+    
+    /// Aggressive buy volume in the window
+    pub fn buy_volume(&self) -> f64 {
+        self.buy_volume
+    }
+    
+    /// Aggressive sell volume in the window
+    pub fn sell_volume(&self) -> f64 {
+        self.sell_volume
+    }
+    
+    /// Net signed volume in the window (buy minus sell)
+    pub fn signed_volume(&self) -> f64 {
+        self.buy_volume - self.sell_volume
+    }
+    
+    /// Trade flow imbalance: -1.0 (all sells) to 1.0 (all buys)
+    pub fn imbalance(&self) -> Option<f64> {
+        let total = self.buy_volume + self.sell_volume;
+        if total > 0.0 {
+            Some(self.signed_volume() / total)
+        } else {
+            None
+        }
+    }
+    
+    /// Number of trades in the window
+    pub fn len(&self) -> usize {
+        self.trades.len()
+    }
+    
+    /// Check if the window holds no trades
+    pub fn is_empty(&self) -> bool {
+        self.trades.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_agg_trade() {
+        let payload = r#"{
+            "e": "aggTrade", "E": 1672515782136, "s": "BTCUSDT", "a": 12345,
+            "p": "16500.50", "q": "0.250", "f": 100, "l": 105,
+            "T": 1672515782134, "m": true, "M": true
+        }"#;
+        
+        let trade: AggTrade = serde_json::from_str(payload).unwrap();
+        assert_eq!(trade.symbol, "BTCUSDT");
+        assert_eq!(trade.trade_id, 12345);
+        assert_eq!(trade.price, 16500.50);
+        assert_eq!(trade.quantity, 0.25);
+        assert!(trade.buyer_is_maker);
+        assert_eq!(trade.timestamp, 1672515782134);
+        assert_eq!(trade.signed_quantity(), -0.25);
+    }
+    
+    #[test]
+    fn test_trade_flow_window() {
+        let trade = |timestamp: u64, quantity: f64, buyer_is_maker: bool| AggTrade {
+            symbol: "BTCUSDT".to_string(),
+            trade_id: timestamp,
+            price: 100.0,
+            quantity,
+            buyer_is_maker,
+            timestamp,
+        };
+        
+        let mut flow = TradeFlow::new(1_000);
+        flow.record(&trade(0, 3.0, false));
+        flow.record(&trade(500, 1.0, true));
+        assert_eq!(flow.signed_volume(), 2.0);
+        assert_eq!(flow.imbalance(), Some(0.5));
+        
+        // The first trade falls out of the window
+        flow.record(&trade(1_200, 1.0, true));
+        assert_eq!(flow.len(), 2);
+        assert_eq!(flow.buy_volume(), 0.0);
+        assert_eq!(flow.sell_volume(), 2.0);
+        assert_eq!(flow.imbalance(), Some(-1.0));
+    }
+}
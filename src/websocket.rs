@@ -1,62 +1,302 @@
 use crate::binance::BinanceClient;
-use crate::config::Config;
-use crate::fair_price::FairPriceCalculator;
-use crate::order_book::{OrderBookManager, OrderBookUpdate};
+use crate::config::{Config, DepthStreamMode, DisplayMode, KeepaliveMode, DEFAULT_DISPLAY_PRECISION};
+use crate::exchange::ExchangeConnector;
+use crate::fair_price::{ratio_to_mid, FairPriceCalculator, FairPriceResult, MarketSignal, SignalDebouncer};
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+use crate::order_book::{OrderBook, OrderBookError, OrderBookManager};
+use crate::trades::{AggTrade, TradeFlow};
 use anyhow::{Result, anyhow};
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{Sink, SinkExt, StreamExt};
 use serde_json::Value;
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::time::{interval, timeout};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, mpsc, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep_until, timeout, Instant};
+use tokio_tungstenite::{connect_async, tungstenite, tungstenite::Message};
 use tracing::{debug, error, info, warn};
 
+/// Smoothing factor for the rolling feed lag average
+const LAG_EMA_ALPHA: f64 = 0.1;
+
+/// Window for the rolling depth update rate (milliseconds)
+const UPDATE_RATE_WINDOW_MS: u64 = 10_000;
+
+/// Event types that can share a connection with the depth stream but carry no depth
+const NON_DEPTH_EVENTS: [&str; 7] = [
+    "trade",
+    "kline",
+    "24hrTicker",
+    "24hrMiniTicker",
+    "markPriceUpdate",
+    "forceOrder",
+    "listenKeyExpired",
+];
+
 /// WebSocket connection manager
-pub struct WebSocketManager {
+///
+/// Symbols added with `with_symbol` share the manager's connection as a
+/// combined stream; each gets its own feed, a manager with its own book,
+/// calculator and stats that is driven by this one's connection.
+pub struct WebSocketManager<C: ExchangeConnector = BinanceClient> {
     config: Config,
     order_book_manager: Arc<OrderBookManager>,
-    fair_price_calculator: Arc<FairPriceCalculator>,
-    binance_client: BinanceClient,
+    fair_price_calculator: Mutex<FairPriceCalculator>, // Keeps price history across ticks
+    connector: Arc<C>,
+    feeds: Mutex<HashMap<String, Arc<WebSocketManager<C>>>>, // Other symbols on this connection, by uppercase symbol
+    last_calculation_us: AtomicU64, // Timestamp of the last fair price calculation
+    calculation_pending: AtomicBool, // A calculation was deferred by the update threshold
+    trade_flow: Mutex<TradeFlow>,
+    signal_debouncer: Mutex<SignalDebouncer>,
+    avg_lag_ms: Mutex<Option<f64>>, // Rolling average of local receive time minus event time
+    book_progress: Mutex<Option<(u64, u64)>>, // Last seen order book update ID and when it advanced (ms)
+    messages_processed: AtomicU64, // Depth updates applied to the book
+    update_times: Mutex<VecDeque<u64>>, // Depth update receive times within the rate window (ms)
+    signal_sender: broadcast::Sender<SignalChange>,
+    state: Mutex<ConnectionState>,
+    live_since: Mutex<Option<Instant>>, // When the current connection went live
+    state_sender: broadcast::Sender<ConnectionState>,
+    result_sender: broadcast::Sender<FairPriceResult>,
+    next_request_id: AtomicU64, // ID for the next SUBSCRIBE/UNSUBSCRIBE frame
+    inbound: Arc<Mutex<InboundBuffer>>, // Frames read from the socket awaiting processing
+    command_sender: mpsc::Sender<Command>,
+    command_receiver: tokio::sync::Mutex<mpsc::Receiver<Command>>, // Held by the running connection
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl WebSocketManager {
+    /// Create a manager streaming from Binance
+    ///
+    /// The manager owns `fair_price_calculator`; read its live state with
+    /// `with_calculator`.
     pub fn new(
         config: Config,
         order_book_manager: Arc<OrderBookManager>,
-        fair_price_calculator: Arc<FairPriceCalculator>,
+        fair_price_calculator: FairPriceCalculator,
+    ) -> Self {
+        let binance_client = BinanceClient::from_config(&config);
+        Self::with_connector(config, order_book_manager, fair_price_calculator, binance_client)
+    }
+}
+
+impl<C: ExchangeConnector> WebSocketManager<C> {
+    /// Create a manager streaming from another exchange
+    pub fn with_connector(
+        config: Config,
+        order_book_manager: Arc<OrderBookManager>,
+        fair_price_calculator: FairPriceCalculator,
+        connector: C,
+    ) -> Self {
+        Self::with_shared_connector(config, order_book_manager, fair_price_calculator, Arc::new(connector))
+    }
+    
+    /// Create a manager on a connector shared with its feeds
+    fn with_shared_connector(
+        config: Config,
+        order_book_manager: Arc<OrderBookManager>,
+        fair_price_calculator: FairPriceCalculator,
+        connector: Arc<C>,
     ) -> Self {
+        let trade_flow = Mutex::new(TradeFlow::new(config.websocket.trade_flow_window_ms));
+        let (signal_sender, _) = broadcast::channel(16);
+        let (result_sender, _) = broadcast::channel(256);
+        let (state_sender, _) = broadcast::channel(16);
+        let inbound = Arc::new(Mutex::new(InboundBuffer::new(
+            config.websocket.max_buffered_messages,
+            config.websocket.depth_stream,
+        )));
+        let (command_sender, command_receiver) = mpsc::channel(16);
+        
         Self {
             config,
             order_book_manager,
-            fair_price_calculator,
-            binance_client: BinanceClient::new(),
+            fair_price_calculator: Mutex::new(fair_price_calculator),
+            connector,
+            feeds: Mutex::new(HashMap::new()),
+            last_calculation_us: AtomicU64::new(0),
+            calculation_pending: AtomicBool::new(false),
+            trade_flow,
+            signal_debouncer: Mutex::new(SignalDebouncer::default()),
+            avg_lag_ms: Mutex::new(None),
+            book_progress: Mutex::new(None),
+            messages_processed: AtomicU64::new(0),
+            update_times: Mutex::new(VecDeque::new()),
+            signal_sender,
+            state: Mutex::new(ConnectionState::Closed),
+            live_since: Mutex::new(None),
+            state_sender,
+            result_sender,
+            next_request_id: AtomicU64::new(1),
+            inbound,
+            command_sender,
+            command_receiver: tokio::sync::Mutex::new(command_receiver),
+            #[cfg(feature = "metrics")]
+            metrics: None,
         }
     }
     
+    /// Debounce signal changes so marginal ticks don't flip the reported signal
+    pub fn with_signal_debouncer(mut self, debouncer: SignalDebouncer) -> Self {
+        self.signal_debouncer = Mutex::new(debouncer);
+        self
+    }
+    
+    /// Report Prometheus metrics while running
+    ///
+    /// Call before `with_symbol` so the added symbols report too.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    
+    /// Stream another symbol on this manager's connection
+    ///
+    /// `config` supplies the symbol and its order book, calculation and
+    /// display settings; the connection settings are this manager's. Needs a
+    /// connector with combined streams.
+    pub fn with_symbol(
+        self,
+        config: Config,
+        order_book_manager: Arc<OrderBookManager>,
+        fair_price_calculator: FairPriceCalculator,
+    ) -> Self {
+        let feed = self.feed_manager(config, order_book_manager, fair_price_calculator);
+        self.feeds.lock().unwrap().insert(feed.config.symbol.to_uppercase(), Arc::new(feed));
+        self
+    }
+    
+    /// Build the feed for another symbol, sharing this manager's connector,
+    /// connection settings, metrics and signal debouncing
+    fn feed_manager(
+        &self,
+        mut config: Config,
+        order_book_manager: Arc<OrderBookManager>,
+        fair_price_calculator: FairPriceCalculator,
+    ) -> Self {
+        config.websocket = self.config.websocket.clone();
+        let mut signal_debouncer = self.signal_debouncer.lock().unwrap().clone();
+        signal_debouncer.reset();
+        
+        let feed = Self::with_shared_connector(
+            config,
+            order_book_manager,
+            fair_price_calculator,
+            self.connector.clone(),
+        )
+        .with_signal_debouncer(signal_debouncer);
+        #[cfg(feature = "metrics")]
+        let feed = match &self.metrics {
+            Some(metrics) => feed.with_metrics(metrics.clone()),
+            None => feed,
+        };
+        *feed.state.lock().unwrap() = self.connection_state();
+        feed
+    }
+    
+    /// Managers for the other symbols on this connection, sorted by symbol
+    pub fn feeds(&self) -> Vec<Arc<WebSocketManager<C>>> {
+        let feeds = self.feeds.lock().unwrap();
+        let mut feeds: Vec<Arc<WebSocketManager<C>>> = feeds.values().cloned().collect();
+        feeds.sort_by(|a, b| a.config.symbol.cmp(&b.config.symbol));
+        feeds
+    }
+    
+    /// The feed streaming `symbol` on this connection, if it is not this manager's own
+    pub fn feed(&self, symbol: &str) -> Option<Arc<WebSocketManager<C>>> {
+        self.feeds.lock().unwrap().get(&symbol.to_uppercase()).cloned()
+    }
+    
+    /// Get a handle for subscribing to or dropping symbols on the live connection
+    ///
+    /// Commands are applied by the running connection, or by the next one
+    /// while reconnecting. A new symbol gets its own feed with a fresh book
+    /// and calculator; its precision and tick size are the defaults.
+    pub fn command_sender(&self) -> mpsc::Sender<Command> {
+        self.command_sender.clone()
+    }
+    
+    /// Every symbol streamed on this connection, this manager's own first
+    pub fn symbols(&self) -> Vec<String> {
+        std::iter::once(self.config.symbol.clone())
+            .chain(self.feeds().iter().map(|feed| feed.config.symbol.clone()))
+            .collect()
+    }
+    
     /// Start WebSocket connection and processing
     pub async fn start(&self) -> Result<()> {
+        self.start_with_shutdown(Arc::new(Notify::new())).await
+    }
+    
+    /// Start WebSocket connection and processing until `shutdown` is notified
+    ///
+    /// Call `shutdown.notify_one()` to close the WebSocket with a Close frame
+    /// and return `Ok(())`.
+    pub async fn start_with_shutdown(&self, shutdown: Arc<Notify>) -> Result<()> {
+        let result = self.run_with_reconnects(&shutdown).await;
+        self.set_state(ConnectionState::Closed);
+        result
+    }
+    
+    /// Reconnect loop behind `start_with_shutdown`
+    async fn run_with_reconnects(&self, shutdown: &Notify) -> Result<()> {
         let mut reconnect_attempts = 0;
+        let mut clean_closes = 0;
         let max_attempts = self.config.websocket.reconnect_attempts;
+        let stable_after = Duration::from_millis(self.config.websocket.stable_connection_ms);
         
         while reconnect_attempts < max_attempts {
-            match self.connect_and_process().await {
+            self.set_state(ConnectionState::Connecting);
+            let outcome = self.connect_and_process(shutdown).await;
+            let live_for = self.live_since.lock().unwrap().take().map(|since| since.elapsed());
+            match outcome {
+                Ok(end) if reconnects_after(end, self.config.websocket.reconnect_on_clean_close) => {
+                    // A clean close is routine (e.g. scheduled server restarts), but a server
+                    // that closes every connection must not turn into a reconnect storm
+                    self.clear_order_books();
+                    clean_closes = clean_closes_after(clean_closes, live_for, stable_after, max_attempts)
+                        .ok_or_else(|| anyhow!(
+                            "Server closed {} connections in a row before they became stable",
+                            max_attempts
+                        ))?;
+                    
+                    self.set_state(ConnectionState::Reconnecting);
+                    info!(
+                        "🔄 Server closed the connection cleanly ({}/{}), reconnecting in {}ms",
+                        clean_closes, max_attempts, self.config.websocket.reconnect_delay_ms
+                    );
+                    if !self.wait_before_reconnect(shutdown).await {
+                        break;
+                    }
+                }
                 Ok(_) => {
                     info!("WebSocket connection completed successfully");
                     break;
                 }
                 Err(e) => {
-                    reconnect_attempts += 1;
+                    // Stale data must not look ready while we wait for a fresh snapshot
+                    self.clear_order_books();
+                    reconnect_attempts = attempts_after_drop(reconnect_attempts, live_for, stable_after);
+                    #[cfg(feature = "metrics")]
+                    if let Some(metrics) = &self.metrics {
+                        metrics.reconnects_total.with_label_values(&[&self.config.symbol]).inc();
+                    }
                     error!(
                         "WebSocket connection failed (attempt {}/{}): {}",
                         reconnect_attempts, max_attempts, e
                     );
                     
                     if reconnect_attempts < max_attempts {
+                        self.set_state(ConnectionState::Reconnecting);
                         info!("Retrying in {} seconds...", 
                               self.config.websocket.reconnect_delay_ms / 1000);
-                        tokio::time::sleep(Duration::from_millis(
-                            self.config.websocket.reconnect_delay_ms
-                        )).await;
+                        if !self.wait_before_reconnect(shutdown).await {
+                            break;
+                        }
                     } else {
                         return Err(anyhow!("Max reconnection attempts reached"));
                     }
@@ -67,68 +307,260 @@ impl WebSocketManager {
         Ok(())
     }
     
+    /// Sleep for the reconnect delay; returns `false` if shutdown was requested meanwhile
+    async fn wait_before_reconnect(&self, shutdown: &Notify) -> bool {
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(self.config.websocket.reconnect_delay_ms)) => true,
+            _ = shutdown.notified() => {
+                info!("🛑 Shutdown requested during reconnect delay");
+                false
+            }
+        }
+    }
+    
     /// Connect to WebSocket and process messages
-    async fn connect_and_process(&self) -> Result<()> {
-        // Get order book snapshot first for initialization
-        info!("📊 Fetching initial order book snapshot...");
-        self.initialize_order_book().await?;
-        
-        // Connect to WebSocket stream
-        let stream_url = self.binance_client.get_orderbook_diff_stream_url(&self.config.symbol);
-        info!("🔗 Connecting to WebSocket: {}", stream_url);
+    ///
+    /// Returns `Ok` when a shutdown was requested or the server closed the
+    /// connection with a Close frame, and `Err` for anything else.
+    async fn connect_and_process(&self, shutdown: &Notify) -> Result<ConnectionEnd> {
+        let combined_url = self.combined_stream_url();
+        let connect = async {
+            if self.config.websocket.depth_stream == DepthStreamMode::Diff {
+                // Get order book snapshots first for initialization; partial
+                // frames carry the full top-N book and need none
+                self.set_state(ConnectionState::Snapshotting);
+                info!("📊 Fetching initial order book snapshot...");
+                self.initialize_order_books().await?;
+            }
+            let stream_url = match &combined_url {
+                Some(url) => url.clone(),
+                None => self.single_stream_url()?,
+            };
+            
+            // Connect to WebSocket stream
+            info!("🔗 Connecting to WebSocket: {}", stream_url);
+            
+            let (ws_stream, _response) = connect_async(&stream_url).await?;
+            Ok::<_, anyhow::Error>(ws_stream)
+        };
         
-        let (ws_stream, _response) = connect_async(&stream_url).await?;
+        let ws_stream = tokio::select! {
+            biased;
+            _ = shutdown.notified() => {
+                info!("🛑 Shutdown requested before WebSocket connected");
+                return Ok(ConnectionEnd::Shutdown);
+            }
+            result = connect => result?,
+        };
         info!("✅ WebSocket connected successfully");
+        self.set_state(ConnectionState::Live);
         
         let (mut ws_sender, mut ws_receiver) = ws_stream.split();
         
+        // Read on a separate task so slow processing fills the bounded buffer instead of stalling the socket
+        self.inbound.lock().unwrap().clear();
+        let inbound_ready = Arc::new(Notify::new());
+        let _reader = AbortOnDrop(tokio::spawn({
+            let inbound = Arc::clone(&self.inbound);
+            let inbound_ready = Arc::clone(&inbound_ready);
+            async move {
+                loop {
+                    let frame = ws_receiver.next().await;
+                    let ended = !matches!(frame, Some(Ok(_)));
+                    let dropped = inbound.lock().unwrap().push(frame);
+                    if dropped > 0 {
+                        debug!("Message buffer full, dropped {} frames", dropped);
+                    }
+                    inbound_ready.notify_one();
+                    if ended {
+                        break;
+                    }
+                }
+            }
+        }));
+        
+        // Subscribe to executed trades alongside depth; a combined URL already carries them
+        if self.config.websocket.agg_trades && combined_url.is_none() {
+            let frame = self.connector
+                .trade_stream_name(&self.config.symbol)
+                .and_then(|stream| {
+                    self.connector.subscription_frame(
+                        true,
+                        vec![stream],
+                        self.next_request_id.fetch_add(1, Ordering::Relaxed),
+                    )
+                });
+            match frame {
+                Some(frame) => {
+                    info!("📈 Subscribing to aggTrade stream");
+                    ws_sender.send(Message::Text(frame)).await?;
+                }
+                None => warn!("Exchange has no trade stream, trade flow disabled"),
+            }
+        }
+        
         // Set up ping interval for connection health
         let mut ping_interval = interval(Duration::from_millis(
             self.config.websocket.ping_interval_ms
         ));
         
+        // Watchdog for half-open connections that stop delivering frames
+        let read_timeout = Duration::from_millis(self.config.websocket.read_timeout_ms);
+        let mut last_traffic = Instant::now();
+        
+        // Heartbeat recalculation for quiet books
+        let recalc_interval = self.config.websocket.recalc_interval_ms.map(Duration::from_millis);
+        let mut last_recalc = Instant::now();
+        
+        // Periodic self-heal check against a REST snapshot (diff books only)
+        let verify_interval = match self.config.websocket.depth_stream {
+            DepthStreamMode::Diff => self.config.order_book.verify_interval_ms.map(Duration::from_millis),
+            DepthStreamMode::Partial { .. } => None,
+        };
+        let mut last_verify = Instant::now();
+        
+        // Scheduled full refresh, regardless of divergence (diff books only)
+        let refresh_interval = match self.config.websocket.depth_stream {
+            DepthStreamMode::Diff => self.config.order_book.snapshot_refresh_interval_ms.map(Duration::from_millis),
+            DepthStreamMode::Partial { .. } => None,
+        };
+        let mut last_refresh = Instant::now();
+        
+        // Subscription changes ride on this connection, so only it takes commands
+        let mut commands = self.command_receiver.lock().await;
+        
         // Message processing loop
-        loop {
+        'connection: loop {
             tokio::select! {
-                // Handle incoming WebSocket messages
-                msg = ws_receiver.next() => {
-                    match msg {
-                        Some(Ok(Message::Text(text))) => {
-                            if let Err(e) = self.process_message(&text).await {
-                                warn!("Failed to process message: {}", e);
+                // Close cleanly when shutdown is requested
+                _ = shutdown.notified() => {
+                    info!("🛑 Shutdown requested, closing WebSocket");
+                    if let Err(e) = ws_sender.send(Message::Close(None)).await {
+                        warn!("Failed to send close frame: {}", e);
+                    }
+                    return Ok(ConnectionEnd::Shutdown);
+                }
+                
+                // Handle buffered WebSocket messages
+                _ = inbound_ready.notified() => {
+                    while let Some((msg, resync)) = self.next_inbound() {
+                        if resync {
+                            // Queued diffs were flushed on overflow; one snapshot per book covers them all
+                            warn!("Message buffer overflowed for {}, resyncing from snapshot", self.symbols().join(", "));
+                            if let Err(e) = self.resync_order_books().await {
+                                error!("Failed to resync order book: {}", e);
+                                break 'connection;
                             }
                         }
-                        Some(Ok(Message::Ping(data))) => {
-                            debug!("Received ping, sending pong");
-                            if let Err(e) = ws_sender.send(Message::Pong(data)).await {
-                                error!("Failed to send pong: {}", e);
-                                break;
+                        
+                        if matches!(msg, Some(Ok(_))) {
+                            last_traffic = Instant::now();
+                        }
+                        
+                        match msg {
+                            Some(Ok(Message::Text(text))) => {
+                                if let Err(e) = self.process_frame(&text).await {
+                                    error!("Failed to resync order book: {}", e);
+                                    break 'connection;
+                                }
+                                last_recalc = Instant::now();
+                            }
+                            Some(Ok(Message::Ping(data))) => {
+                                debug!("Received ping, sending pong");
+                                if let Err(e) = ws_sender.send(Message::Pong(data)).await {
+                                    error!("Failed to send pong: {}", e);
+                                    break 'connection;
+                                }
+                            }
+                            Some(Ok(Message::Pong(_))) => {
+                                debug!("Received pong");
+                            }
+                            Some(Ok(Message::Close(_))) => {
+                                info!("WebSocket connection closed by server");
+                                return Ok(ConnectionEnd::ClosedByServer);
                             }
+                            Some(Err(e)) => {
+                                error!("WebSocket error: {}", e);
+                                break 'connection;
+                            }
+                            None => {
+                                warn!("WebSocket stream ended");
+                                break 'connection;
+                            }
+                            _ => {}
                         }
-                        Some(Ok(Message::Pong(_))) => {
-                            debug!("Received pong");
+                    }
+                }
+                
+                // Add or drop symbols without reconnecting
+                Some(command) = commands.recv() => {
+                    if combined_url.is_none() {
+                        warn!("Exchange has no combined streams, ignoring {:?}", command);
+                    } else if let Err(e) = self.apply_command(command, &mut ws_sender).await {
+                        error!("Failed to send subscription frame: {}", e);
+                        break 'connection;
+                    }
+                }
+                
+                // Send periodic keepalives
+                _ = ping_interval.tick() => {
+                    debug!("Sending keepalive");
+                    if let Err(e) = ws_sender.send(self.keepalive_message()).await {
+                        error!("Failed to send keepalive: {}", e);
+                        break;
+                    }
+                }
+                
+                // Price the final state of a burst once its throttle window ends
+                _ = sleep_until_or_pending(self.next_pending_calculation()) => {
+                    let feeds = self.feeds();
+                    for manager in std::iter::once(self).chain(feeds.iter().map(Arc::as_ref)) {
+                        // Early ones are deferred again by the threshold check
+                        if manager.calculation_pending.swap(false, Ordering::Relaxed) {
+                            if let Err(e) = manager.calculate_and_display_fair_price().await {
+                                warn!("Failed to calculate deferred fair price: {}", e);
+                            }
                         }
-                        Some(Ok(Message::Close(_))) => {
-                            info!("WebSocket connection closed by server");
-                            break;
+                    }
+                }
+                
+                // Recompute on a timer so consumers keep getting results from a quiet book
+                _ = sleep_until_or_pending(next_deadline(last_recalc, recalc_interval)) => {
+                    last_recalc = Instant::now();
+                    let feeds = self.feeds();
+                    for manager in std::iter::once(self).chain(feeds.iter().map(Arc::as_ref)) {
+                        if let Err(e) = manager.calculate_and_display_fair_price().await {
+                            warn!("Failed to recalculate fair price: {}", e);
                         }
-                        Some(Err(e)) => {
-                            error!("WebSocket error: {}", e);
-                            break;
+                    }
+                }
+                
+                // Resync if a local book has drifted from the exchange
+                _ = sleep_until_or_pending(next_deadline(last_verify, verify_interval)) => {
+                    let feeds = self.feeds();
+                    for manager in std::iter::once(self).chain(feeds.iter().map(Arc::as_ref)) {
+                        if let Err(e) = manager.verify_order_book().await {
+                            warn!("Order book verification failed: {}", e);
                         }
-                        None => {
-                            warn!("WebSocket stream ended");
-                            break;
+                    }
+                    last_verify = Instant::now();
+                }
+                
+                // Replace the books outright to shed any drift the checks missed
+                _ = sleep_until_or_pending(next_deadline(last_refresh, refresh_interval)) => {
+                    let feeds = self.feeds();
+                    for manager in std::iter::once(self).chain(feeds.iter().map(Arc::as_ref)) {
+                        if let Err(e) = manager.refresh_order_book().await {
+                            warn!("Scheduled order book refresh failed: {}", e);
                         }
-                        _ => {}
                     }
+                    last_refresh = Instant::now();
                 }
                 
-                // Send periodic pings
-                _ = ping_interval.tick() => {
-                    debug!("Sending ping");
-                    if let Err(e) = ws_sender.send(Message::Ping(vec![])).await {
-                        error!("Failed to send ping: {}", e);
+                // Reconnect if nothing has arrived within the read timeout
+                _ = sleep_until(last_traffic + read_timeout) => {
+                    if read_timed_out(last_traffic, Instant::now(), read_timeout) {
+                        warn!("No WebSocket traffic for {:?}, reconnecting", read_timeout);
                         break;
                     }
                 }
@@ -138,49 +570,354 @@ impl WebSocketManager {
         Err(anyhow!("WebSocket connection ended"))
     }
     
-    /// Initialize order book from REST API snapshot
-    async fn initialize_order_book(&self) -> Result<()> {
-        let snapshot_url = format!(
-            "https://api.binance.com/api/v3/depth?symbol={}&limit=100",
-            self.config.symbol
-        );
+    /// Take the oldest buffered frame and whether to resync first; the lock is
+    /// released before it is processed
+    fn next_inbound(&self) -> Option<(InboundFrame, bool)> {
+        self.inbound.lock().unwrap().pop()
+    }
+    
+    /// Subscribe to or drop a symbol on the open connection
+    ///
+    /// Subscribing adds a feed and loads its book once the frame is sent;
+    /// unsubscribing drops the feed and its book. Commands for symbols already
+    /// (or not) streamed are ignored, and only a failed send is an error.
+    async fn apply_command<S>(&self, command: Command, ws_sender: &mut S) -> Result<()>
+    where
+        S: Sink<Message> + Unpin,
+        S::Error: std::error::Error + Send + Sync + 'static,
+    {
+        match command {
+            Command::Subscribe(symbol) => {
+                let symbol = symbol.trim().to_uppercase();
+                if symbol.is_empty() || self.symbols().iter().any(|s| s.eq_ignore_ascii_case(&symbol)) {
+                    warn!("Ignoring subscribe: '{}' is empty or already streaming", symbol);
+                    return Ok(());
+                }
+                let Some(frame) = self.subscription_message(true, std::slice::from_ref(&symbol)) else {
+                    warn!("Exchange has no subscription frames, ignoring subscribe to {}", symbol);
+                    return Ok(());
+                };
+                
+                info!("📡 Subscribing to {}", symbol);
+                let mut config = self.config.clone();
+                config.symbol = symbol.clone();
+                config.display_precision = None;
+                let order_book_manager = Arc::new(OrderBookManager::from_config(&config.order_book));
+                let mut fair_price_calculator = self.fair_price_calculator.lock().unwrap().clone();
+                fair_price_calculator.reset();
+                let feed = Arc::new(self.feed_manager(config, order_book_manager, fair_price_calculator));
+                self.feeds.lock().unwrap().insert(symbol.clone(), feed.clone());
+                ws_sender.send(frame).await?;
+                
+                // Diffs arriving before the snapshot are dropped; the snapshot covers them
+                if self.config.websocket.depth_stream == DepthStreamMode::Diff {
+                    if let Err(e) = feed.initialize_order_book().await {
+                        // Without a book the feed would never recover, so back out
+                        warn!("Failed to load order book for {}, unsubscribing: {}", symbol, e);
+                        self.drop_feed(&symbol, ws_sender).await?;
+                    }
+                }
+            }
+            Command::Unsubscribe(symbol) => {
+                let symbol = symbol.trim().to_uppercase();
+                if symbol.eq_ignore_ascii_case(&self.config.symbol) {
+                    warn!("Ignoring unsubscribe: {} is this connection's own symbol", symbol);
+                    return Ok(());
+                }
+                if self.feed(&symbol).is_none() {
+                    warn!("Ignoring unsubscribe: {} is not streaming", symbol);
+                    return Ok(());
+                }
+                
+                info!("📡 Unsubscribing from {}", symbol);
+                self.drop_feed(&symbol, ws_sender).await?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Stop a feed's streams and drop it with its book
+    async fn drop_feed<S>(&self, symbol: &str, ws_sender: &mut S) -> Result<()>
+    where
+        S: Sink<Message> + Unpin,
+        S::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let Some(feed) = self.feeds.lock().unwrap().remove(symbol) else {
+            return Ok(());
+        };
+        feed.clear_order_book();
+        feed.set_state(ConnectionState::Closed);
         
-        let client = reqwest::Client::new();
-        let response = client.get(&snapshot_url).send().await?;
+        match self.subscription_message(false, &[symbol.to_string()]) {
+            Some(frame) => Ok(ws_sender.send(frame).await?),
+            None => Ok(()),
+        }
+    }
+    
+    /// SUBSCRIBE (or UNSUBSCRIBE) frame for every stream of `symbols`
+    fn subscription_message(&self, subscribe: bool, symbols: &[String]) -> Option<Message> {
+        let mut streams = Vec::new();
+        for symbol in symbols {
+            streams.extend(self.stream_names(symbol)?);
+        }
+        let id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        self.connector.subscription_frame(subscribe, streams, id).map(Message::Text)
+    }
+    
+    /// Combined-stream URL for every symbol's depth (and trades, when enabled),
+    /// or `None` when the connector has no combined streams
+    fn combined_stream_url(&self) -> Option<String> {
+        let mut streams = Vec::new();
+        for symbol in self.symbols() {
+            streams.extend(self.stream_names(&symbol)?);
+        }
+        self.connector.combined_stream_url(&streams)
+    }
+    
+    /// Streams carrying `symbol`'s depth, and its trades when enabled
+    fn stream_names(&self, symbol: &str) -> Option<Vec<String>> {
+        let depth = self.connector.depth_stream_name(symbol, self.config.websocket.depth_stream)?;
+        let trades = self.config.websocket.agg_trades
+            .then(|| self.connector.trade_stream_name(symbol))
+            .flatten();
+        Some(std::iter::once(depth).chain(trades).collect())
+    }
+    
+    /// Depth stream URL for this manager's symbol alone
+    fn single_stream_url(&self) -> Result<String> {
+        if !self.feeds.lock().unwrap().is_empty() {
+            return Err(anyhow!("Exchange has no combined streams for several symbols"));
+        }
+        match self.config.websocket.depth_stream {
+            DepthStreamMode::Diff => Ok(self.connector.diff_stream_url(&self.config.symbol)),
+            DepthStreamMode::Partial { levels } => self.connector
+                .partial_stream_url(&self.config.symbol, levels)
+                .ok_or_else(|| anyhow!("Exchange has no partial depth streams")),
+        }
+    }
+    
+    /// Process one text frame on the manager of the symbol it belongs to,
+    /// resyncing that book after a gap
+    ///
+    /// Combined-stream payloads are unwrapped and routed by their stream's
+    /// symbol; anything else is this manager's. Only a failed resync is
+    /// returned as an error.
+    async fn process_frame(&self, text: &str) -> Result<()> {
+        let (feed, message) = match self.connector.unwrap_combined_frame(text) {
+            Some((symbol, data)) if symbol.eq_ignore_ascii_case(&self.config.symbol) => (None, data),
+            Some((symbol, data)) => match self.feed(&symbol) {
+                Some(feed) => (Some(feed), data),
+                None => {
+                    debug!("Ignoring frame for untracked symbol {}", symbol);
+                    return Ok(());
+                }
+            },
+            None => (None, text),
+        };
+        let manager = feed.as_deref().unwrap_or(self);
         
-        if !response.status().is_success() {
-            return Err(anyhow!("Failed to fetch order book snapshot: {}", response.status()));
+        if let Err(e) = manager.process_message(message).await {
+            if e.downcast_ref::<OrderBookError>().is_some_and(OrderBookError::needs_resync) {
+                // Missed updates leave holes only a fresh snapshot can fill
+                warn!("{} for {}, resyncing from snapshot", e, manager.config.symbol);
+                manager.resync_order_book().await?;
+            } else {
+                warn!("Failed to process message: {}", e);
+            }
         }
         
-        let snapshot: crate::order_book::OrderBookSnapshot = response.json().await?;
+        Ok(())
+    }
+    
+    /// When the earliest calculation deferred on any symbol is due
+    fn next_pending_calculation(&self) -> Option<Instant> {
+        self.feeds()
+            .iter()
+            .filter_map(|feed| feed.pending_calculation_deadline())
+            .chain(self.pending_calculation_deadline())
+            .min()
+    }
+    
+    /// Initialize every symbol's book from a REST snapshot
+    async fn initialize_order_books(&self) -> Result<()> {
+        self.initialize_order_book().await?;
+        for feed in self.feeds() {
+            feed.initialize_order_book().await?;
+        }
+        Ok(())
+    }
+    
+    /// Resync every symbol's book, e.g. after diffs for all of them were flushed
+    async fn resync_order_books(&self) -> Result<()> {
+        self.clear_order_books();
+        self.initialize_order_books().await
+    }
+    
+    /// Drop every symbol's book when the shared connection goes down
+    fn clear_order_books(&self) {
+        self.clear_order_book();
+        for feed in self.feeds() {
+            feed.clear_order_book();
+        }
+    }
+    
+    /// Initialize order book from REST API snapshot
+    async fn initialize_order_book(&self) -> Result<()> {
+        let snapshot = self.connector
+            .fetch_snapshot(&self.config.symbol, self.config.order_book.snapshot_depth)
+            .await?;
         
         self.order_book_manager
             .initialize_from_snapshot(&self.config.symbol, snapshot)?;
+        self.reset_calculator();
+        self.record_book_progress(now_millis()?);
             
-        info!("✅ Order book initialized with {} bids and {} asks", 
-              self.order_book_manager.get_order_book().map_or(0, |ob| ob.bids.len()),
-              self.order_book_manager.get_order_book().map_or(0, |ob| ob.asks.len()));
+        let (bid_levels, ask_levels) = self.order_book_manager
+            .with_order_book(|ob| (ob.bids.len(), ob.asks.len()))
+            .unwrap_or((0, 0));
+        info!("✅ Order book initialized with {} bids and {} asks", bid_levels, ask_levels);
+        
+        Ok(())
+    }
+    
+    /// Drop the book, then re-initialize it from a fresh snapshot
+    ///
+    /// Clearing first keeps the known-bad book from reporting ready while the
+    /// snapshot is in flight.
+    async fn resync_order_book(&self) -> Result<()> {
+        self.clear_order_book();
+        self.initialize_order_book().await
+    }
+    
+    /// Drop the book and the calculator history built from it
+    fn clear_order_book(&self) {
+        self.order_book_manager.clear();
+        self.reset_calculator();
+    }
+    
+    /// Forget price history, the EMA and the imbalance series so they don't
+    /// span a discontinuity in the book
+    fn reset_calculator(&self) {
+        self.fair_price_calculator.lock().unwrap().reset();
+    }
+    
+    /// Replace the book with a fresh snapshot
+    ///
+    /// Unlike `resync_order_book` the current book keeps serving until the new
+    /// one is swapped in, since it is not known to be bad. A snapshot behind
+    /// the live book is discarded rather than rewinding it.
+    async fn refresh_order_book(&self) -> Result<()> {
+        let snapshot = self.connector
+            .fetch_snapshot(&self.config.symbol, self.config.order_book.snapshot_depth)
+            .await?;
+        debug!("Refreshing order book for {} from snapshot {}", self.config.symbol, snapshot.last_update_id);
+        
+        if self.order_book_manager.refresh_from_snapshot(&self.config.symbol, snapshot)? {
+            self.reset_calculator();
+            self.record_book_progress(now_millis()?);
+        }
+        
+        Ok(())
+    }
+    
+    /// Compare the book against a fresh snapshot, re-initializing it on divergence
+    ///
+    /// Only a snapshot at the book's own update ID is compared; any other
+    /// differs by the updates in between and is skipped.
+    async fn verify_order_book(&self) -> Result<()> {
+        let snapshot = self.connector
+            .fetch_snapshot(&self.config.symbol, self.config.order_book.snapshot_depth)
+            .await?;
+        let divergence = self.order_book_manager.verify_against_snapshot(&snapshot)?;
+        debug!("Order book divergence: {:?}", divergence);
+        
+        if !divergence.comparable() {
+            debug!(
+                "Skipping verification: snapshot {} is not at book update {}",
+                divergence.snapshot_update_id, divergence.local_update_id
+            );
+            return Ok(());
+        }
+        
+        if divergence.exceeds(
+            self.config.order_book.max_price_divergence,
+            self.config.order_book.max_quantity_divergence,
+        ) {
+            warn!(
+                "Order book for {} drifted from snapshot ({} missing levels, price Δ {:.8}, quantity Δ {:.1}%), resyncing",
+                self.config.symbol,
+                divergence.missing_levels,
+                divergence.max_price_divergence,
+                divergence.max_quantity_divergence * 100.0
+            );
+            self.order_book_manager
+                .initialize_from_snapshot(&self.config.symbol, snapshot)?;
+            self.reset_calculator();
+            self.record_book_progress(now_millis()?);
+        }
         
         Ok(())
     }
     
     /// Process incoming WebSocket message
     async fn process_message(&self, message: &str) -> Result<()> {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            metrics.messages_total.with_label_values(&[&self.config.symbol]).inc();
+        }
+        
         // Parse the JSON message
         let json_value: Value = serde_json::from_str(message)?;
         
-        // Check if it's a depth update
-        if json_value.get("e").and_then(|v| v.as_str()) == Some("depthUpdate") {
-            let update: OrderBookUpdate = serde_json::from_str(message)?;
+        let event_type = json_value.get("e").and_then(|v| v.as_str());
+        
+        // Subscription acknowledgements carry no market data
+        if json_value.get("result").is_some() {
+            debug!("Subscription acknowledged: {}", message);
+        } else if let Some(event_type) = event_type.filter(|e| NON_DEPTH_EVENTS.contains(e)) {
+            debug!("Ignoring {} event", event_type);
+        } else if event_type == Some("aggTrade") {
+            let trade: AggTrade = serde_json::from_str(message)?;
+            if trade.symbol.eq_ignore_ascii_case(&self.config.symbol) {
+                self.trade_flow.lock().unwrap().record(&trade);
+            } else {
+                debug!("Ignoring trade for untracked symbol {}", trade.symbol);
+            }
+        } else if let DepthStreamMode::Partial { .. } = self.config.websocket.depth_stream {
+            // Partial-book frame: replace the whole top of book
+            let partial = self.connector.parse_partial_depth_message(message)?;
+            self.order_book_manager
+                .replace_from_partial_depth(&self.config.symbol, partial)?;
+            let now_ms = now_millis()?;
+            self.record_book_progress(now_ms);
+            self.record_update(now_ms);
+            
+            self.calculate_and_display_fair_price().await?;
+        } else {
+            // Anything else is the exchange's incremental depth update
+            let update = self.connector.parse_depth_message(message)?;
+            
+            let now_ms = now_millis()?;
+            self.record_lag(update.event_time, now_ms);
             
-            // Verify symbol matches
-            if update.symbol != self.config.symbol {
-                warn!("Received update for wrong symbol: {}", update.symbol);
+            // Keepalive frames only advance the sequence; the feed is alive but nothing moved
+            if update.is_empty() {
+                self.order_book_manager.apply_update(update)?;
+                self.record_book_progress(now_ms);
                 return Ok(());
             }
             
             // Apply the update
-            self.order_book_manager.apply_update(update)?;
+            let change = self.order_book_manager.apply_update(update)?;
+            self.record_book_progress(now_ms);
+            self.record_update(now_ms);
+            
+            // Deep-level changes cannot move the fair price enough to matter
+            if !change.changed() {
+                debug!("No top-of-book change, skipping fair price calculation");
+                return Ok(());
+            }
             
             // Calculate and display fair price
             self.calculate_and_display_fair_price().await?;
@@ -189,132 +926,739 @@ impl WebSocketManager {
         Ok(())
     }
     
+    /// Fold one event's feed lag into the rolling average and return the new average
+    fn record_lag(&self, event_time_ms: u64, now_ms: u64) -> f64 {
+        // Clock skew can put the event slightly in the future; count that as no lag
+        let lag_ms = now_ms.saturating_sub(event_time_ms) as f64;
+        
+        let mut avg_lag_ms = self.avg_lag_ms.lock().unwrap();
+        let average = match *avg_lag_ms {
+            Some(average) => LAG_EMA_ALPHA * lag_ms + (1.0 - LAG_EMA_ALPHA) * average,
+            None => lag_ms,
+        };
+        *avg_lag_ms = Some(average);
+        
+        if lag_ms > 1000.0 {
+            debug!("Depth update lagging the feed by {}ms", lag_ms);
+        }
+        
+        average
+    }
+    
     /// Calculate fair price and display results
-    async fn calculate_and_display_fair_price(&self) -> Result<()> {
+    ///
+    /// Calculations are coalesced to at most one per `update_threshold_us`
+    /// window; returns `None` when deferred, and the connection loop runs the
+    /// deferred calculation once the window ends.
+    async fn calculate_and_display_fair_price(&self) -> Result<Option<FairPriceResult>> {
         if !self.order_book_manager.is_ready() {
-            return Ok(()); // Skip if order book not ready
+            return Ok(None); // Skip if order book not ready
         }
         
-        let order_book = match self.order_book_manager.get_order_book() {
-            Some(ob) => ob,
-            None => return Ok(()),
-        };
+        // Throttle bursts of rapid depth updates
+        let now_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)?
+            .as_micros() as u64;
+        let last_us = self.last_calculation_us.load(Ordering::Relaxed);
+        if last_us != 0 && now_us.saturating_sub(last_us) < self.config.order_book.update_threshold_us {
+            self.calculation_pending.store(true, Ordering::Relaxed);
+            return Ok(None);
+        }
+        self.calculation_pending.store(false, Ordering::Relaxed);
         
-        // We need to handle the Arc<FairPriceCalculator> properly
-        // Since it needs to be mutable, we'll create a temporary calculator
-        let mut temp_calculator = FairPriceCalculator::new(
-            self.fair_price_calculator.get_method().clone()
-        );
+        let trade_flow_imbalance = self.trade_flow_imbalance();
+        let mut calculator = self.fair_price_calculator.lock().unwrap();
+        calculator.set_trade_flow_imbalance(trade_flow_imbalance);
+        
+        // Calculate and display under the read lock to avoid cloning the book
+        let fair_price_result = self.order_book_manager.with_order_book(|order_book| {
+            let result = calculator.calculate(order_book)?;
+            self.display_results(&result, order_book);
+            Some(result)
+        });
         
-        let fair_price_result = match temp_calculator.calculate(&order_book) {
+        let fair_price_result = match fair_price_result.flatten() {
             Some(result) => result,
             None => {
                 warn!("Failed to calculate fair price");
-                return Ok(());
+                return Ok(None);
             }
         };
+        drop(calculator);
+        self.last_calculation_us.store(now_us, Ordering::Relaxed);
+        
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            metrics.observe(&self.config.symbol, &fair_price_result);
+        }
         
-        // Display the results
-        self.display_results(&fair_price_result, &order_book).await;
+        self.record_signal(&fair_price_result);
         
-        Ok(())
+        // No receivers just means no sinks are attached
+        let _ = self.result_sender.send(fair_price_result.clone());
+        
+        Ok(Some(fair_price_result))
     }
     
-    /// Display calculation results
-    async fn display_results(
-        &self,
-        result: &crate::fair_price::FairPriceResult,
-        order_book: &crate::order_book::OrderBook,
-    ) {
-        let best_bid = order_book.best_bid().map(|b| b.price.0).unwrap_or(0.0);
-        let best_ask = order_book.best_ask().map(|a| a.price.0).unwrap_or(0.0);
-        
-        // Create a formatted output
-        let output = format!(
-            "\n┌─ {} Fair Price Update ─────────────────────────────────┐\n\
-             │ Fair Price: ${:<15.4} Method: {:<20} │\n\
-             │ Mid Price:  ${:<15.4} Confidence: {:<17.1}% │\n\
-             │ Best Bid:   ${:<15.4} Best Ask: ${:<16.4} │\n\
-             │ Spread:     ${:<15.4} ({:<20.3}%) │\n\
-             │ Signal:     {:<35} │\n\
-             │ Volumes:    Bid: {:<8.2} Ask: {:<8.2} Total: {:<8.2} │\n\
-             │ Flow:       {:<35.2} │\n\
-             └─────────────────────────────────────────────────────────┘",
-            self.config.symbol,
-            result.fair_price,
-            result.calculation_method,
-            result.mid_price,
-            result.confidence * 100.0,
-            best_bid,
-            best_ask,
-            result.spread,
-            (result.spread / result.mid_price) * 100.0,
-            result.market_signal(),
-            result.metadata.bid_volume,
-            result.metadata.ask_volume,
-            result.metadata.total_volume,
-            result.metadata.order_flow_imbalance,
-        );
+    /// When a calculation deferred by the update threshold is due, if any
+    fn pending_calculation_deadline(&self) -> Option<Instant> {
+        if !self.calculation_pending.load(Ordering::Relaxed) {
+            return None;
+        }
+        let now_us = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_micros() as u64;
+        let elapsed_us = now_us.saturating_sub(self.last_calculation_us.load(Ordering::Relaxed));
+        let remaining_us = self.config.order_book.update_threshold_us.saturating_sub(elapsed_us);
+        Some(Instant::now() + Duration::from_micros(remaining_us))
+    }
+    
+    /// Track the debounced market signal and emit an event only when it changes
+    fn record_signal(&self, result: &FairPriceResult) -> Option<SignalChange> {
+        let mut debouncer = self.signal_debouncer.lock().unwrap();
+        let previous = debouncer.reported().cloned();
+        let current = debouncer.update(result.market_signal(), result.timestamp);
+        
+        let previous = match previous {
+            Some(previous) if previous != current => previous,
+            _ => return None, // First observation or unchanged
+        };
         
-        info!("{}", output);
+        let change = SignalChange {
+            symbol: self.config.symbol.clone(),
+            previous,
+            current,
+            fair_price: result.fair_price,
+            timestamp: result.timestamp,
+        };
         
-        // Log additional debug information
-        debug!(
-            "Order book stats - Bids: {}, Asks: {}, Last update: {}",
-            order_book.bids.len(),
-            order_book.asks.len(),
-            order_book.last_update
-        );
+        info!("📣 {}", change);
+        
+        // No receivers is fine; the log line above still records the change
+        let _ = self.signal_sender.send(change.clone());
+        
+        Some(change)
     }
     
-    /// Health check for WebSocket connection
-    pub async fn health_check(&self) -> Result<bool> {
-        // Try to get server time from Binance API
-        match timeout(
-            Duration::from_secs(5),
-            self.binance_client.get_server_time()
-        ).await {
-            Ok(Ok(_)) => Ok(true),
-            Ok(Err(e)) => {
-                warn!("Health check failed: {}", e);
-                Ok(false)
-            }
-            Err(_) => {
-                warn!("Health check timeout");
-                Ok(false)
+    /// Frame for the next heartbeat tick; falls back to a ping when the
+    /// connector has no application-level keepalive
+    fn keepalive_message(&self) -> Message {
+        let websocket = &self.config.websocket;
+        if websocket.keepalive == KeepaliveMode::ListSubscriptions {
+            let id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+            if let Some(frame) = self.connector.keepalive_frame(id) {
+                return Message::Text(frame);
             }
         }
+        Message::Ping(websocket.ping_payload.as_bytes().to_vec())
     }
     
-    /// Get connection statistics
-    pub fn get_stats(&self) -> ConnectionStats {
-        ConnectionStats {
-            is_order_book_ready: self.order_book_manager.is_ready(),
-            current_spread: self.order_book_manager.get_spread(),
-            current_mid_price: self.order_book_manager.get_mid_price(),
-            symbol: self.config.symbol.clone(),
+    /// Count an applied depth update for the message rate
+    fn record_update(&self, now_ms: u64) {
+        self.messages_processed.fetch_add(1, Ordering::Relaxed);
+        
+        let mut update_times = self.update_times.lock().unwrap();
+        update_times.push_back(now_ms);
+        while update_times
+            .front()
+            .is_some_and(|&time| now_ms.saturating_sub(time) >= UPDATE_RATE_WINDOW_MS)
+        {
+            update_times.pop_front();
         }
     }
-}
-
-/// Connection statistics
-#[derive(Debug, Clone)]
-pub struct ConnectionStats {
-    pub is_order_book_ready: bool,
-    pub current_spread: Option<f64>,
-    pub current_mid_price: Option<f64>,
+    
+    /// Depth updates per second over the rolling window ending at `now_ms`
+    fn updates_per_sec(&self, now_ms: u64) -> f64 {
+        let update_times = self.update_times.lock().unwrap();
+        let recent = update_times
+            .iter()
+            .filter(|&&time| now_ms.saturating_sub(time) < UPDATE_RATE_WINDOW_MS)
+            .count();
+        recent as f64 * 1000.0 / UPDATE_RATE_WINDOW_MS as f64
+    }
+    
+    /// Remember when the order book's update ID last advanced
+    fn record_book_progress(&self, now_ms: u64) {
+        let Some(update_id) = self.order_book_manager.with_order_book(|ob| ob.last_update) else {
+            return;
+        };
+        
+        let mut progress = self.book_progress.lock().unwrap();
+        if progress.map(|(last_id, _)| last_id) != Some(update_id) {
+            *progress = Some((update_id, now_ms));
+        }
+    }
+    
+    /// Move to a new connection state, notifying subscribers on change
+    fn set_state(&self, next: ConnectionState) {
+        let mut state = self.state.lock().unwrap();
+        if *state == next {
+            return;
+        }
+        
+        debug!("Connection state for {}: {} → {}", self.config.symbol, *state, next);
+        *state = next;
+        if next == ConnectionState::Live {
+            *self.live_since.lock().unwrap() = Some(Instant::now());
+        }
+        
+        drop(state);
+        
+        // Feeds share this connection, so they share its state
+        for feed in self.feeds() {
+            feed.set_state(next);
+        }
+        
+        // No receivers is fine; nobody is supervising
+        let _ = self.state_sender.send(next);
+    }
+    
+    /// Get the current connection state
+    pub fn connection_state(&self) -> ConnectionState {
+        *self.state.lock().unwrap()
+    }
+    
+    /// Subscribe to connection state transitions
+    pub fn subscribe_state(&self) -> broadcast::Receiver<ConnectionState> {
+        self.state_sender.subscribe()
+    }
+    
+    /// Subscribe to market signal transitions
+    pub fn subscribe_signals(&self) -> broadcast::Receiver<SignalChange> {
+        self.signal_sender.subscribe()
+    }
+    
+    /// Subscribe to every calculated fair price result
+    pub fn subscribe_results(&self) -> broadcast::Receiver<FairPriceResult> {
+        self.result_sender.subscribe()
+    }
+    
+    /// Get the symbol this manager streams
+    pub fn symbol(&self) -> &str {
+        &self.config.symbol
+    }
+    
+    /// Display calculation results in the configured display mode
+    fn display_results(
+        &self,
+        result: &FairPriceResult,
+        order_book: &OrderBook,
+    ) {
+        let Some(output) = self.format_results(result, order_book) else {
+            return;
+        };
+        
+        // The box is too noisy for the default level; compact modes are opt-in
+        match self.config.display_mode {
+            DisplayMode::Box => debug!("{}", output),
+            _ => info!("{}", output),
+        }
+        
+        debug!("Order book summary: {:.*}", self.display_precision(), order_book.summary());
+    }
+    
+    /// Decimal places used for logged prices
+    fn display_precision(&self) -> usize {
+        self.config.display_precision.unwrap_or(DEFAULT_DISPLAY_PRECISION)
+    }
+    
+    /// Render a result for the configured display mode, or `None` when silent
+    fn format_results(
+        &self,
+        result: &FairPriceResult,
+        order_book: &OrderBook,
+    ) -> Option<String> {
+        let summary = order_book.summary();
+        let best_bid = summary.best_bid.unwrap_or(0.0);
+        let best_ask = summary.best_ask.unwrap_or(0.0);
+        let precision = self.display_precision();
+        
+        let output = match self.config.display_mode {
+            DisplayMode::Box => format!(
+                "\n┌─ {} Fair Price Update ─────────────────────────────────┐\n\
+                 │ Fair Price: ${:<15.precision$} Method: {:<20} │\n\
+                 │ Mid Price:  ${:<15.precision$} Confidence: {:<17.1}% │\n\
+                 │ Best Bid:   ${:<15.precision$} Best Ask: ${:<16.precision$} │\n\
+                 │ Spread:     ${:<15.precision$} ({:<20.3}%) │\n\
+                 │ Signal:     {:<35} │\n\
+                 │ Volumes:    Bid: {:<8.2} Ask: {:<8.2} Total: {:<8.2} │\n\
+                 │ Flow:       {:<35.2} │\n\
+                 └─────────────────────────────────────────────────────────┘",
+                self.config.symbol,
+                result.fair_price,
+                result.calculation_method,
+                result.mid_price,
+                result.confidence * 100.0,
+                best_bid,
+                best_ask,
+                result.spread,
+                ratio_to_mid(result.spread, result.mid_price) * 100.0,
+                result.market_signal(),
+                result.metadata.bid_volume,
+                result.metadata.ask_volume,
+                result.metadata.total_volume,
+                result.metadata.order_flow_imbalance,
+            ),
+            DisplayMode::OneLine => format!(
+                "{} fair=${:.precision$} mid=${:.precision$} bid=${:.precision$} ask=${:.precision$} spread={:.2}bps conf={:.1}% flow={:.2} signal={}",
+                self.config.symbol,
+                result.fair_price,
+                result.mid_price,
+                best_bid,
+                best_ask,
+                result.spread_bps,
+                result.confidence * 100.0,
+                result.signal_imbalance(),
+                result.market_signal(),
+            ),
+            DisplayMode::Json => serde_json::json!({
+                "symbol": self.config.symbol,
+                "timestamp": result.timestamp,
+                "method": result.calculation_method,
+                "fair_price": result.fair_price,
+                "mid_price": result.mid_price,
+                "best_bid": best_bid,
+                "best_ask": best_ask,
+                "spread": result.spread,
+                "spread_bps": result.spread_bps,
+                "confidence": result.confidence,
+                "imbalance": result.signal_imbalance(),
+                "signal": result.market_signal().to_string(),
+            })
+            .to_string(),
+            DisplayMode::Silent => return None,
+        };
+        
+        Some(output)
+    }
+    
+    /// Get realized trade flow imbalance over the rolling window
+    pub fn trade_flow_imbalance(&self) -> Option<f64> {
+        self.trade_flow.lock().unwrap().imbalance()
+    }
+    
+    /// Get net signed trade volume (buy minus sell) over the rolling window
+    pub fn trade_flow_signed_volume(&self) -> f64 {
+        self.trade_flow.lock().unwrap().signed_volume()
+    }
+    
+    /// Get the change in book order flow imbalance over recent calculations
+    pub fn imbalance_trend(&self) -> Option<f64> {
+        self.with_calculator(FairPriceCalculator::imbalance_trend)
+    }
+    
+    /// Read the live calculator (price history, EMA, volatility, imbalance series)
+    pub fn with_calculator<R>(&self, f: impl FnOnce(&FairPriceCalculator) -> R) -> R {
+        f(&self.fair_price_calculator.lock().unwrap())
+    }
+    
+    /// Health check for the API and the order book feed
+    pub async fn health_check(&self) -> Result<bool> {
+        let status = self.health_status().await?;
+        if !status.is_healthy() {
+            warn!("Health check for {}: {}", self.config.symbol, status);
+        }
+        Ok(status.is_healthy())
+    }
+    
+    /// Check API reachability and order book freshness
+    pub async fn health_status(&self) -> Result<HealthStatus> {
+        // Try a cheap request against the exchange API
+        let api_reachable = match timeout(
+            Duration::from_secs(5),
+            self.connector.ping()
+        ).await {
+            Ok(Ok(_)) => true,
+            Ok(Err(e)) => {
+                warn!("Health check failed: {}", e);
+                false
+            }
+            Err(_) => {
+                warn!("Health check timeout");
+                false
+            }
+        };
+        
+        Ok(self.health_status_at(api_reachable, now_millis()?))
+    }
+    
+    /// Build a health status for a given API result and clock reading (milliseconds)
+    pub fn health_status_at(&self, api_reachable: bool, now_ms: u64) -> HealthStatus {
+        let feed_age_ms = self.book_progress
+            .lock()
+            .unwrap()
+            .map(|(_, advanced_ms)| now_ms.saturating_sub(advanced_ms));
+        
+        HealthStatus {
+            api_reachable,
+            book_ready: self.order_book_manager.is_ready(),
+            feed_age_ms,
+            feed_fresh: feed_age_ms.is_some_and(|age| age <= self.config.websocket.max_feed_staleness_ms),
+        }
+    }
+    
+    /// Get connection statistics
+    pub fn get_stats(&self) -> ConnectionStats {
+        // Read spread and mid together so the bps figure matches them
+        let (current_spread, current_mid_price) = self.order_book_manager
+            .with_order_book(|ob| (ob.spread(), ob.mid_price()))
+            .unwrap_or((None, None));
+        let (buffered_messages, dropped_messages) = {
+            let inbound = self.inbound.lock().unwrap();
+            (inbound.len(), inbound.dropped())
+        };
+        
+        ConnectionStats {
+            is_order_book_ready: self.order_book_manager.is_ready(),
+            current_spread,
+            current_mid_price,
+            current_spread_bps: spread_bps(current_spread, current_mid_price),
+            avg_lag_ms: *self.avg_lag_ms.lock().unwrap(),
+            messages_processed: self.messages_processed.load(Ordering::Relaxed),
+            updates_per_sec: self.updates_per_sec(now_millis().unwrap_or(0)),
+            buffered_messages,
+            dropped_messages,
+            state: self.connection_state(),
+            symbol: self.config.symbol.clone(),
+        }
+    }
+}
+
+/// Current wall-clock time in milliseconds since the Unix epoch
+fn now_millis() -> Result<u64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64)
+}
+
+/// Spread relative to mid in basis points, if both are known and the mid is positive
+fn spread_bps(spread: Option<f64>, mid_price: Option<f64>) -> Option<f64> {
+    match (spread, mid_price) {
+        (Some(spread), Some(mid)) if mid > 0.0 => Some(spread / mid * 10_000.0),
+        _ => None,
+    }
+}
+
+/// A frame as read from the socket; `None` marks the end of the stream
+type InboundFrame = Option<Result<Message, tungstenite::Error>>;
+
+/// Bounded queue between the socket reader task and message processing
+///
+/// When full, depth frames make room first so trades, subscription acks and
+/// control frames survive. Partial-depth frames each carry the whole book, so
+/// the oldest is simply superseded. Diff frames cannot be skipped without a
+/// gap, so all queued diffs are flushed at once and a single resync is
+/// requested instead of dropping one diff per frame. Only when no depth frame
+/// is queued does the oldest other frame go; the end marker is always kept.
+struct InboundBuffer {
+    frames: VecDeque<InboundFrame>,
+    capacity: usize,
+    depth_stream: DepthStreamMode,
+    dropped: u64,
+    resync_pending: bool,
+}
+
+impl InboundBuffer {
+    fn new(capacity: usize, depth_stream: DepthStreamMode) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            capacity: capacity.max(1),
+            depth_stream,
+            dropped: 0,
+            resync_pending: false,
+        }
+    }
+    
+    /// Queue a frame, returning how many frames were dropped to make room
+    fn push(&mut self, frame: InboundFrame) -> usize {
+        let mut dropped = 0;
+        if self.frames.len() >= self.capacity {
+            dropped = self.make_room();
+            if dropped == 0 {
+                // Nothing queued can go, so the new frame does
+                self.dropped += 1;
+                return 1;
+            }
+            self.dropped += dropped as u64;
+        }
+        self.frames.push_back(frame);
+        dropped
+    }
+    
+    /// Drop queued frames by the overflow rules, returning how many went
+    fn make_room(&mut self) -> usize {
+        let is_depth = |frame: &InboundFrame| {
+            matches!(frame, Some(Ok(Message::Text(text))) if is_depth_frame(text))
+        };
+        
+        if self.frames.iter().any(is_depth) {
+            return match self.depth_stream {
+                DepthStreamMode::Partial { .. } => {
+                    let oldest = self.frames.iter().position(is_depth).unwrap();
+                    self.frames.remove(oldest);
+                    1
+                }
+                DepthStreamMode::Diff => {
+                    let before = self.frames.len();
+                    self.frames.retain(|frame| !is_depth(frame));
+                    self.resync_pending = true;
+                    before - self.frames.len()
+                }
+            };
+        }
+        
+        let oldest = self.frames.iter().position(|frame| !is_stream_end(frame));
+        match oldest {
+            Some(index) => {
+                self.frames.remove(index);
+                1
+            }
+            None => 0,
+        }
+    }
+    
+    /// Take the oldest frame, and whether the book must be resynced before it
+    /// is processed because queued diffs were flushed
+    fn pop(&mut self) -> Option<(InboundFrame, bool)> {
+        let frame = self.frames.pop_front()?;
+        Some((frame, std::mem::take(&mut self.resync_pending)))
+    }
+    
+    /// Discard frames left over from a previous connection, keeping the drop count
+    fn clear(&mut self) {
+        self.frames.clear();
+        self.resync_pending = false;
+    }
+    
+    fn len(&self) -> usize {
+        self.frames.len()
+    }
+    
+    fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+/// Whether a frame ends the stream: a Close frame, a read error or the end marker
+fn is_stream_end(frame: &InboundFrame) -> bool {
+    matches!(frame, None | Some(Err(_)) | Some(Ok(Message::Close(_))))
+}
+
+/// Whether a text frame carries depth rather than trades, other events or a subscription ack
+///
+/// Combined-stream frames are judged by their payload.
+fn is_depth_frame(text: &str) -> bool {
+    let Ok(mut json) = serde_json::from_str::<Value>(text) else {
+        return false;
+    };
+    if json.get("stream").is_some() {
+        json = json["data"].take();
+    }
+    if json.get("result").is_some() {
+        return false;
+    }
+    match json.get("e").and_then(|v| v.as_str()) {
+        Some(event_type) => event_type != "aggTrade" && !NON_DEPTH_EVENTS.contains(&event_type),
+        None => true,
+    }
+}
+
+/// Aborts the wrapped task when dropped, so the reader ends with its connection
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// How a connection ended without an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectionEnd {
+    /// Shutdown was requested locally
+    Shutdown,
+    /// The server sent a Close frame
+    ClosedByServer,
+}
+
+/// Attempt count after a connection drops, by error or by a clean server close
+///
+/// A connection that stayed live for `stable_after` proves the earlier
+/// drops were transient, so counting starts over at this one.
+fn attempts_after_drop(attempts: u32, live_for: Option<Duration>, stable_after: Duration) -> u32 {
+    match live_for {
+        Some(live_for) if live_for >= stable_after => 1,
+        _ => attempts + 1,
+    }
+}
+
+/// Clean-close count after the server closes a connection, or `None` once
+/// `max_attempts` closes in a row came before the connection was stable
+fn clean_closes_after(clean_closes: u32, live_for: Option<Duration>, stable_after: Duration, max_attempts: u32) -> Option<u32> {
+    Some(attempts_after_drop(clean_closes, live_for, stable_after)).filter(|&closes| closes < max_attempts)
+}
+
+/// Whether a cleanly ended connection should be reopened
+fn reconnects_after(end: ConnectionEnd, reconnect_on_clean_close: bool) -> bool {
+    end == ConnectionEnd::ClosedByServer && reconnect_on_clean_close
+}
+
+/// Check whether the connection has been silent for longer than `read_timeout`
+fn read_timed_out(last_traffic: Instant, now: Instant, read_timeout: Duration) -> bool {
+    now.saturating_duration_since(last_traffic) >= read_timeout
+}
+
+/// When the next run of an optional periodic task is due
+fn next_deadline(last_run: Instant, interval: Option<Duration>) -> Option<Instant> {
+    interval.map(|interval| last_run + interval)
+}
+
+/// Sleep until `deadline`, or forever when there is none
+async fn sleep_until_or_pending(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Live subscription change for a running connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Start streaming a symbol
+    Subscribe(String),
+    /// Stop streaming a symbol and drop its book
+    Unsubscribe(String),
+}
+
+/// WebSocket connection lifecycle state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Opening the WebSocket
+    Connecting,
+    /// Fetching the REST depth snapshot
+    Snapshotting,
+    /// Streaming updates
+    Live,
+    /// Waiting to retry after a failure
+    Reconnecting,
+    /// Not running
+    Closed,
+}
+
+impl std::fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionState::Connecting => write!(f, "Connecting"),
+            ConnectionState::Snapshotting => write!(f, "Snapshotting"),
+            ConnectionState::Live => write!(f, "Live"),
+            ConnectionState::Reconnecting => write!(f, "Reconnecting"),
+            ConnectionState::Closed => write!(f, "Closed"),
+        }
+    }
+}
+
+/// API and order book feed health
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthStatus {
+    /// The REST API answered a server time request
+    pub api_reachable: bool,
+    /// The order book has both sides and is not crossed
+    pub book_ready: bool,
+    /// Time since the order book last advanced (milliseconds), if it ever has
+    pub feed_age_ms: Option<u64>,
+    /// The order book advanced within the configured staleness limit
+    pub feed_fresh: bool,
+}
+
+impl HealthStatus {
+    /// The order book feed is ready and fresh
+    pub fn is_feed_healthy(&self) -> bool {
+        self.book_ready && self.feed_fresh
+    }
+    
+    /// Both the API and the order book feed are healthy
+    pub fn is_healthy(&self) -> bool {
+        self.api_reachable && self.is_feed_healthy()
+    }
+}
+
+impl std::fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let feed = if !self.book_ready {
+            "feed not ready"
+        } else if !self.feed_fresh {
+            "feed stale"
+        } else {
+            "feed healthy"
+        };
+        let api = if self.api_reachable { "API reachable" } else { "API unreachable" };
+        
+        write!(f, "{}, {}", api, feed)?;
+        if let Some(age_ms) = self.feed_age_ms {
+            write!(f, " (last update {}ms ago)", age_ms)?;
+        }
+        Ok(())
+    }
+}
+
+/// Market signal transition event
+#[derive(Debug, Clone)]
+pub struct SignalChange {
+    pub symbol: String,
+    pub previous: MarketSignal,
+    pub current: MarketSignal,
+    pub fair_price: f64,
+    pub timestamp: u64,
+}
+
+impl std::fmt::Display for SignalChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} signal changed: {} → {} (Fair Price: ${:.4})",
+            self.symbol, self.previous, self.current, self.fair_price
+        )
+    }
+}
+
+/// Connection statistics
+#[derive(Debug, Clone)]
+pub struct ConnectionStats {
+    pub is_order_book_ready: bool,
+    pub current_spread: Option<f64>,
+    pub current_mid_price: Option<f64>,
+    /// Spread relative to mid in basis points
+    pub current_spread_bps: Option<f64>,
+    /// Rolling average of receive time minus exchange event time (milliseconds)
+    pub avg_lag_ms: Option<f64>,
+    /// Depth updates applied since start
+    pub messages_processed: u64,
+    /// Depth updates per second over the last 10 seconds
+    pub updates_per_sec: f64,
+    /// Frames read from the socket but not yet processed
+    pub buffered_messages: usize,
+    /// Frames dropped or flushed because the buffer was full, since start
+    pub dropped_messages: u64,
+    pub state: ConnectionState,
     pub symbol: String,
 }
 
 impl std::fmt::Display for ConnectionStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spread_bps = match self.current_spread_bps {
+            Some(bps) => format!("{:.2}bps", bps),
+            None => "n/a".to_string(),
+        };
         write!(
             f,
-            "Stats for {}: Ready={}, Mid=${:.4}, Spread=${:.4}",
+            "Stats for {}: State={}, Ready={}, Mid=${:.4}, Spread=${:.4} ({}), Lag={:.1}ms, Updates={} ({:.1}/s), Buffered={} (dropped {})",
             self.symbol,
+            self.state,
             self.is_order_book_ready,
             self.current_mid_price.unwrap_or(0.0),
-            self.current_spread.unwrap_or(0.0)
+            self.current_spread.unwrap_or(0.0),
+            spread_bps,
+            self.avg_lag_ms.unwrap_or(0.0),
+            self.messages_processed,
+            self.updates_per_sec,
+            self.buffered_messages,
+            self.dropped_messages
         )
     }
 }
@@ -323,12 +1667,576 @@ impl std::fmt::Display for ConnectionStats {
 mod tests {
     use super::*;
     use crate::config::FairPriceMethod;
+    use crate::order_book::{OrderBookLevel, OrderBookSnapshot, OrderBookUpdate, PriceTicks};
+    
+    /// One lot each at 50000/50001 as of `last_update_id`
+    fn snapshot_at(last_update_id: u64) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            last_update_id,
+            bids: vec![["50000.0".to_string(), "1.0".to_string()]],
+            asks: vec![["50001.0".to_string(), "1.0".to_string()]],
+        }
+    }
+    
+    /// Test manager over a BTCUSDT book loaded from `snapshot_at(1)` unless overridden
+    struct TestManager {
+        config: Config,
+        calculator: FairPriceCalculator,
+        book: Option<OrderBookSnapshot>,
+    }
+    
+    impl Default for TestManager {
+        fn default() -> Self {
+            Self {
+                config: Config::new("BTCUSDT".to_string(), "mid-price".to_string()),
+                calculator: FairPriceCalculator::new(FairPriceMethod::MidPrice),
+                book: Some(snapshot_at(1)),
+            }
+        }
+    }
+    
+    impl TestManager {
+        /// Configure and key the book by `symbol` exactly as given
+        fn symbol(mut self, symbol: &str) -> Self {
+            self.config.symbol = symbol.to_string();
+            self
+        }
+        
+        fn update_threshold_us(mut self, update_threshold_us: u64) -> Self {
+            self.config.order_book.update_threshold_us = update_threshold_us;
+            self
+        }
+        
+        /// Price with `calculator`, e.g. one smoothing with an EMA
+        fn calculator(mut self, calculator: FairPriceCalculator) -> Self {
+            self.calculator = calculator;
+            self
+        }
+        
+        /// Load `book` instead of `snapshot_at(1)`; `None` leaves the book empty
+        fn book(mut self, book: Option<OrderBookSnapshot>) -> Self {
+            self.book = book;
+            self
+        }
+        
+        fn configure(mut self, configure: impl FnOnce(&mut Config)) -> Self {
+            configure(&mut self.config);
+            self
+        }
+        
+        fn build(self) -> (WebSocketManager, Arc<OrderBookManager>) {
+            let config = self.config.clone();
+            self.build_with(BinanceClient::from_config(&config))
+        }
+        
+        fn build_with<C: ExchangeConnector>(self, connector: C) -> (WebSocketManager<C>, Arc<OrderBookManager>) {
+            let order_book_manager = Arc::new(OrderBookManager::new());
+            if let Some(book) = self.book {
+                order_book_manager.initialize_from_snapshot(&self.config.symbol, book).unwrap();
+            }
+            let ws_manager = WebSocketManager::with_connector(
+                self.config,
+                order_book_manager.clone(),
+                self.calculator,
+                connector,
+            );
+            (ws_manager, order_book_manager)
+        }
+    }
     
     #[tokio::test]
     async fn test_websocket_manager_creation() {
+        let (ws_manager, _) = TestManager::default().book(None).build();
+        
+        let stats = ws_manager.get_stats();
+        assert_eq!(stats.symbol, "BTCUSDT");
+        assert!(!stats.is_order_book_ready);
+        assert_eq!(stats.current_spread_bps, None);
+        assert!(stats.to_string().contains("Spread=$0.0000 (n/a)"));
+    }
+    
+    #[test]
+    fn test_spread_bps_in_stats() {
+        let (ws_manager, _) = TestManager::default()
+            .book(Some(OrderBookSnapshot {
+                last_update_id: 1,
+                bids: vec![["49995.0".to_string(), "1.0".to_string()]],
+                asks: vec![["50005.0".to_string(), "1.0".to_string()]],
+            }))
+            .build();
+        
+        // $10 spread on a $50,000 mid is 2bps
+        let stats = ws_manager.get_stats();
+        assert_eq!(stats.current_mid_price, Some(50000.0));
+        assert!((stats.current_spread_bps.unwrap() - 2.0).abs() < 1e-9);
+        assert!(stats.to_string().contains("Spread=$10.0000 (2.00bps)"));
+    }
+    
+    #[tokio::test]
+    async fn test_testnet_config_is_threaded_to_client() {
+        let config = Config::default().with_testnet();
+        let order_book_manager = Arc::new(OrderBookManager::new());
+        let fair_price_calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        
+        let ws_manager = WebSocketManager::new(
+            config,
+            order_book_manager,
+            fair_price_calculator,
+        );
+        
+        assert_eq!(ws_manager.connector.base_url(), "https://testnet.binance.vision");
+        assert_eq!(
+            ws_manager.connector.diff_stream_url("BTCUSDT"),
+            "wss://testnet.binance.vision/ws/btcusdt@depth@100ms"
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_update_threshold_defers_calculations() {
+        let (ws_manager, _) = TestManager::default().update_threshold_us(50_000).build();
+        
+        let mut results = ws_manager.subscribe_results();
+        assert!(ws_manager.calculate_and_display_fair_price().await.unwrap().is_some());
+        assert_eq!(results.try_recv().unwrap().fair_price, 50000.5);
+        assert_eq!(ws_manager.pending_calculation_deadline(), None);
+        
+        // A burst inside the window is deferred rather than dropped
+        ws_manager
+            .process_message(r#"{"e":"depthUpdate","E":0,"s":"BTCUSDT","U":2,"u":2,"b":[["50000.4","1.0"]],"a":[]}"#)
+            .await
+            .unwrap();
+        ws_manager
+            .process_message(r#"{"e":"depthUpdate","E":0,"s":"BTCUSDT","U":3,"u":3,"b":[["50000.5","1.0"]],"a":[]}"#)
+            .await
+            .unwrap();
+        assert!(results.try_recv().is_err());
+        let deadline = ws_manager.pending_calculation_deadline().unwrap();
+        assert!(deadline <= Instant::now() + Duration::from_millis(50));
+        
+        // Once the window ends the burst yields one result for its final state
+        sleep_until(deadline).await;
+        let deferred = ws_manager.calculate_and_display_fair_price().await.unwrap().unwrap();
+        assert_eq!(deferred.fair_price, 50000.75);
+        assert_eq!(results.try_recv().unwrap().fair_price, 50000.75);
+        assert!(results.try_recv().is_err());
+        assert_eq!(ws_manager.pending_calculation_deadline(), None);
+    }
+    
+    #[tokio::test]
+    async fn test_volatility_lowers_live_confidence() {
+        let mut calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        calculator.set_volatility_sensitivity(0.1);
+        let (ws_manager, _) = TestManager::default().update_threshold_us(0).calculator(calculator).build();
+        
+        let calm = ws_manager.calculate_and_display_fair_price().await.unwrap().unwrap();
+        
+        // Swing the mid by 100 and back a few times, ending on the original book
+        let mut last = None;
+        for round in 0..3u64 {
+            let up = format!(
+                r#"{{"e":"depthUpdate","E":0,"s":"BTCUSDT","U":{id},"u":{id},"b":[["50100.0","1.0"],["50000.0","0"]],"a":[["50101.0","1.0"],["50001.0","0"]]}}"#,
+                id = 2 + round * 2,
+            );
+            let down = format!(
+                r#"{{"e":"depthUpdate","E":0,"s":"BTCUSDT","U":{id},"u":{id},"b":[["50000.0","1.0"],["50100.0","0"]],"a":[["50001.0","1.0"],["50101.0","0"]]}}"#,
+                id = 3 + round * 2,
+            );
+            ws_manager.process_message(&up).await.unwrap();
+            ws_manager.process_message(&down).await.unwrap();
+            last = ws_manager.calculate_and_display_fair_price().await.unwrap();
+        }
+        
+        let volatile = last.unwrap();
+        assert_eq!(volatile.mid_price, calm.mid_price);
+        assert!(volatile.confidence < calm.confidence);
+        
+        // The live history is readable through the manager
+        assert_eq!(ws_manager.with_calculator(|calculator| calculator.history_len()), 10);
+        assert!(ws_manager.with_calculator(|calculator| calculator.get_price_volatility(10)).unwrap() > 0.0);
+    }
+    
+    #[tokio::test]
+    async fn test_live_results_are_smoothed() {
+        let (ws_manager, _) = TestManager::default()
+            .update_threshold_us(0)
+            .calculator(FairPriceCalculator::with_ema(FairPriceMethod::MidPrice, 0.5))
+            .build();
+        let mut results = ws_manager.subscribe_results();
+        
+        let first = ws_manager.calculate_and_display_fair_price().await.unwrap().unwrap();
+        assert_eq!(first.smoothed_price, Some(50000.5));
+        results.try_recv().unwrap();
+        
+        // A step up of 100 is only half absorbed by the next tick
+        ws_manager
+            .process_message(r#"{"e":"depthUpdate","E":0,"s":"BTCUSDT","U":2,"u":2,"b":[["50100.0","1.0"],["50000.0","0"]],"a":[["50101.0","1.0"],["50001.0","0"]]}"#)
+            .await
+            .unwrap();
+        let second = results.try_recv().unwrap();
+        assert_eq!(second.fair_price, 50100.5);
+        assert_eq!(second.smoothed_price, Some(50050.5));
+    }
+    
+    #[tokio::test]
+    async fn test_live_imbalance_trend() {
+        let (ws_manager, _) = TestManager::default().update_threshold_us(0).build();
+        assert_eq!(ws_manager.imbalance_trend(), None);
+        
+        // Bids building up across ticks show as a rising imbalance
+        for (id, quantity) in [(2, "2.0"), (3, "3.0"), (4, "4.0")] {
+            let message = format!(
+                r#"{{"e":"depthUpdate","E":0,"s":"BTCUSDT","U":{id},"u":{id},"b":[["50000.0","{quantity}"]],"a":[]}}"#,
+            );
+            ws_manager.process_message(&message).await.unwrap();
+        }
+        assert!(ws_manager.imbalance_trend().unwrap() > 0.0);
+    }
+    
+    fn result_with(imbalance: f64, confidence: f64) -> FairPriceResult {
+        let mut result = crate::fair_price::test_result(100.0);
+        result.confidence = confidence;
+        result.metadata.order_flow_imbalance = imbalance;
+        result.metadata.notional_imbalance = imbalance;
+        result
+    }
+    
+    #[tokio::test]
+    async fn test_signal_transitions() {
+        let config = Config::new("BTCUSDT".to_string(), "mid-price".to_string());
+        let order_book_manager = Arc::new(OrderBookManager::new());
+        let fair_price_calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        
+        let ws_manager = WebSocketManager::new(
+            config,
+            order_book_manager,
+            fair_price_calculator,
+        );
+        let mut signals = ws_manager.subscribe_signals();
+        
+        let sequence = [
+            (0.0, 0.9),  // Balanced (seeds the state)
+            (0.1, 0.9),  // Balanced
+            (0.5, 0.9),  // BuyPressure
+            (0.6, 0.9),  // BuyPressure
+            (-0.5, 0.9), // SellPressure
+            (-0.5, 0.2), // Neutral
+            (-0.5, 0.2), // Neutral
+        ];
+        
+        let transitions = sequence
+            .iter()
+            .filter_map(|(imbalance, confidence)| {
+                ws_manager.record_signal(&result_with(*imbalance, *confidence))
+            })
+            .count();
+        assert_eq!(transitions, 3);
+        
+        let first = signals.try_recv().unwrap();
+        assert_eq!(first.previous, MarketSignal::Balanced);
+        assert_eq!(first.current, MarketSignal::BuyPressure);
+        
+        // Debounced, the single SellPressure tick never surfaces
+        let debounced = WebSocketManager::new(
+            Config::new("BTCUSDT".to_string(), "mid-price".to_string()),
+            Arc::new(OrderBookManager::new()),
+            FairPriceCalculator::new(FairPriceMethod::MidPrice),
+        )
+        .with_signal_debouncer(SignalDebouncer::new(2));
+        let currents: Vec<MarketSignal> = sequence
+            .iter()
+            .filter_map(|(imbalance, confidence)| {
+                debounced.record_signal(&result_with(*imbalance, *confidence))
+            })
+            .map(|change| change.current)
+            .collect();
+        assert_eq!(currents, vec![MarketSignal::BuyPressure, MarketSignal::Neutral]);
+    }
+    
+    #[test]
+    fn test_display_modes() {
+        let manager_with = |mode: DisplayMode| {
+            WebSocketManager::new(
+                Config::builder().display_mode(mode).build().unwrap(),
+                Arc::new(OrderBookManager::new()),
+                FairPriceCalculator::new(FairPriceMethod::MidPrice),
+            )
+        };
+        let result = result_with(0.3, 0.9);
+        let order_book = OrderBook::new("BTCUSDT".to_string());
+        
+        let boxed = manager_with(DisplayMode::default()).format_results(&result, &order_book).unwrap();
+        assert!(boxed.lines().count() > 1);
+        
+        let one_line = manager_with(DisplayMode::OneLine).format_results(&result, &order_book).unwrap();
+        assert!(!one_line.contains('\n'));
+        assert!(one_line.starts_with("BTCUSDT fair=$100.0000"));
+        
+        let json = manager_with(DisplayMode::Json).format_results(&result, &order_book).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["symbol"], "BTCUSDT");
+        assert_eq!(value["fair_price"], 100.0);
+        
+        assert_eq!(manager_with(DisplayMode::Silent).format_results(&result, &order_book), None);
+        
+        // A zero mid must not print inf/NaN percentages
+        let mut degenerate = result_with(0.0, 0.5);
+        degenerate.mid_price = 0.0;
+        let boxed = manager_with(DisplayMode::Box).format_results(&degenerate, &order_book).unwrap();
+        assert!(!boxed.contains("inf") && !boxed.contains("NaN"));
+    }
+    
+    #[test]
+    fn test_display_precision() {
+        let manager = WebSocketManager::new(
+            Config::builder().symbol("SHIBUSDT").display_mode(DisplayMode::OneLine).display_precision(8).build().unwrap(),
+            Arc::new(OrderBookManager::new()),
+            FairPriceCalculator::new(FairPriceMethod::MidPrice),
+        );
+        let mut order_book = OrderBook::with_tick_size("SHIBUSDT".to_string(), 0.00000001);
+        order_book.bids.insert(PriceTicks(1234), OrderBookLevel::new(0.00001234, 1e6));
+        order_book.asks.insert(PriceTicks(1236), OrderBookLevel::new(0.00001236, 1e6));
+        let mut result = result_with(0.0, 0.9);
+        result.fair_price = 0.00001235;
+        result.mid_price = 0.00001235;
+        
+        let one_line = manager.format_results(&result, &order_book).unwrap();
+        assert!(one_line.starts_with("SHIBUSDT fair=$0.00001235 mid=$0.00001235 bid=$0.00001234 ask=$0.00001236"));
+        
+        // The default precision would flatten every price to zero
+        let boxed = WebSocketManager::new(
+            Config::builder().display_precision(8).build().unwrap(),
+            Arc::new(OrderBookManager::new()),
+            FairPriceCalculator::new(FairPriceMethod::MidPrice),
+        )
+        .format_results(&result, &order_book)
+        .unwrap();
+        assert!(boxed.contains("Best Bid:   $0.00001234"));
+    }
+    
+    #[tokio::test]
+    async fn test_connection_state_transitions() {
+        // Nothing listens on port 1, so every connection attempt fails fast
+        let config = Config::builder()
+            .ws_base_url("ws://127.0.0.1:1")
+            .depth_stream(DepthStreamMode::Partial { levels: 5 })
+            .reconnect_attempts(2)
+            .reconnect_delay_ms(10)
+            .build()
+            .unwrap();
+        let order_book_manager = Arc::new(OrderBookManager::new());
+        let fair_price_calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
+        
+        let ws_manager = WebSocketManager::new(
+            config,
+            order_book_manager,
+            fair_price_calculator,
+        );
+        let mut states = ws_manager.subscribe_state();
+        assert_eq!(ws_manager.connection_state(), ConnectionState::Closed);
+        
+        let result = timeout(Duration::from_secs(5), ws_manager.start()).await.unwrap();
+        assert!(result.is_err());
+        
+        let mut transitions = Vec::new();
+        while let Ok(state) = states.try_recv() {
+            transitions.push(state);
+        }
+        assert_eq!(
+            transitions,
+            vec![
+                ConnectionState::Connecting,
+                ConnectionState::Reconnecting,
+                ConnectionState::Connecting,
+                ConnectionState::Closed,
+            ]
+        );
+        assert_eq!(ws_manager.get_stats().state, ConnectionState::Closed);
+    }
+    
+    #[tokio::test]
+    async fn test_partial_depth_frames_by_market() {
+        let partial_manager = |market| {
+            let config = Config::builder()
+                .market(market)
+                .depth_stream(DepthStreamMode::Partial { levels: 5 })
+                .build()
+                .unwrap();
+            let order_book_manager = Arc::new(OrderBookManager::new());
+            let ws_manager = WebSocketManager::new(
+                config,
+                order_book_manager.clone(),
+                FairPriceCalculator::new(FairPriceMethod::MidPrice),
+            );
+            (ws_manager, order_book_manager)
+        };
+        let top = |order_book_manager: &OrderBookManager| {
+            order_book_manager
+                .with_order_book(|ob| (ob.last_update, ob.best_bid().unwrap().price.0, ob.best_ask().unwrap().price.0))
+        };
+        
+        let (spot, spot_book) = partial_manager(crate::binance::Market::Spot);
+        spot.process_message(
+            r#"{"lastUpdateId":160,"bids":[["0.0024","10"]],"asks":[["0.0026","100"]]}"#,
+        ).await.unwrap();
+        assert_eq!(top(&spot_book), Some((160, 0.0024, 0.0026)));
+        
+        // USD-M futures partial frames look like diffs and have no lastUpdateId
+        let (futures, futures_book) = partial_manager(crate::binance::Market::UsdFutures);
+        futures.process_message(
+            r#"{"e":"depthUpdate","E":1571889248277,"T":1571889248276,"s":"BTCUSDT","U":390497796,"u":390497878,"pu":390497794,"b":[["7403.89","0.002"],["7403.90","3.906"],["7404.00","1.428"],["7404.85","5.239"],["7405.43","2.562"]],"a":[["7405.96","3.340"],["7406.63","4.525"],["7407.08","2.475"],["7407.15","4.800"],["7407.20","0.175"]]}"#,
+        ).await.unwrap();
+        assert_eq!(top(&futures_book), Some((390497878, 7405.43, 7405.96)));
+        assert_eq!(futures_book.with_order_book(|ob| ob.bids.len()), Some(5));
+        assert!(futures_book.is_ready());
+    }
+    
+    #[tokio::test]
+    async fn test_other_symbols_are_ignored() {
+        let ws_manager = WebSocketManager::new(
+            Config::new("BTCUSDT".to_string(), "mid-price".to_string()),
+            Arc::new(OrderBookManager::new()),
+            FairPriceCalculator::new(FairPriceMethod::MidPrice),
+        );
+        
+        // Trades for another symbol don't count towards this symbol's flow
+        ws_manager
+            .process_message(r#"{"e":"aggTrade","s":"ETHUSDT","a":1,"p":"3000.0","q":"5.0","m":false,"T":1000}"#)
+            .await
+            .unwrap();
+        ws_manager
+            .process_message(r#"{"e":"aggTrade","s":"BTCUSDT","a":2,"p":"50000.0","q":"1.0","m":false,"T":1000}"#)
+            .await
+            .unwrap();
+        assert_eq!(ws_manager.trade_flow_signed_volume(), 1.0);
+    }
+    
+    #[tokio::test]
+    async fn test_combined_frames_route_to_feeds() {
+        let (ws_manager, btc_book) = TestManager::default().configure(|config| config.websocket.agg_trades = true).build();
+        let eth_book = Arc::new(OrderBookManager::new());
+        let eth_snapshot = OrderBookSnapshot {
+            last_update_id: 1,
+            bids: vec![["3000.0".to_string(), "2.0".to_string()]],
+            asks: vec![["3001.0".to_string(), "2.0".to_string()]],
+        };
+        eth_book.initialize_from_snapshot("ETHUSDT", eth_snapshot).unwrap();
+        let ws_manager = ws_manager.with_symbol(
+            Config::new("ETHUSDT".to_string(), "mid-price".to_string()),
+            eth_book.clone(),
+            FairPriceCalculator::new(FairPriceMethod::MidPrice),
+        );
+        assert_eq!(ws_manager.symbols(), vec!["BTCUSDT", "ETHUSDT"]);
+        assert_eq!(
+            ws_manager.combined_stream_url().unwrap(),
+            "wss://stream.binance.com:9443/stream?streams=btcusdt@depth@100ms/btcusdt@aggTrade/ethusdt@depth@100ms/ethusdt@aggTrade"
+        );
+        
+        // Each payload lands on its own symbol's book and stats
+        let eth = ws_manager.feed("ethusdt").unwrap();
+        let mut eth_results = eth.subscribe_results();
+        ws_manager
+            .process_frame(r#"{"stream":"ethusdt@depth@100ms","data":{"e":"depthUpdate","E":0,"s":"ETHUSDT","U":2,"u":2,"b":[["3000.5","1.0"]],"a":[]}}"#)
+            .await
+            .unwrap();
+        ws_manager
+            .process_frame(r#"{"stream":"ethusdt@aggTrade","data":{"e":"aggTrade","s":"ETHUSDT","a":1,"p":"3000.5","q":"4.0","m":false,"T":1000}}"#)
+            .await
+            .unwrap();
+        assert_eq!(eth_book.with_order_book(|ob| ob.mid_price()).flatten(), Some(3000.75));
+        assert_eq!(btc_book.with_order_book(|ob| ob.mid_price()).flatten(), Some(50000.5));
+        assert!(eth_results.try_recv().is_ok());
+        assert_eq!(eth.get_stats().messages_processed, 1);
+        assert_eq!(eth.trade_flow_signed_volume(), 4.0);
+        assert_eq!(ws_manager.get_stats().messages_processed, 0);
+        assert_eq!(ws_manager.trade_flow_signed_volume(), 0.0);
+        
+        // Untracked symbols are dropped; the manager's own symbol stays on it
+        ws_manager
+            .process_frame(r#"{"stream":"bnbusdt@depth@100ms","data":{"e":"depthUpdate","E":0,"s":"BNBUSDT","U":2,"u":2,"b":[["600.0","1.0"]],"a":[]}}"#)
+            .await
+            .unwrap();
+        ws_manager
+            .process_frame(r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":0,"s":"BTCUSDT","U":2,"u":2,"b":[["50000.5","1.0"]],"a":[]}}"#)
+            .await
+            .unwrap();
+        assert_eq!(ws_manager.get_stats().messages_processed, 1);
+        assert_eq!(eth.get_stats().messages_processed, 1);
+        
+        // Feeds follow the shared connection's state
+        ws_manager.set_state(ConnectionState::Live);
+        assert_eq!(eth.connection_state(), ConnectionState::Live);
+    }
+    
+    #[test]
+    fn test_subscription_frame_for_symbols() {
+        let (ws_manager, _) = TestManager::default().build();
+        let symbols = ["BTCUSDT".to_string(), "ethusdt".to_string()];
+        let frame = |message: Option<Message>| match message {
+            Some(Message::Text(text)) => text,
+            other => panic!("expected a text frame, got {:?}", other),
+        };
+        
+        assert_eq!(
+            frame(ws_manager.subscription_message(true, &symbols)),
+            r#"{"method":"SUBSCRIBE","params":["btcusdt@depth@100ms","ethusdt@depth@100ms"],"id":1}"#
+        );
+        assert_eq!(
+            frame(ws_manager.subscription_message(false, &symbols[1..])),
+            r#"{"method":"UNSUBSCRIBE","params":["ethusdt@depth@100ms"],"id":2}"#
+        );
+        
+        // Trades ride along with each symbol's depth
+        let (ws_manager, _) = TestManager::default().configure(|config| config.websocket.agg_trades = true).build();
+        assert_eq!(
+            frame(ws_manager.subscription_message(true, &symbols)),
+            r#"{"method":"SUBSCRIBE","params":["btcusdt@depth@100ms","btcusdt@aggTrade","ethusdt@depth@100ms","ethusdt@aggTrade"],"id":1}"#
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_commands_add_and_drop_feeds() {
+        let (ws_manager, _) = TestManager::default()
+            .configure(|config| config.websocket.depth_stream = DepthStreamMode::Partial { levels: 5 })
+            .build();
+        let mut sent: Vec<Message> = Vec::new();
+        
+        ws_manager.apply_command(Command::Subscribe("ethusdt".to_string()), &mut sent).await.unwrap();
+        assert_eq!(
+            sent,
+            vec![Message::Text(r#"{"method":"SUBSCRIBE","params":["ethusdt@depth5@100ms"],"id":1}"#.to_string())]
+        );
+        assert_eq!(ws_manager.symbols(), vec!["BTCUSDT", "ETHUSDT"]);
+        
+        // The new symbol's frames build its own book
+        ws_manager
+            .process_frame(r#"{"stream":"ethusdt@depth5@100ms","data":{"lastUpdateId":7,"bids":[["3000.0","1"]],"asks":[["3001.0","1"]]}}"#)
+            .await
+            .unwrap();
+        let eth = ws_manager.feed("ETHUSDT").unwrap();
+        assert_eq!(eth.get_stats().current_mid_price, Some(3000.5));
+        
+        // Symbols already streaming, or not streaming, send nothing
+        ws_manager.apply_command(Command::Subscribe("ETHUSDT".to_string()), &mut sent).await.unwrap();
+        ws_manager.apply_command(Command::Subscribe("btcusdt".to_string()), &mut sent).await.unwrap();
+        ws_manager.apply_command(Command::Unsubscribe("BNBUSDT".to_string()), &mut sent).await.unwrap();
+        ws_manager.apply_command(Command::Unsubscribe("BTCUSDT".to_string()), &mut sent).await.unwrap();
+        assert_eq!(sent.len(), 1);
+        
+        ws_manager.apply_command(Command::Unsubscribe("ETHUSDT".to_string()), &mut sent).await.unwrap();
+        assert_eq!(
+            sent[1],
+            Message::Text(r#"{"method":"UNSUBSCRIBE","params":["ethusdt@depth5@100ms"],"id":2}"#.to_string())
+        );
+        assert_eq!(ws_manager.symbols(), vec!["BTCUSDT"]);
+        assert!(!eth.get_stats().is_order_book_ready);
+        assert_eq!(eth.connection_state(), ConnectionState::Closed);
+    }
+    
+    #[tokio::test]
+    async fn test_shutdown_before_start() {
         let config = Config::new("BTCUSDT".to_string(), "mid-price".to_string());
         let order_book_manager = Arc::new(OrderBookManager::new());
-        let fair_price_calculator = Arc::new(FairPriceCalculator::new(FairPriceMethod::MidPrice));
+        let fair_price_calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
         
         let ws_manager = WebSocketManager::new(
             config,
@@ -336,16 +2244,513 @@ mod tests {
             fair_price_calculator,
         );
         
+        let shutdown = Arc::new(Notify::new());
+        shutdown.notify_one();
+        
+        let result = timeout(Duration::from_secs(5), ws_manager.start_with_shutdown(shutdown)).await;
+        assert!(matches!(result, Ok(Ok(()))));
+    }
+    
+    #[tokio::test]
+    async fn test_empty_update_skips_recompute() {
+        let (ws_manager, order_book_manager) = TestManager::default().build();
+        let mut results = ws_manager.subscribe_results();
+        
+        ws_manager
+            .process_message(r#"{"e":"depthUpdate","E":0,"s":"BTCUSDT","U":2,"u":2,"b":[],"a":[]}"#)
+            .await
+            .unwrap();
+        ws_manager
+            .process_message(r#"{"e":"trade","E":0,"s":"BTCUSDT","t":1,"p":"50000.5","q":"1.0"}"#)
+            .await
+            .unwrap();
+        assert!(results.try_recv().is_err());
+        assert_eq!(ws_manager.get_stats().messages_processed, 0);
+        assert_eq!(order_book_manager.recent_flow(), 0.0);
+        
+        // The keepalive still advanced the sequence, so the next update is not a gap
+        ws_manager
+            .process_message(r#"{"e":"depthUpdate","E":0,"s":"BTCUSDT","U":3,"u":3,"b":[["50000.5","1.0"]],"a":[]}"#)
+            .await
+            .unwrap();
+        assert!(results.try_recv().is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_lowercase_symbol_applies_updates() {
+        let (ws_manager, order_book_manager) = TestManager::default().symbol("btcusdt").build();
+        
+        ws_manager
+            .process_message(r#"{"e":"depthUpdate","E":0,"s":"BTCUSDT","U":2,"u":2,"b":[["50000.5","1.0"]],"a":[]}"#)
+            .await
+            .unwrap();
+        assert_eq!(order_book_manager.with_order_book(|book| book.last_update), Some(2));
+        
+        // Another symbol's update is refused by the book itself
+        let other = ws_manager
+            .process_message(r#"{"e":"depthUpdate","E":0,"s":"ETHUSDT","U":3,"u":3,"b":[["3000.0","1.0"]],"a":[]}"#)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            other.downcast_ref::<OrderBookError>(),
+            Some(OrderBookError::SymbolMismatch { .. })
+        ));
+    }
+    
+    #[tokio::test]
+    async fn test_feed_lag_average() {
+        let (ws_manager, _) = TestManager::default().book(None).build();
+        assert_eq!(ws_manager.get_stats().avg_lag_ms, None);
+        
+        // The first sample seeds the average, later ones are smoothed in
+        assert_eq!(ws_manager.record_lag(1_000, 1_050), 50.0);
+        assert!((ws_manager.record_lag(2_000, 2_150) - 60.0).abs() < 1e-9);
+        
+        // Event time ahead of the local clock counts as zero lag
+        assert!((ws_manager.record_lag(3_100, 3_000) - 54.0).abs() < 1e-9);
+        assert!((ws_manager.get_stats().avg_lag_ms.unwrap() - 54.0).abs() < 1e-9);
+    }
+    
+    #[tokio::test]
+    async fn test_update_counter() {
+        let (ws_manager, _) = TestManager::default().build();
+        
+        let depth_update = |symbol: &str, id: u64| {
+            format!(
+                r#"{{"e":"depthUpdate","E":0,"s":"{}","U":{},"u":{},"b":[["50000.0","{}.0"]],"a":[]}}"#,
+                symbol, id, id, id
+            )
+        };
+        for id in 2..7 {
+            ws_manager.process_message(&depth_update("BTCUSDT", id)).await.unwrap();
+        }
+        
+        // Updates for another symbol are refused and not counted
+        assert!(ws_manager.process_message(&depth_update("ETHUSDT", 7)).await.is_err());
+        
         let stats = ws_manager.get_stats();
-        assert_eq!(stats.symbol, "BTCUSDT");
-        assert!(!stats.is_order_book_ready);
+        assert_eq!(stats.messages_processed, 5);
+        assert!((stats.updates_per_sec - 0.5).abs() < 1e-9);
+        
+        // The rate decays once updates fall out of the window
+        let last = *ws_manager.update_times.lock().unwrap().back().unwrap();
+        assert_eq!(ws_manager.updates_per_sec(last + UPDATE_RATE_WINDOW_MS), 0.0);
+    }
+    
+    /// Connector for a venue with its own message format, fed canned data
+    ///
+    /// Serves queued snapshots in order, repeating the last one, and can
+    /// record whether a book was ready at each fetch.
+    #[derive(Default)]
+    struct MockConnector {
+        snapshots: Mutex<VecDeque<OrderBookSnapshot>>,
+        probed_book: Option<Arc<OrderBookManager>>,
+        ready_at_fetch: Mutex<Vec<bool>>,
+    }
+    
+    impl MockConnector {
+        /// Serve `snapshots` in order, then keep serving the last one
+        fn with_snapshots(snapshots: Vec<OrderBookSnapshot>) -> Self {
+            Self {
+                snapshots: Mutex::new(snapshots.into()),
+                ..Self::default()
+            }
+        }
+        
+        /// Record `order_book_manager.is_ready()` each time a snapshot is fetched
+        fn probing(order_book_manager: Arc<OrderBookManager>) -> Self {
+            Self {
+                probed_book: Some(order_book_manager),
+                ..Self::default()
+            }
+        }
+    }
+    
+    impl ExchangeConnector for MockConnector {
+        fn snapshot_url(&self, symbol: &str, limit: u32) -> String {
+            format!("https://mock.exchange/book/{}?depth={}", symbol, limit)
+        }
+        
+        fn diff_stream_url(&self, symbol: &str) -> String {
+            format!("wss://mock.exchange/l2/{}", symbol)
+        }
+        
+        fn parse_depth_message(&self, message: &str) -> Result<OrderBookUpdate> {
+            let json: Value = serde_json::from_str(message)?;
+            let sequence = json["sequence"].as_u64().ok_or_else(|| anyhow!("Missing sequence"))?;
+            let mut update = OrderBookUpdate {
+                event_time: 0,
+                symbol: json["product"].as_str().unwrap_or_default().to_string(),
+                first_update_id: sequence,
+                final_update_id: sequence,
+                prev_final_update_id: None,
+                bids: Vec::new(),
+                asks: Vec::new(),
+            };
+            for change in json["changes"].as_array().into_iter().flatten() {
+                let level = [
+                    change[1].as_str().unwrap_or_default().to_string(),
+                    change[2].as_str().unwrap_or_default().to_string(),
+                ];
+                match change[0].as_str() {
+                    Some("buy") => update.bids.push(level),
+                    Some("sell") => update.asks.push(level),
+                    _ => return Err(anyhow!("Unknown side in {}", change)),
+                }
+            }
+            Ok(update)
+        }
+        
+        async fn fetch_snapshot(&self, _symbol: &str, _limit: u32) -> Result<OrderBookSnapshot> {
+            if let Some(order_book_manager) = &self.probed_book {
+                self.ready_at_fetch.lock().unwrap().push(order_book_manager.is_ready());
+            }
+            
+            let mut snapshots = self.snapshots.lock().unwrap();
+            let snapshot = match snapshots.len() {
+                0 => OrderBookSnapshot {
+                    last_update_id: 1,
+                    bids: vec![["50000.0".to_string(), "1.0".to_string()]],
+                    asks: vec![["50001.0".to_string(), "1.0".to_string()]],
+                },
+                1 => snapshots[0].clone(),
+                _ => snapshots.pop_front().unwrap(),
+            };
+            Ok(snapshot)
+        }
+        
+        async fn ping(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_resync_unreadies_book_during_fetch() {
+        let order_book_manager = Arc::new(OrderBookManager::new());
+        let ws_manager = WebSocketManager::with_connector(
+            Config::new("BTCUSDT".to_string(), "mid-price".to_string()),
+            order_book_manager.clone(),
+            FairPriceCalculator::new(FairPriceMethod::MidPrice),
+            MockConnector::probing(order_book_manager.clone()),
+        );
+        
+        ws_manager.initialize_order_book().await.unwrap();
+        assert!(order_book_manager.is_ready());
+        
+        // Ready → cleared while the snapshot is fetched → ready again
+        ws_manager.resync_order_book().await.unwrap();
+        assert!(order_book_manager.is_ready());
+        assert_eq!(*ws_manager.connector.ready_at_fetch.lock().unwrap(), vec![false, false]);
+    }
+    
+    /// Manager over a book at update 100 (50000/50001) whose connector serves `snapshot`
+    fn manager_with_snapshot(snapshot: OrderBookSnapshot) -> (WebSocketManager<MockConnector>, Arc<OrderBookManager>) {
+        TestManager::default()
+            .book(Some(snapshot_at(100)))
+            .build_with(MockConnector::with_snapshots(vec![snapshot]))
+    }
+    
+    #[tokio::test]
+    async fn test_verify_skips_lagging_snapshot() {
+        // Three updates behind the book, before its bid grew to 4.0
+        let (ws_manager, order_book_manager) = manager_with_snapshot(OrderBookSnapshot {
+            last_update_id: 97,
+            bids: vec![["50000.0".to_string(), "1.0".to_string()]],
+            asks: vec![["50001.0".to_string(), "1.0".to_string()]],
+        });
+        order_book_manager
+            .apply_update(OrderBookUpdate {
+                event_time: 0,
+                symbol: "BTCUSDT".to_string(),
+                first_update_id: 101,
+                final_update_id: 103,
+                prev_final_update_id: None,
+                bids: vec![["50000.0".to_string(), "4.0".to_string()]],
+                asks: vec![],
+            })
+            .unwrap();
+        
+        ws_manager.verify_order_book().await.unwrap();
+        let (last_update, bid) = order_book_manager
+            .with_order_book(|book| (book.last_update, book.best_bid().unwrap().quantity))
+            .unwrap();
+        assert_eq!((last_update, bid), (103, 4.0));
+    }
+    
+    #[tokio::test]
+    async fn test_refresh_discards_lagging_snapshot() {
+        let (ws_manager, order_book_manager) = manager_with_snapshot(OrderBookSnapshot {
+            last_update_id: 97,
+            bids: vec![["49990.0".to_string(), "1.0".to_string()]],
+            asks: vec![["50001.0".to_string(), "1.0".to_string()]],
+        });
+        
+        // The live book at update 100 keeps serving instead of rewinding
+        ws_manager.refresh_order_book().await.unwrap();
+        let (last_update, best_bid) = order_book_manager
+            .with_order_book(|book| (book.last_update, book.best_bid().unwrap().price.0))
+            .unwrap();
+        assert_eq!((last_update, best_bid), (100, 50000.0));
+        
+        // So the next diff still continues the sequence
+        order_book_manager
+            .apply_update(OrderBookUpdate {
+                event_time: 0,
+                symbol: "BTCUSDT".to_string(),
+                first_update_id: 101,
+                final_update_id: 101,
+                prev_final_update_id: None,
+                bids: vec![["50000.5".to_string(), "1.0".to_string()]],
+                asks: vec![],
+            })
+            .unwrap();
+        assert!(order_book_manager.is_ready());
+    }
+    
+    #[tokio::test]
+    async fn test_snapshot_resets_calculator_history() {
+        let (ws_manager, _) = manager_with_snapshot(OrderBookSnapshot {
+            last_update_id: 200,
+            bids: vec![["50005.0".to_string(), "1.0".to_string()]],
+            asks: vec![["50006.0".to_string(), "1.0".to_string()]],
+        });
+        ws_manager.calculate_and_display_fair_price().await.unwrap().unwrap();
+        assert_eq!(ws_manager.with_calculator(|calculator| calculator.history_len()), 1);
+        
+        // Prices from before the new snapshot don't carry over
+        ws_manager.refresh_order_book().await.unwrap();
+        assert_eq!(ws_manager.with_calculator(|calculator| calculator.history_len()), 0);
+        
+        tokio::time::sleep(Duration::from_millis(2)).await; // Past the update threshold
+        ws_manager.calculate_and_display_fair_price().await.unwrap().unwrap();
+        ws_manager.resync_order_book().await.unwrap();
+        assert_eq!(ws_manager.with_calculator(|calculator| calculator.history_len()), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_snapshot_refresh_replaces_book() {
+        let (ws_manager, order_book_manager) = TestManager::default()
+            .book(None)
+            .configure(|config| config.order_book.snapshot_refresh_interval_ms = Some(60_000))
+            .build_with(MockConnector::with_snapshots(vec![
+                OrderBookSnapshot {
+                    last_update_id: 100,
+                    bids: vec![["50001.0".to_string(), "1.0".to_string()]],
+                    asks: vec![["50010.0".to_string(), "1.0".to_string()]],
+                },
+                OrderBookSnapshot {
+                    last_update_id: 200,
+                    bids: vec![["50002.0".to_string(), "1.0".to_string()]],
+                    asks: vec![["50010.0".to_string(), "1.0".to_string()]],
+                },
+            ]));
+        
+        ws_manager.initialize_order_book().await.unwrap();
+        order_book_manager
+            .apply_update(OrderBookUpdate {
+                event_time: 0,
+                symbol: "BTCUSDT".to_string(),
+                first_update_id: 101,
+                final_update_id: 101,
+                prev_final_update_id: None,
+                bids: vec![["49000.0".to_string(), "5.0".to_string()]],
+                asks: vec![],
+            })
+            .unwrap();
+        
+        ws_manager.refresh_order_book().await.unwrap();
+        let (last_update, bids) = order_book_manager
+            .with_order_book(|book| (book.last_update, book.bids.len()))
+            .unwrap();
+        assert_eq!((last_update, bids), (200, 1));
+        assert_eq!(order_book_manager.with_order_book(|book| book.best_bid().unwrap().price.0), Some(50002.0));
+        
+        assert!(Config::builder().snapshot_refresh_interval_ms(0).build().is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_mock_exchange_connector() {
+        let (ws_manager, order_book_manager) = TestManager::default().book(None).build_with(MockConnector::default());
+        
+        ws_manager.initialize_order_book().await.unwrap();
+        assert!(order_book_manager.is_ready());
+        
+        let messages = [
+            r#"{"type":"l2update","product":"BTCUSDT","sequence":2,"changes":[["buy","50000.5","2.0"]]}"#,
+            r#"{"type":"l2update","product":"BTCUSDT","sequence":3,"changes":[["sell","50001.0","0.0"],["sell","50002.0","3.0"]]}"#,
+        ];
+        for message in messages {
+            ws_manager.process_message(message).await.unwrap();
+        }
+        assert!(ws_manager.process_message(r#"{"type":"l2update","product":"BTCUSDT"}"#).await.is_err());
+        
+        let best = order_book_manager
+            .with_order_book(|ob| (ob.best_bid().map(|b| b.price.0), ob.best_ask().map(|a| a.price.0)));
+        assert_eq!(best, Some((Some(50000.5), Some(50002.0))));
+        assert_eq!(ws_manager.get_stats().messages_processed, 2);
+        assert!(ws_manager.health_status().await.unwrap().is_healthy());
+    }
+    
+    #[test]
+    fn test_keepalive_message() {
+        let config = Config::builder()
+            .ping_payload("hb")
+            .keepalive(KeepaliveMode::ListSubscriptions)
+            .build()
+            .unwrap();
+        let binance = WebSocketManager::new(
+            config.clone(),
+            Arc::new(OrderBookManager::new()),
+            FairPriceCalculator::new(FairPriceMethod::MidPrice),
+        );
+        assert_eq!(
+            binance.keepalive_message(),
+            Message::Text(r#"{"method":"LIST_SUBSCRIPTIONS","id":1}"#.to_string())
+        );
+        
+        // Connectors without an application-level keepalive fall back to the ping
+        let mock = WebSocketManager::with_connector(
+            config,
+            Arc::new(OrderBookManager::new()),
+            FairPriceCalculator::new(FairPriceMethod::MidPrice),
+            MockConnector::default(),
+        );
+        assert_eq!(mock.keepalive_message(), Message::Ping(b"hb".to_vec()));
+    }
+    
+    #[tokio::test]
+    async fn test_recalc_schedule() {
+        let last_recalc = Instant::now();
+        assert_eq!(next_deadline(last_recalc, None), None);
+        assert_eq!(
+            next_deadline(last_recalc, Some(Duration::from_millis(500))),
+            Some(last_recalc + Duration::from_millis(500))
+        );
+        
+        // A due deadline completes, a disabled timer never does
+        let due = next_deadline(Instant::now(), Some(Duration::from_millis(10)));
+        assert!(timeout(Duration::from_secs(1), sleep_until_or_pending(due)).await.is_ok());
+        assert!(timeout(Duration::from_millis(50), sleep_until_or_pending(None)).await.is_err());
+    }
+    
+    #[test]
+    fn test_inbound_buffer_coalesces_partial_depth() {
+        let text = |body: &str| Some(Ok(Message::Text(body.to_string())));
+        let depth = |id: u64| text(&format!(r#"{{"lastUpdateId":{},"bids":[],"asks":[]}}"#, id));
+        let trade = || text(r#"{"e":"aggTrade","s":"BTCUSDT"}"#);
+        let ack = text(r#"{"result":null,"id":1}"#);
+        let mut buffer = InboundBuffer::new(4, DepthStreamMode::Partial { levels: 5 });
+        
+        assert_eq!(buffer.push(depth(1)), 0);
+        assert_eq!(buffer.push(trade()), 0);
+        assert_eq!(buffer.push(ack), 0);
+        assert_eq!(buffer.push(depth(2)), 0);
+        
+        // Full: the oldest book goes, the trade and ack stay in order
+        assert_eq!(buffer.push(depth(3)), 1);
+        assert_eq!((buffer.len(), buffer.dropped()), (4, 1));
+        assert!(matches!(buffer.pop(), Some((Some(Ok(Message::Text(body))), false)) if body.contains("aggTrade")));
+        assert!(matches!(buffer.pop(), Some((Some(Ok(Message::Text(body))), false)) if body.contains("result")));
+        assert!(matches!(buffer.pop(), Some((Some(Ok(Message::Text(body))), false)) if body.contains(":2,")));
+        
+        // With no depth queued the oldest other frame goes, never the end marker
+        let mut trades = InboundBuffer::new(2, DepthStreamMode::Partial { levels: 5 });
+        trades.push(trade());
+        trades.push(None);
+        assert_eq!(trades.push(Some(Ok(Message::Ping(vec![])))), 1);
+        assert!(matches!(trades.pop(), Some((None, false))));
+        assert_eq!(trades.len(), 1);
+        
+        // Nothing droppable queued: the new frame is refused, keeping the bound
+        let mut ended = InboundBuffer::new(1, DepthStreamMode::Partial { levels: 5 });
+        ended.push(None);
+        assert_eq!(ended.push(trade()), 1);
+        assert_eq!((ended.len(), ended.dropped()), (1, 1));
+        
+        buffer.clear();
+        assert_eq!((buffer.len(), buffer.dropped()), (0, 1));
+    }
+    
+    #[test]
+    fn test_inbound_buffer_flushes_diffs_once() {
+        let text = |body: &str| Some(Ok(Message::Text(body.to_string())));
+        let diff = |id: u64| text(&format!(r#"{{"e":"depthUpdate","s":"BTCUSDT","U":{},"u":{},"b":[],"a":[]}}"#, id, id));
+        let mut buffer = InboundBuffer::new(3, DepthStreamMode::Diff);
+        
+        buffer.push(diff(1));
+        buffer.push(text(r#"{"e":"aggTrade","s":"BTCUSDT"}"#));
+        buffer.push(diff(2));
+        
+        // Overflow flushes every queued diff and asks for one resync
+        assert_eq!(buffer.push(diff(3)), 2);
+        assert_eq!(buffer.push(diff(4)), 0);
+        assert_eq!((buffer.len(), buffer.dropped()), (3, 2));
+        
+        assert!(matches!(buffer.pop(), Some((Some(Ok(Message::Text(body))), true)) if body.contains("aggTrade")));
+        assert!(matches!(buffer.pop(), Some((Some(Ok(Message::Text(body))), false)) if body.contains(r#""u":3"#)));
+        assert!(matches!(buffer.pop(), Some((Some(Ok(Message::Text(body))), false)) if body.contains(r#""u":4"#)));
+        assert!(buffer.pop().is_none());
+        
+        // Combined-stream frames are judged by their payload
+        assert!(is_depth_frame(r#"{"stream":"ethusdt@depth@100ms","data":{"e":"depthUpdate","U":1,"u":1}}"#));
+        assert!(!is_depth_frame(r#"{"stream":"ethusdt@aggTrade","data":{"e":"aggTrade","s":"ETHUSDT"}}"#));
+    }
+    
+    #[test]
+    fn test_stable_connection_resets_attempts() {
+        let stable_after = Duration::from_secs(60);
+        
+        // Failures before going live, and brief connections, keep counting up
+        let mut attempts = attempts_after_drop(0, None, stable_after);
+        attempts = attempts_after_drop(attempts, Some(Duration::from_secs(5)), stable_after);
+        assert_eq!(attempts, 2);
+        
+        // Staying live past the threshold starts the count over at this failure
+        attempts = attempts_after_drop(attempts, Some(Duration::from_secs(60)), stable_after);
+        assert_eq!(attempts, 1);
+        attempts = attempts_after_drop(attempts, None, stable_after);
+        assert_eq!(attempts, 2);
+    }
+    
+    #[test]
+    fn test_clean_close_decision() {
+        assert!(reconnects_after(ConnectionEnd::ClosedByServer, true));
+        assert!(!reconnects_after(ConnectionEnd::ClosedByServer, false));
+        
+        // A requested shutdown never reconnects
+        assert!(!reconnects_after(ConnectionEnd::Shutdown, true));
+        assert!(!reconnects_after(ConnectionEnd::Shutdown, false));
+        
+        // Back-to-back clean closes give up at the attempt limit unless one was stable
+        let stable_after = Duration::from_secs(60);
+        let brief = Some(Duration::from_millis(50));
+        assert_eq!(clean_closes_after(0, brief, stable_after, 3), Some(1));
+        assert_eq!(clean_closes_after(1, brief, stable_after, 3), Some(2));
+        assert_eq!(clean_closes_after(2, brief, stable_after, 3), None);
+        assert_eq!(clean_closes_after(2, Some(stable_after), stable_after, 3), Some(1));
+        
+        assert!(Config::builder().build().unwrap().websocket.reconnect_on_clean_close);
+        assert!(!Config::builder().reconnect_on_clean_close(false).build().unwrap().websocket.reconnect_on_clean_close);
+    }
+    
+    #[test]
+    fn test_read_timeout_decision() {
+        let last_traffic = Instant::now();
+        let read_timeout = Duration::from_millis(60_000);
+        
+        assert!(!read_timed_out(last_traffic, last_traffic, read_timeout));
+        assert!(!read_timed_out(last_traffic, last_traffic + Duration::from_millis(59_999), read_timeout));
+        assert!(read_timed_out(last_traffic, last_traffic + read_timeout, read_timeout));
+        
+        // A clock reading before the last frame is never a timeout
+        assert!(!read_timed_out(last_traffic + read_timeout, last_traffic, read_timeout));
     }
     
     #[tokio::test]
     async fn test_health_check() {
         let config = Config::new("BTCUSDT".to_string(), "mid-price".to_string());
         let order_book_manager = Arc::new(OrderBookManager::new());
-        let fair_price_calculator = Arc::new(FairPriceCalculator::new(FairPriceMethod::MidPrice));
+        let fair_price_calculator = FairPriceCalculator::new(FairPriceMethod::MidPrice);
         
         let ws_manager = WebSocketManager::new(
             config,
@@ -357,4 +2762,42 @@ mod tests {
         // but should compile and structure correctly
         let _health_result = ws_manager.health_check().await;
     }
+    
+    #[test]
+    fn test_health_status_combinations() {
+        let (ws_manager, order_book_manager) = TestManager::default()
+            .book(None)
+            .configure(|config| config.websocket.max_feed_staleness_ms = 5_000)
+            .build();
+        
+        // No book yet: never fresh, even with a reachable API
+        let status = ws_manager.health_status_at(true, 1_000);
+        assert!(!status.book_ready);
+        assert_eq!(status.feed_age_ms, None);
+        assert!(!status.is_healthy());
+        
+        order_book_manager.initialize_from_snapshot("BTCUSDT", snapshot_at(1)).unwrap();
+        ws_manager.record_book_progress(10_000);
+        
+        // Fully healthy
+        let status = ws_manager.health_status_at(true, 12_000);
+        assert_eq!(status.feed_age_ms, Some(2_000));
+        assert!(status.is_healthy());
+        
+        // Feed fine but API unreachable
+        let status = ws_manager.health_status_at(false, 12_000);
+        assert!(status.is_feed_healthy());
+        assert!(!status.is_healthy());
+        
+        // API reachable but feed stale; an unchanged update ID does not count as progress
+        ws_manager.record_book_progress(14_000);
+        let status = ws_manager.health_status_at(true, 16_000);
+        assert!(status.api_reachable);
+        assert!(status.book_ready);
+        assert!(!status.feed_fresh);
+        assert_eq!(status.to_string(), "API reachable, feed stale (last update 6000ms ago)");
+        
+        // Neither
+        assert!(!ws_manager.health_status_at(false, 16_000).is_healthy());
+    }
 }
\ No newline at end of file